@@ -0,0 +1,40 @@
+//! Localnet stand-in for the Pyth oracle. Price accounts it owns use the Pyth v2 layout,
+//! so `omniflow_rwa` built with the `mock-price-feed` feature reads them like real feeds.
+
+use anchor_lang::prelude::*;
+use omniflow_rwa::price_feed::{mock_price_account_data, PYTH_PRICE_ACCOUNT_LEN};
+
+declare_id!("G2FDWn5jwDwszophCnsjkecjkMJ8mvXTof3BhNhu1DaD");
+
+#[program]
+pub mod mock_pyth {
+    use super::*;
+
+    /// Write the aggregate price of a price account owned by this program (price account keypair)
+    pub fn set_price(
+        ctx: Context<SetPrice>,
+        price: i64,
+        conf: u64,
+        expo: i32,
+        publish_time: i64,
+    ) -> Result<()> {
+        let data = mock_price_account_data(price, conf, expo, publish_time);
+        ctx.accounts.price_account.try_borrow_mut_data()?[..PYTH_PRICE_ACCOUNT_LEN]
+            .copy_from_slice(&data);
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SetPrice<'info> {
+    /// CHECK: Raw Pyth v2 layout. Created by the caller with this program as owner and
+    /// at least `PYTH_PRICE_ACCOUNT_LEN` bytes.
+    #[account(
+        mut,
+        signer,
+        owner = crate::ID,
+        constraint = price_account.data_len() >= PYTH_PRICE_ACCOUNT_LEN
+    )]
+    pub price_account: UncheckedAccount<'info>,
+}
@@ -0,0 +1,358 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+use crate::identity::IdentityPassport;
+use crate::merkle;
+use crate::reserve::{require_reserves, ReserveAttestation};
+use crate::valuation::{require_current_valuation, ValuationHistory};
+use crate::{ErrorCode, RWAAsset, RWATokensMinted, Registry, PAUSE_MINTING};
+
+/// Largest number of allocations in one round, bounded by the claim bitmap size
+pub const MAX_ALLOCATION_LEAVES: u32 = 8192;
+
+/// Primary offering allocations committed as a Merkle root, stored at
+/// `[b"allocation", asset_id, round_id]`. Leaves are `merkle::allocation_leaf`.
+#[account]
+#[derive(InitSpace)]
+pub struct AllocationRound {
+    pub asset_id: u64,
+    pub round_id: u64,
+    pub allocations_root: [u8; 32],
+    pub leaf_count: u32,
+    pub total_allocated: u64,
+    pub claimed_amount: u64,
+    pub created_at: i64,
+    /// Bit `i` is set once allocation `i` was claimed
+    #[max_len(1024)]
+    pub claimed: Vec<u8>,
+    pub bump: u8,
+}
+
+impl AllocationRound {
+    pub fn is_claimed(&self, index: u32) -> bool {
+        self.claimed[(index / 8) as usize] & (1 << (index % 8)) != 0
+    }
+
+    /// Whether allocations are left to claim
+    pub fn is_open(&self) -> bool {
+        self.claimed_amount < self.total_allocated
+    }
+
+    fn set_claimed(&mut self, index: u32) {
+        self.claimed[(index / 8) as usize] |= 1 << (index % 8);
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct CommitAllocations<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump,
+        has_one = owner
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + AllocationRound::INIT_SPACE,
+        seeds = [
+            b"allocation",
+            &asset_id.to_le_bytes(),
+            &asset.allocation_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub allocation_round: Account<'info, AllocationRound>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAllocation<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &allocation_round.asset_id.to_le_bytes()],
+        bump = asset.bump
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"allocation",
+            &allocation_round.asset_id.to_le_bytes(),
+            &allocation_round.round_id.to_le_bytes()
+        ],
+        bump = allocation_round.bump
+    )]
+    pub allocation_round: Account<'info, AllocationRound>,
+
+    #[account(
+        mut,
+        address = asset.mint @ ErrorCode::MintMismatch
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = recipient,
+        associated_token::mint = mint,
+        associated_token::authority = recipient
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// Required when the asset has a KYC requirement
+    #[account(constraint = recipient_passport.owner == recipient.key() @ ErrorCode::Unauthorized)]
+    pub recipient_passport: Option<Account<'info, IdentityPassport>>,
+
+    /// Required when the asset has a valuation policy
+    #[account(
+        seeds = [b"valuation", &allocation_round.asset_id.to_le_bytes()],
+        bump = valuation.bump
+    )]
+    pub valuation: Option<Account<'info, ValuationHistory>>,
+
+    /// Required when the asset has a custodian
+    #[account(
+        seeds = [b"reserve", &allocation_round.asset_id.to_le_bytes()],
+        bump = reserve.bump
+    )]
+    pub reserve: Option<Account<'info, ReserveAttestation>>,
+
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseAllocationRound<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &allocation_round.asset_id.to_le_bytes()],
+        bump = asset.bump,
+        has_one = owner
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"allocation",
+            &allocation_round.asset_id.to_le_bytes(),
+            &allocation_round.round_id.to_le_bytes()
+        ],
+        bump = allocation_round.bump
+    )]
+    pub allocation_round: Account<'info, AllocationRound>,
+
+    pub owner: Signer<'info>,
+}
+
+// Instructions
+pub fn commit_allocations(
+    ctx: Context<CommitAllocations>,
+    asset_id: u64,
+    allocations_root: [u8; 32],
+    leaf_count: u32,
+    total_allocated: u64,
+) -> Result<()> {
+    require!(
+        leaf_count > 0 && leaf_count <= MAX_ALLOCATION_LEAVES,
+        AllocationError::InvalidLeafCount
+    );
+    require!(total_allocated > 0, AllocationError::InvalidTotal);
+
+    let asset = &mut ctx.accounts.asset;
+    asset.require_operational()?;
+    // Claims re-check supply, but reject rounds that could never be filled
+    require!(
+        asset.circulating_supply.checked_add(total_allocated).unwrap() <= asset.total_supply,
+        ErrorCode::ExceedsMaxSupply
+    );
+
+    let round_id = asset.allocation_count;
+    asset.allocation_count = round_id.checked_add(1).unwrap();
+    asset.open_allocation_rounds = asset.open_allocation_rounds.checked_add(1).unwrap();
+
+    let allocation_round = &mut ctx.accounts.allocation_round;
+    allocation_round.asset_id = asset_id;
+    allocation_round.round_id = round_id;
+    allocation_round.allocations_root = allocations_root;
+    allocation_round.leaf_count = leaf_count;
+    allocation_round.total_allocated = total_allocated;
+    allocation_round.claimed_amount = 0;
+    allocation_round.created_at = Clock::get()?.unix_timestamp;
+    allocation_round.claimed = vec![0; ((leaf_count + 7) / 8) as usize];
+    allocation_round.bump = ctx.bumps.allocation_round;
+
+    emit!(AllocationsCommitted {
+        asset_id,
+        round_id,
+        allocations_root,
+        leaf_count,
+        total_allocated,
+        timestamp: allocation_round.created_at,
+    });
+
+    Ok(())
+}
+
+pub fn claim_allocation(
+    ctx: Context<ClaimAllocation>,
+    index: u32,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    ctx.accounts.registry.require_not_paused(PAUSE_MINTING)?;
+
+    let recipient = ctx.accounts.recipient.key();
+    let allocation_round = &mut ctx.accounts.allocation_round;
+    require!(index < allocation_round.leaf_count, AllocationError::IndexOutOfRange);
+    require!(!allocation_round.is_claimed(index), AllocationError::AlreadyClaimed);
+    require!(
+        merkle::verify_proof(
+            &proof,
+            &allocation_round.allocations_root,
+            merkle::allocation_leaf(index, &recipient, amount)
+        ),
+        AllocationError::InvalidProof
+    );
+
+    let asset = &mut ctx.accounts.asset;
+    asset.require_operational()?;
+    asset.require_kyc(&ctx.accounts.recipient_passport)?;
+    require_current_valuation(asset, &ctx.accounts.valuation)?;
+    asset.record_mint(amount)?;
+    require_reserves(asset, &ctx.accounts.reserve)?;
+
+    allocation_round.set_claimed(index);
+    let was_open = allocation_round.is_open();
+    allocation_round.claimed_amount = allocation_round.claimed_amount.checked_add(amount).unwrap();
+    // Leaves are not checked against the committed total, so bound the claims instead
+    require!(
+        allocation_round.claimed_amount <= allocation_round.total_allocated,
+        AllocationError::ExceedsRoundTotal
+    );
+    if was_open && !allocation_round.is_open() {
+        asset.open_allocation_rounds = asset.open_allocation_rounds.checked_sub(1).unwrap();
+    }
+
+    let cpi_accounts = token::MintTo {
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.token_account.to_account_info(),
+        authority: ctx.accounts.asset.to_account_info(),
+    };
+
+    let asset = &ctx.accounts.asset;
+    let asset_seeds = &[b"asset".as_ref(), &asset.asset_id.to_le_bytes(), &[asset.bump]];
+    let signer_seeds = &[&asset_seeds[..]];
+
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+    token::mint_to(cpi_ctx, amount)?;
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    emit!(AllocationClaimed {
+        asset_id: asset.asset_id,
+        round_id: allocation_round.round_id,
+        index,
+        recipient,
+        amount,
+        timestamp,
+    });
+
+    emit!(RWATokensMinted {
+        asset_id: asset.asset_id,
+        recipient,
+        amount,
+        timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn close_allocation_round(ctx: Context<CloseAllocationRound>) -> Result<()> {
+    let allocation_round = &mut ctx.accounts.allocation_round;
+    require!(allocation_round.is_open(), AllocationError::RoundClosed);
+
+    // Lowering the total to the claimed amount makes every remaining claim exceed it
+    let unclaimed = allocation_round.total_allocated - allocation_round.claimed_amount;
+    allocation_round.total_allocated = allocation_round.claimed_amount;
+
+    let asset = &mut ctx.accounts.asset;
+    asset.open_allocation_rounds = asset.open_allocation_rounds.checked_sub(1).unwrap();
+
+    emit!(AllocationRoundClosed {
+        asset_id: asset.asset_id,
+        round_id: allocation_round.round_id,
+        unclaimed,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Events
+#[event]
+pub struct AllocationsCommitted {
+    pub asset_id: u64,
+    pub round_id: u64,
+    pub allocations_root: [u8; 32],
+    pub leaf_count: u32,
+    pub total_allocated: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AllocationClaimed {
+    pub asset_id: u64,
+    pub round_id: u64,
+    pub index: u32,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AllocationRoundClosed {
+    pub asset_id: u64,
+    pub round_id: u64,
+    pub unclaimed: u64,
+    pub timestamp: i64,
+}
+
+// Errors
+#[error_code(offset = 7100)]
+pub enum AllocationError {
+    #[msg("Allocation round must hold 1-8192 allocations")]
+    InvalidLeafCount,
+    #[msg("Allocation index is out of range")]
+    IndexOutOfRange,
+    #[msg("Allocation was already claimed")]
+    AlreadyClaimed,
+    #[msg("Allocation proof does not match the committed root")]
+    InvalidProof,
+    #[msg("Claims would exceed the round's committed total")]
+    ExceedsRoundTotal,
+    #[msg("Allocation round total must be positive")]
+    InvalidTotal,
+    #[msg("Allocation round has nothing left to claim")]
+    RoundClosed,
+}
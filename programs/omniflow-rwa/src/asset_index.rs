@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+
+use crate::AssetType;
+
+/// Number of entries held by each `AssetIndexPage`
+pub const ASSET_INDEX_PAGE_SIZE: u64 = 32;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum AssetStatus {
+    Active,
+    Inactive,
+    Retired,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct AssetIndexEntry {
+    pub asset_id: u64,
+    pub asset_type: AssetType,
+    pub owner: Pubkey,
+    pub status: AssetStatus,
+}
+
+/// Fixed-size page of the append-only asset index, stored at `[b"asset_index", page]`.
+/// Assets keep their position for life; retirement only updates the entry status.
+#[account]
+#[derive(InitSpace)]
+pub struct AssetIndexPage {
+    pub page: u64,
+    #[max_len(32)]
+    pub entries: Vec<AssetIndexEntry>,
+    pub bump: u8,
+}
+
+impl AssetIndexPage {
+    pub fn page_of(position: u64) -> u64 {
+        position / ASSET_INDEX_PAGE_SIZE
+    }
+
+    pub fn address(page: u64) -> Pubkey {
+        Pubkey::find_program_address(&[b"asset_index", &page.to_le_bytes()], &crate::ID).0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.entries.len() as u64 >= ASSET_INDEX_PAGE_SIZE
+    }
+
+    pub fn entry_mut(&mut self, position: u64) -> Result<&mut AssetIndexEntry> {
+        require!(Self::page_of(position) == self.page, AssetIndexError::WrongIndexPage);
+        self.entries
+            .get_mut((position % ASSET_INDEX_PAGE_SIZE) as usize)
+            .ok_or_else(|| error!(AssetIndexError::WrongIndexPage))
+    }
+}
+
+// Errors
+#[error_code(offset = 6500)]
+pub enum AssetIndexError {
+    #[msg("Index page does not hold this asset")]
+    WrongIndexPage,
+}
@@ -0,0 +1,222 @@
+use anchor_lang::prelude::*;
+
+use crate::{AssetType, RWAAsset, Registry, PAUSE_METADATA_UPDATES};
+
+pub const MAX_ATTRIBUTE_ID_LEN: usize = 64;
+pub const MAX_BAR_SERIALS: usize = 16;
+pub const MAX_BAR_SERIAL_LEN: usize = 32;
+/// Fineness is expressed in parts per 10,000 (9999 = "four nines" gold)
+pub const MAX_FINENESS: u16 = 10_000;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum CarbonRegistry {
+    Verra,
+    GoldStandard,
+    AmericanCarbonRegistry,
+    ClimateActionReserve,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum Metal {
+    Gold,
+    Silver,
+    Platinum,
+    Palladium,
+}
+
+/// Type-specific details of an asset. The variant must match the asset's `AssetType`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub enum AssetAttributeData {
+    RealEstate {
+        #[max_len(64)]
+        parcel_id: String,
+        square_footage: u64,
+        /// Annual rental yield in basis points
+        rental_yield_bps: u16,
+    },
+    CarbonCredits {
+        registry: CarbonRegistry,
+        vintage: u16,
+        #[max_len(64)]
+        project_id: String,
+        tonnes: u64,
+    },
+    PreciousMetals {
+        metal: Metal,
+        fineness: u16,
+        #[max_len(64)]
+        vault: String,
+        #[max_len(16, 32)]
+        bar_serials: Vec<String>,
+    },
+}
+
+impl AssetAttributeData {
+    pub fn asset_type(&self) -> AssetType {
+        match self {
+            AssetAttributeData::RealEstate { .. } => AssetType::RealEstate,
+            AssetAttributeData::CarbonCredits { .. } => AssetType::CarbonCredits,
+            AssetAttributeData::PreciousMetals { .. } => AssetType::PreciousMetals,
+        }
+    }
+
+    pub fn validate(&self, asset_type: AssetType) -> Result<()> {
+        require!(self.asset_type() == asset_type, AttributeError::AttributeTypeMismatch);
+
+        match self {
+            AssetAttributeData::RealEstate {
+                parcel_id,
+                square_footage,
+                rental_yield_bps,
+            } => {
+                require!(is_valid_id(parcel_id), AttributeError::InvalidParcelId);
+                require!(*square_footage > 0, AttributeError::InvalidSquareFootage);
+                require!(*rental_yield_bps <= 10_000, AttributeError::InvalidRentalYield);
+            }
+            AssetAttributeData::CarbonCredits {
+                vintage,
+                project_id,
+                tonnes,
+                ..
+            } => {
+                require!(is_valid_id(project_id), AttributeError::InvalidProjectId);
+                // Kyoto-era credits are the oldest still traded
+                require!((1990..=2100).contains(vintage), AttributeError::InvalidVintage);
+                require!(*tonnes > 0, AttributeError::InvalidTonnes);
+            }
+            AssetAttributeData::PreciousMetals {
+                fineness,
+                vault,
+                bar_serials,
+                ..
+            } => {
+                require!(
+                    *fineness > 0 && *fineness <= MAX_FINENESS,
+                    AttributeError::InvalidFineness
+                );
+                require!(is_valid_id(vault), AttributeError::InvalidVault);
+                require!(bar_serials.len() <= MAX_BAR_SERIALS, AttributeError::InvalidBarSerials);
+                for (i, serial) in bar_serials.iter().enumerate() {
+                    require!(
+                        !serial.is_empty() && serial.len() <= MAX_BAR_SERIAL_LEN,
+                        AttributeError::InvalidBarSerials
+                    );
+                    require!(
+                        !bar_serials[..i].contains(serial),
+                        AttributeError::InvalidBarSerials
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn is_valid_id(id: &str) -> bool {
+    !id.is_empty() && id.len() <= MAX_ATTRIBUTE_ID_LEN
+}
+
+/// Typed extension of an `RWAAsset`, stored at `[b"asset_attributes", asset_id]`
+#[account]
+#[derive(InitSpace)]
+pub struct AssetAttributes {
+    pub asset_id: u64,
+    pub attributes: AssetAttributeData,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct UpdateAssetAttributes<'info> {
+    #[account(
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump,
+        has_one = owner
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + AssetAttributes::INIT_SPACE,
+        seeds = [b"asset_attributes", &asset_id.to_le_bytes()],
+        bump
+    )]
+    pub asset_attributes: Account<'info, AssetAttributes>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Instructions
+pub fn update_asset_attributes(
+    ctx: Context<UpdateAssetAttributes>,
+    asset_id: u64,
+    attributes: AssetAttributeData,
+) -> Result<()> {
+    ctx.accounts.registry.require_not_paused(PAUSE_METADATA_UPDATES)?;
+    let asset = &ctx.accounts.asset;
+    asset.require_operational()?;
+    attributes.validate(asset.asset_type)?;
+
+    let asset_attributes = &mut ctx.accounts.asset_attributes;
+    asset_attributes.asset_id = asset_id;
+    asset_attributes.attributes = attributes.clone();
+    asset_attributes.updated_at = Clock::get()?.unix_timestamp;
+    asset_attributes.bump = ctx.bumps.asset_attributes;
+
+    emit!(AssetAttributesUpdated {
+        asset_id,
+        attributes,
+        updated_by: ctx.accounts.owner.key(),
+        timestamp: asset_attributes.updated_at,
+    });
+
+    Ok(())
+}
+
+// Events
+#[event]
+pub struct AssetAttributesUpdated {
+    pub asset_id: u64,
+    pub attributes: AssetAttributeData,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+// Errors
+#[error_code(offset = 6600)]
+pub enum AttributeError {
+    #[msg("Attributes do not match the asset type")]
+    AttributeTypeMismatch,
+    #[msg("Attributes and the attributes account must be supplied together")]
+    AttributesAccountMismatch,
+    #[msg("Parcel id must be 1-64 characters")]
+    InvalidParcelId,
+    #[msg("Square footage must be positive")]
+    InvalidSquareFootage,
+    #[msg("Rental yield cannot exceed 10000 basis points")]
+    InvalidRentalYield,
+    #[msg("Project id must be 1-64 characters")]
+    InvalidProjectId,
+    #[msg("Vintage must be between 1990 and 2100")]
+    InvalidVintage,
+    #[msg("Tonnes must be positive")]
+    InvalidTonnes,
+    #[msg("Fineness must be between 1 and 10000")]
+    InvalidFineness,
+    #[msg("Vault must be 1-64 characters")]
+    InvalidVault,
+    #[msg("Bar serials must be unique, 1-32 characters and at most 16")]
+    InvalidBarSerials,
+}
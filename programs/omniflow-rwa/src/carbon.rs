@@ -0,0 +1,378 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use mpl_token_metadata::instruction as mpl_instruction;
+
+use crate::attributes::{AssetAttributeData, AssetAttributes};
+use crate::{AssetType, ErrorCode, RWAAsset, Registry, PAUSE_REDEMPTIONS};
+
+pub const MAX_BENEFICIARY_NAME_LEN: usize = 64;
+pub const MAX_RETIREMENT_REASON_LEN: usize = 128;
+
+/// Permanent record of retired carbon credits, stored at
+/// `[b"retirement", asset_id, certificate_id]`
+#[account]
+#[derive(InitSpace)]
+pub struct RetirementCertificate {
+    pub asset_id: u64,
+    pub certificate_id: u64,
+    pub retired_by: Pubkey,
+    #[max_len(64)]
+    pub beneficiary_name: String,
+    #[max_len(128)]
+    pub reason: String,
+    /// Tokens burned, in base units
+    pub amount: u64,
+    pub tonnes: u64,
+    pub vintage: u16,
+    pub retired_at: i64,
+    /// Non-transferable certificate NFT, once minted
+    pub certificate_mint: Option<Pubkey>,
+    pub bump: u8,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct RetireCarbonCredits<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump,
+        constraint = asset.asset_type == AssetType::CarbonCredits @ CarbonError::NotCarbonAsset
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    /// Source of the credits' vintage and tonnage
+    #[account(
+        mut,
+        seeds = [b"asset_attributes", &asset_id.to_le_bytes()],
+        bump = asset_attributes.bump
+    )]
+    pub asset_attributes: Account<'info, AssetAttributes>,
+
+    #[account(
+        init,
+        payer = holder,
+        space = 8 + RetirementCertificate::INIT_SPACE,
+        seeds = [
+            b"retirement",
+            &asset_id.to_le_bytes(),
+            &asset.retirement_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub certificate: Account<'info, RetirementCertificate>,
+
+    #[account(
+        mut,
+        address = asset.mint @ ErrorCode::MintMismatch
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = holder
+    )]
+    pub holder_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MintRetirementCertificate<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"retirement",
+            &certificate.asset_id.to_le_bytes(),
+            &certificate.certificate_id.to_le_bytes()
+        ],
+        bump = certificate.bump,
+        constraint = certificate.retired_by == holder.key() @ ErrorCode::Unauthorized,
+        constraint = certificate.certificate_mint.is_none() @ CarbonError::CertificateAlreadyMinted
+    )]
+    pub certificate: Account<'info, RetirementCertificate>,
+
+    #[account(
+        init,
+        payer = holder,
+        mint::decimals = 0,
+        mint::authority = certificate.key(),
+        mint::freeze_authority = certificate.key(),
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = holder,
+        associated_token::mint = mint,
+        associated_token::authority = holder,
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: This is the metadata account for the NFT
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: This is the master edition account for the NFT
+    #[account(mut)]
+    pub master_edition: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// CHECK: This is the token metadata program
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+}
+
+// Instructions
+pub fn retire_carbon_credits(
+    ctx: Context<RetireCarbonCredits>,
+    asset_id: u64,
+    amount: u64,
+    beneficiary_name: String,
+    reason: String,
+) -> Result<()> {
+    require!(
+        !beneficiary_name.is_empty() && beneficiary_name.len() <= MAX_BENEFICIARY_NAME_LEN,
+        CarbonError::InvalidBeneficiaryName
+    );
+    require!(
+        reason.len() <= MAX_RETIREMENT_REASON_LEN,
+        CarbonError::InvalidRetirementReason
+    );
+    ctx.accounts.registry.require_not_paused(PAUSE_REDEMPTIONS)?;
+    require!(amount > 0, CarbonError::InvalidRetirementAmount);
+
+    let asset = &mut ctx.accounts.asset;
+    asset.require_operational()?;
+    require!(amount <= asset.circulating_supply, ErrorCode::InsufficientBalance);
+
+    let (vintage, total_tonnes) = match &mut ctx.accounts.asset_attributes.attributes {
+        AssetAttributeData::CarbonCredits {
+            vintage, tonnes, ..
+        } => (*vintage, tonnes),
+        _ => return err!(CarbonError::NotCarbonAsset),
+    };
+
+    // Credits back the full supply pro rata, so retiring tokens retires their share of tonnes
+    let tonnes = (amount as u128)
+        .checked_mul(*total_tonnes as u128)
+        .and_then(|tonnes| tonnes.checked_div(asset.total_supply as u128))
+        .and_then(|tonnes| u64::try_from(tonnes).ok())
+        .ok_or(CarbonError::NoCreditSupply)?;
+    require!(tonnes > 0, CarbonError::RetirementTooSmall);
+
+    let cpi_accounts = token::Burn {
+        mint: ctx.accounts.mint.to_account_info(),
+        from: ctx.accounts.holder_token_account.to_account_info(),
+        authority: ctx.accounts.holder.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::burn(cpi_ctx, amount)?;
+
+    // Retired credits can never be minted again
+    *total_tonnes = total_tonnes.checked_sub(tonnes).unwrap();
+    asset.circulating_supply = asset.circulating_supply.checked_sub(amount).unwrap();
+    asset.total_supply = asset.total_supply.checked_sub(amount).unwrap();
+
+    let certificate_id = asset.retirement_count;
+    asset.retirement_count = certificate_id.checked_add(1).unwrap();
+
+    let certificate = &mut ctx.accounts.certificate;
+    certificate.asset_id = asset_id;
+    certificate.certificate_id = certificate_id;
+    certificate.retired_by = ctx.accounts.holder.key();
+    certificate.beneficiary_name = beneficiary_name.clone();
+    certificate.reason = reason.clone();
+    certificate.amount = amount;
+    certificate.tonnes = tonnes;
+    certificate.vintage = vintage;
+    certificate.retired_at = Clock::get()?.unix_timestamp;
+    certificate.certificate_mint = None;
+    certificate.bump = ctx.bumps.certificate;
+
+    emit!(CarbonCreditsRetired {
+        asset_id,
+        certificate_id,
+        retired_by: certificate.retired_by,
+        beneficiary_name,
+        reason,
+        amount,
+        tonnes,
+        vintage,
+        timestamp: certificate.retired_at,
+    });
+
+    Ok(())
+}
+
+pub fn mint_retirement_certificate(
+    ctx: Context<MintRetirementCertificate>,
+    metadata_uri: String,
+) -> Result<()> {
+    require!(metadata_uri.len() <= 200, ErrorCode::MetadataUriTooLong);
+
+    let certificate = &ctx.accounts.certificate;
+    let asset_id_bytes = certificate.asset_id.to_le_bytes();
+    let certificate_id_bytes = certificate.certificate_id.to_le_bytes();
+    let seeds = &[
+        b"retirement".as_ref(),
+        asset_id_bytes.as_ref(),
+        certificate_id_bytes.as_ref(),
+        &[certificate.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    // Mint the NFT to the holder
+    let cpi_accounts = token::MintTo {
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.token_account.to_account_info(),
+        authority: ctx.accounts.certificate.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    token::mint_to(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer), 1)?;
+
+    // Freeze it so the certificate stays with the holder. This must happen before
+    // the master edition takes over the freeze authority.
+    let cpi_accounts = token::FreezeAccount {
+        account: ctx.accounts.token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        authority: ctx.accounts.certificate.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    token::freeze_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer))?;
+
+    // Create metadata for the NFT
+    let metadata_infos = vec![
+        ctx.accounts.metadata.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.certificate.to_account_info(),
+        ctx.accounts.holder.to_account_info(),
+        ctx.accounts.token_metadata_program.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.rent.to_account_info(),
+    ];
+
+    let metadata_ix = mpl_instruction::create_metadata_accounts_v3(
+        ctx.accounts.token_metadata_program.key(),
+        ctx.accounts.metadata.key(),
+        ctx.accounts.mint.key(),
+        ctx.accounts.certificate.key(),
+        ctx.accounts.holder.key(),
+        ctx.accounts.certificate.key(),
+        format!(
+            "OmniFlow Carbon Retirement #{}-{}",
+            certificate.asset_id, certificate.certificate_id
+        ),
+        "OFCR".to_string(),
+        metadata_uri,
+        None,
+        0,
+        true,
+        false,
+        None,
+        None,
+        None,
+    );
+
+    invoke_signed(&metadata_ix, &metadata_infos, signer)?;
+
+    // Create master edition
+    let master_edition_infos = vec![
+        ctx.accounts.master_edition.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.certificate.to_account_info(),
+        ctx.accounts.holder.to_account_info(),
+        ctx.accounts.metadata.to_account_info(),
+        ctx.accounts.token_metadata_program.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.rent.to_account_info(),
+    ];
+
+    let master_edition_ix = mpl_instruction::create_master_edition_v3(
+        ctx.accounts.token_metadata_program.key(),
+        ctx.accounts.master_edition.key(),
+        ctx.accounts.mint.key(),
+        ctx.accounts.certificate.key(),
+        ctx.accounts.certificate.key(),
+        ctx.accounts.metadata.key(),
+        ctx.accounts.holder.key(),
+        Some(0), // Max supply of 1 (unique NFT)
+    );
+
+    invoke_signed(&master_edition_ix, &master_edition_infos, signer)?;
+
+    let certificate = &mut ctx.accounts.certificate;
+    certificate.certificate_mint = Some(ctx.accounts.mint.key());
+
+    emit!(RetirementCertificateMinted {
+        asset_id: certificate.asset_id,
+        certificate_id: certificate.certificate_id,
+        mint: ctx.accounts.mint.key(),
+        owner: certificate.retired_by,
+    });
+
+    Ok(())
+}
+
+// Events
+#[event]
+pub struct CarbonCreditsRetired {
+    pub asset_id: u64,
+    pub certificate_id: u64,
+    pub retired_by: Pubkey,
+    pub beneficiary_name: String,
+    pub reason: String,
+    pub amount: u64,
+    pub tonnes: u64,
+    pub vintage: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RetirementCertificateMinted {
+    pub asset_id: u64,
+    pub certificate_id: u64,
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+}
+
+// Errors
+#[error_code(offset = 6700)]
+pub enum CarbonError {
+    #[msg("Asset is not a carbon credit asset")]
+    NotCarbonAsset,
+    #[msg("Beneficiary name must be 1-64 characters")]
+    InvalidBeneficiaryName,
+    #[msg("Retirement reason is too long (max 128 characters)")]
+    InvalidRetirementReason,
+    #[msg("Amount retires less than one tonne")]
+    RetirementTooSmall,
+    #[msg("Certificate NFT was already minted")]
+    CertificateAlreadyMinted,
+    #[msg("Retirement amount must be positive")]
+    InvalidRetirementAmount,
+    #[msg("Asset has no credit supply to retire against")]
+    NoCreditSupply,
+}
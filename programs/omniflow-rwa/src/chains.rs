@@ -0,0 +1,440 @@
+use anchor_lang::prelude::*;
+
+use crate::multisig::{consume_proposal, MultisigProposal, PrivilegedAction};
+use crate::roles::{require_role, Role, RoleMembership};
+use crate::Registry;
+
+/// Wormhole chain id of Solana, used as the source chain of outbound messages
+pub const SOLANA_CHAIN_ID: u16 = 1;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum AddressFormat {
+    /// 20-byte account address, left-padded to 32 bytes
+    Evm,
+    /// 32-byte ed25519 public key
+    Solana,
+    /// Bech32-encoded account or contract hash
+    Cosmos,
+}
+
+const BECH32_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+impl AddressFormat {
+    /// Whether `recipient` is a well-formed wire address for this format.
+    /// An all-zero address is never valid, since tokens sent there are unrecoverable.
+    pub fn is_valid_recipient(&self, recipient: &[u8; 32]) -> bool {
+        if recipient.iter().all(|byte| *byte == 0) {
+            return false;
+        }
+
+        match self {
+            AddressFormat::Evm => recipient[..12].iter().all(|byte| *byte == 0),
+            AddressFormat::Solana => true,
+            // Account hashes are 20 bytes left-padded and contract hashes fill all 32, so any
+            // other padding is the wire form of a payload bech32 decoding rejects
+            AddressFormat::Cosmos => {
+                recipient[..12].iter().all(|byte| *byte == 0) || recipient[0] != 0
+            }
+        }
+    }
+
+    /// Decode a human-readable address into its 32-byte wire form
+    pub fn decode_address(&self, address: &str) -> Option<[u8; 32]> {
+        let bytes = match self {
+            AddressFormat::Evm => decode_hex_address(address)?,
+            AddressFormat::Solana => {
+                let bytes = bs58::decode(address).into_vec().ok()?;
+                // A shorter key would be left-padded into a different account
+                if bytes.len() != 32 {
+                    return None;
+                }
+                bytes
+            }
+            AddressFormat::Cosmos => decode_bech32_address(address)?,
+        };
+
+        let wire = left_pad(&bytes)?;
+        if self.is_valid_recipient(&wire) {
+            Some(wire)
+        } else {
+            None
+        }
+    }
+}
+
+fn left_pad(bytes: &[u8]) -> Option<[u8; 32]> {
+    if bytes.len() > 32 {
+        return None;
+    }
+
+    let mut wire = [0u8; 32];
+    wire[32 - bytes.len()..].copy_from_slice(bytes);
+    Some(wire)
+}
+
+fn decode_hex_address(address: &str) -> Option<Vec<u8>> {
+    let hex = address
+        .strip_prefix("0x")
+        .or_else(|| address.strip_prefix("0X"))
+        .unwrap_or(address);
+    if hex.len() != 40 {
+        return None;
+    }
+
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let high = (pair[0] as char).to_digit(16)?;
+            let low = (pair[1] as char).to_digit(16)?;
+            Some((high * 16 + low) as u8)
+        })
+        .collect()
+}
+
+fn bech32_polymod(values: impl Iterator<Item = u8>) -> u32 {
+    const GENERATORS: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+    let mut checksum = 1u32;
+    for value in values {
+        let top = checksum >> 25;
+        checksum = ((checksum & 0x1ffffff) << 5) ^ value as u32;
+        for (i, generator) in GENERATORS.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum
+}
+
+fn decode_bech32_address(address: &str) -> Option<Vec<u8>> {
+    let has_lower = address.bytes().any(|b| b.is_ascii_lowercase());
+    let has_upper = address.bytes().any(|b| b.is_ascii_uppercase());
+    if has_lower && has_upper {
+        return None;
+    }
+    let address = address.to_ascii_lowercase();
+    let separator = address.rfind('1')?;
+    let (hrp, data) = (&address[..separator], &address[separator + 1..]);
+    if hrp.is_empty() || data.len() < 6 {
+        return None;
+    }
+
+    let values = data
+        .bytes()
+        .map(|c| BECH32_CHARSET.iter().position(|x| *x == c).map(|v| v as u8))
+        .collect::<Option<Vec<u8>>>()?;
+
+    let expanded_hrp = hrp
+        .bytes()
+        .map(|c| c >> 5)
+        .chain(std::iter::once(0))
+        .chain(hrp.bytes().map(|c| c & 31));
+    if bech32_polymod(expanded_hrp.chain(values.iter().copied())) != 1 {
+        return None;
+    }
+
+    // Regroup the 5-bit payload (minus checksum) into bytes
+    let mut bytes = Vec::new();
+    let (mut acc, mut bits) = (0u32, 0u32);
+    for value in &values[..values.len() - 6] {
+        acc = (acc << 5) | *value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    if bits >= 5 || acc != 0 {
+        return None;
+    }
+
+    match bytes.len() {
+        20 | 32 => Some(bytes),
+        _ => None,
+    }
+}
+
+/// Configuration of a chain reachable through the bridges, keyed by Wormhole chain id
+#[account]
+#[derive(InitSpace)]
+pub struct ChainConfig {
+    pub wormhole_chain_id: u16,
+    #[max_len(32)]
+    pub name: String,
+    pub evm_chain_id: Option<u64>,
+    pub address_format: AddressFormat,
+    pub enabled: bool,
+    pub added_at: i64,
+    pub bump: u8,
+}
+
+#[derive(Accounts)]
+#[instruction(wormhole_chain_id: u16)]
+pub struct AddSupportedChain<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ChainConfig::INIT_SPACE,
+        seeds = [b"chain", &wormhole_chain_id.to_le_bytes()],
+        bump
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    /// Admin membership of the signer, when the signer is not the registry authority
+    #[account(
+        seeds = [b"role", &[Role::Admin as u8], authority.key().as_ref()],
+        bump = admin_role.bump
+    )]
+    pub admin_role: Option<Account<'info, RoleMembership>>,
+
+    /// CHECK: Multisig PDA; once created, the Admin path is closed
+    #[account(seeds = [b"multisig"], bump)]
+    pub multisig: UncheckedAccount<'info>,
+
+    /// Executed multisig proposal authorizing this call in place of the Admin role
+    #[account(
+        mut,
+        seeds = [b"multisig_proposal", &multisig_proposal.proposal_id.to_le_bytes()],
+        bump = multisig_proposal.bump
+    )]
+    pub multisig_proposal: Option<Account<'info, MultisigProposal>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DisableSupportedChain<'info> {
+    #[account(
+        mut,
+        seeds = [b"chain", &chain_config.wormhole_chain_id.to_le_bytes()],
+        bump = chain_config.bump
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    /// Admin membership of the signer, when the signer is not the registry authority
+    #[account(
+        seeds = [b"role", &[Role::Admin as u8], authority.key().as_ref()],
+        bump = admin_role.bump
+    )]
+    pub admin_role: Option<Account<'info, RoleMembership>>,
+
+    /// CHECK: Multisig PDA; once created, the Admin path is closed
+    #[account(seeds = [b"multisig"], bump)]
+    pub multisig: UncheckedAccount<'info>,
+
+    /// Executed multisig proposal authorizing this call in place of the Admin role
+    #[account(
+        mut,
+        seeds = [b"multisig_proposal", &multisig_proposal.proposal_id.to_le_bytes()],
+        bump = multisig_proposal.bump
+    )]
+    pub multisig_proposal: Option<Account<'info, MultisigProposal>>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EnableSupportedChain<'info> {
+    #[account(
+        mut,
+        seeds = [b"chain", &chain_config.wormhole_chain_id.to_le_bytes()],
+        bump = chain_config.bump
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    /// Admin membership of the signer, when the signer is not the registry authority
+    #[account(
+        seeds = [b"role", &[Role::Admin as u8], authority.key().as_ref()],
+        bump = admin_role.bump
+    )]
+    pub admin_role: Option<Account<'info, RoleMembership>>,
+
+    /// CHECK: Multisig PDA; once created, the Admin path is closed
+    #[account(seeds = [b"multisig"], bump)]
+    pub multisig: UncheckedAccount<'info>,
+
+    /// Executed multisig proposal authorizing this call in place of the Admin role
+    #[account(
+        mut,
+        seeds = [b"multisig_proposal", &multisig_proposal.proposal_id.to_le_bytes()],
+        bump = multisig_proposal.bump
+    )]
+    pub multisig_proposal: Option<Account<'info, MultisigProposal>>,
+
+    pub authority: Signer<'info>,
+}
+
+// Instructions
+pub fn add_supported_chain(
+    ctx: Context<AddSupportedChain>,
+    wormhole_chain_id: u16,
+    name: String,
+    evm_chain_id: Option<u64>,
+    address_format: AddressFormat,
+) -> Result<()> {
+    let action = PrivilegedAction::AddSupportedChain {
+        wormhole_chain_id,
+        name: name.clone(),
+        evm_chain_id,
+        address_format,
+    };
+    if !consume_proposal(
+        &ctx.accounts.multisig,
+        &mut ctx.accounts.multisig_proposal,
+        &action,
+    )? {
+        require_role(
+            &ctx.accounts.registry,
+            &ctx.accounts.authority.key(),
+            &ctx.accounts.admin_role,
+            Role::Admin,
+        )?;
+    }
+
+    require!(!name.is_empty() && name.len() <= 32, ChainError::InvalidChainName);
+    require!(
+        evm_chain_id.is_some() == (address_format == AddressFormat::Evm),
+        ChainError::InvalidEvmChainId
+    );
+
+    let chain_config = &mut ctx.accounts.chain_config;
+    chain_config.wormhole_chain_id = wormhole_chain_id;
+    chain_config.name = name.clone();
+    chain_config.evm_chain_id = evm_chain_id;
+    chain_config.address_format = address_format;
+    chain_config.enabled = true;
+    chain_config.added_at = Clock::get()?.unix_timestamp;
+    chain_config.bump = ctx.bumps.chain_config;
+
+    emit!(SupportedChainAdded {
+        wormhole_chain_id,
+        name,
+        evm_chain_id,
+        address_format,
+    });
+
+    Ok(())
+}
+
+pub fn disable_supported_chain(ctx: Context<DisableSupportedChain>) -> Result<()> {
+    let action = PrivilegedAction::DisableSupportedChain {
+        wormhole_chain_id: ctx.accounts.chain_config.wormhole_chain_id,
+    };
+    if !consume_proposal(
+        &ctx.accounts.multisig,
+        &mut ctx.accounts.multisig_proposal,
+        &action,
+    )? {
+        require_role(
+            &ctx.accounts.registry,
+            &ctx.accounts.authority.key(),
+            &ctx.accounts.admin_role,
+            Role::Admin,
+        )?;
+    }
+
+    let chain_config = &mut ctx.accounts.chain_config;
+    require!(chain_config.enabled, ChainError::ChainDisabled);
+
+    chain_config.enabled = false;
+
+    emit!(SupportedChainDisabled {
+        wormhole_chain_id: chain_config.wormhole_chain_id,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Re-enable a disabled chain. Its config account already exists, so `add_supported_chain`
+/// cannot be used again.
+pub fn enable_supported_chain(ctx: Context<EnableSupportedChain>) -> Result<()> {
+    let action = PrivilegedAction::EnableSupportedChain {
+        wormhole_chain_id: ctx.accounts.chain_config.wormhole_chain_id,
+    };
+    if !consume_proposal(
+        &ctx.accounts.multisig,
+        &mut ctx.accounts.multisig_proposal,
+        &action,
+    )? {
+        require_role(
+            &ctx.accounts.registry,
+            &ctx.accounts.authority.key(),
+            &ctx.accounts.admin_role,
+            Role::Admin,
+        )?;
+    }
+
+    let chain_config = &mut ctx.accounts.chain_config;
+    require!(!chain_config.enabled, ChainError::ChainAlreadyEnabled);
+
+    chain_config.enabled = true;
+
+    emit!(SupportedChainEnabled {
+        wormhole_chain_id: chain_config.wormhole_chain_id,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Events
+#[event]
+pub struct SupportedChainAdded {
+    pub wormhole_chain_id: u16,
+    pub name: String,
+    pub evm_chain_id: Option<u64>,
+    pub address_format: AddressFormat,
+}
+
+#[event]
+pub struct SupportedChainDisabled {
+    pub wormhole_chain_id: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SupportedChainEnabled {
+    pub wormhole_chain_id: u16,
+    pub timestamp: i64,
+}
+
+// Errors
+#[error_code(offset = 6100)]
+pub enum ChainError {
+    #[msg("Chain name must be 1-32 characters")]
+    InvalidChainName,
+    #[msg("EVM chain id must be set exactly for EVM chains")]
+    InvalidEvmChainId,
+    #[msg("Chain is disabled")]
+    ChainDisabled,
+    #[msg("Recipient address is malformed for the target chain")]
+    InvalidRecipient,
+    #[msg("Recipient is not a verified address of the sender's passport")]
+    RecipientNotVerified,
+    #[msg("Chain is already enabled")]
+    ChainAlreadyEnabled,
+}
@@ -0,0 +1,167 @@
+//! Host-side helpers for reading program state. Account data is supplied by
+//! an `AccountFetcher`, so these work with an RPC client, a test bank or a cache.
+
+use anchor_lang::prelude::*;
+
+use crate::asset_index::{AssetIndexEntry, AssetIndexPage, ASSET_INDEX_PAGE_SIZE};
+use crate::documents::{AssetDocument, DocumentType};
+use crate::reserve::ReserveAttestation;
+use crate::snapshot::BalanceSnapshot;
+use crate::valuation::ValuationHistory;
+use crate::{AssetType, RWAAsset};
+
+pub trait AccountFetcher {
+    /// Raw data of the account at `address`, or `None` if it does not exist
+    fn fetch(&self, address: &Pubkey) -> Option<Vec<u8>>;
+}
+
+impl<F: Fn(&Pubkey) -> Option<Vec<u8>>> AccountFetcher for F {
+    fn fetch(&self, address: &Pubkey) -> Option<Vec<u8>> {
+        self(address)
+    }
+}
+
+/// Fetch and deserialize an Anchor account, checking its discriminator
+pub fn fetch_account<T: AccountDeserialize>(
+    fetcher: &impl AccountFetcher,
+    address: &Pubkey,
+) -> Option<T> {
+    let data = fetcher.fetch(address)?;
+    T::try_deserialize(&mut data.as_slice()).ok()
+}
+
+/// Iterator over every indexed asset, loading one index page at a time
+pub struct AssetIter<'a, F: AccountFetcher> {
+    fetcher: &'a F,
+    next_page: Option<u64>,
+    entries: std::vec::IntoIter<AssetIndexEntry>,
+}
+
+impl<'a, F: AccountFetcher> Iterator for AssetIter<'a, F> {
+    type Item = AssetIndexEntry;
+
+    fn next(&mut self) -> Option<AssetIndexEntry> {
+        loop {
+            if let Some(entry) = self.entries.next() {
+                return Some(entry);
+            }
+
+            let page_number = self.next_page?;
+            let page: AssetIndexPage =
+                fetch_account(self.fetcher, &AssetIndexPage::address(page_number))?;
+            // A partially filled page is always the last one
+            self.next_page = if page.entries.len() as u64 == ASSET_INDEX_PAGE_SIZE {
+                Some(page_number + 1)
+            } else {
+                None
+            };
+            self.entries = page.entries.into_iter();
+        }
+    }
+}
+
+pub fn iter_assets<F: AccountFetcher>(fetcher: &F) -> AssetIter<'_, F> {
+    AssetIter {
+        fetcher,
+        next_page: Some(0),
+        entries: Vec::new().into_iter(),
+    }
+}
+
+pub fn assets_by_type<F: AccountFetcher>(
+    fetcher: &F,
+    asset_type: AssetType,
+) -> impl Iterator<Item = AssetIndexEntry> + '_ {
+    iter_assets(fetcher).filter(move |entry| entry.asset_type == asset_type)
+}
+
+pub fn assets_by_owner<F: AccountFetcher>(
+    fetcher: &F,
+    owner: Pubkey,
+) -> impl Iterator<Item = AssetIndexEntry> + '_ {
+    iter_assets(fetcher).filter(move |entry| entry.owner == owner)
+}
+
+pub fn fetch_snapshot(
+    fetcher: &impl AccountFetcher,
+    asset_id: u64,
+    snapshot_id: u64,
+) -> Option<BalanceSnapshot> {
+    fetch_account(fetcher, &BalanceSnapshot::address(asset_id, snapshot_id))
+}
+
+/// Every snapshot taken for `asset_id`, oldest first
+pub fn asset_snapshots(fetcher: &impl AccountFetcher, asset_id: u64) -> Vec<BalanceSnapshot> {
+    let Some(asset) = fetch_account::<RWAAsset>(fetcher, &RWAAsset::address(asset_id)) else {
+        return Vec::new();
+    };
+    (1..=asset.snapshot_id)
+        .filter_map(|snapshot_id| fetch_snapshot(fetcher, asset_id, snapshot_id))
+        .collect()
+}
+
+/// Latest snapshot with a committed balance root taken at or before `timestamp`
+pub fn snapshot_at(
+    fetcher: &impl AccountFetcher,
+    asset_id: u64,
+    timestamp: i64,
+) -> Option<BalanceSnapshot> {
+    asset_snapshots(fetcher, asset_id)
+        .into_iter()
+        .filter(|snapshot| snapshot.taken_at <= timestamp && snapshot.balances_root.is_some())
+        .last()
+}
+
+/// Whether `proof` shows `holder` held `balance` of `asset_id` at `snapshot_id`
+pub fn verify_snapshot_balance(
+    fetcher: &impl AccountFetcher,
+    asset_id: u64,
+    snapshot_id: u64,
+    holder: &Pubkey,
+    balance: u64,
+    proof: &[[u8; 32]],
+) -> bool {
+    fetch_snapshot(fetcher, asset_id, snapshot_id)
+        .is_some_and(|snapshot| snapshot.verify_balance(holder, balance, proof))
+}
+
+/// Net asset value per whole issued token of `asset_id`, whose mint has `decimals`.
+/// `None` before any tokens are issued.
+pub fn nav_per_token(fetcher: &impl AccountFetcher, asset_id: u64, decimals: u8) -> Option<u64> {
+    let asset: RWAAsset = fetch_account(fetcher, &RWAAsset::address(asset_id))?;
+    ValuationHistory::nav_per_token(asset.total_value, asset.circulating_supply, decimals)
+}
+
+/// Attested reserves over circulating supply of `asset_id` in basis points,
+/// `None` without an attestation or with nothing issued
+pub fn collateralization_ratio_bps(fetcher: &impl AccountFetcher, asset_id: u64) -> Option<u64> {
+    let asset: RWAAsset = fetch_account(fetcher, &RWAAsset::address(asset_id))?;
+    let reserve: ReserveAttestation =
+        fetch_account(fetcher, &ReserveAttestation::address(asset_id))?;
+    if reserve.attestation_count == 0 {
+        return None;
+    }
+    reserve.collateralization_bps(asset.circulating_supply)
+}
+
+/// Every published version of a document, oldest first
+pub fn document_history(
+    fetcher: &impl AccountFetcher,
+    asset_id: u64,
+    document_type: DocumentType,
+) -> Vec<AssetDocument> {
+    (1..)
+        .map_while(|version| {
+            fetch_account(fetcher, &AssetDocument::address(asset_id, document_type, version))
+        })
+        .collect()
+}
+
+/// Current version of a document, `None` if it was never published
+pub fn latest_document(
+    fetcher: &impl AccountFetcher,
+    asset_id: u64,
+    document_type: DocumentType,
+) -> Option<AssetDocument> {
+    document_history(fetcher, asset_id, document_type).pop()
+}
@@ -0,0 +1,426 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+use crate::merkle;
+use crate::snapshot::{BalanceSnapshot, SnapshotError};
+use crate::{RWAAsset, Registry, PAUSE_DISTRIBUTIONS};
+
+/// Payout to holders of an asset, pro rata to their balance at the record date.
+/// Stored at `[b"distribution", asset_id, distribution_id]`.
+#[account]
+#[derive(InitSpace)]
+pub struct Distribution {
+    pub asset_id: u64,
+    pub distribution_id: u64,
+    pub payout_mint: Pubkey,
+    /// Balance snapshot used as the record date
+    pub snapshot_id: u64,
+    /// Copied from the snapshot, which cannot change once committed
+    pub balances_root: [u8; 32],
+    pub snapshot_supply: u64,
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    /// Snapshot balances claimed against, never above `snapshot_supply`
+    pub claimed_balance: u64,
+    pub created_at: i64,
+    /// Claims close at this timestamp, after which the owner can sweep the rest
+    pub claim_deadline: i64,
+    pub swept: bool,
+    pub bump: u8,
+}
+
+impl Distribution {
+    /// Pro rata share of `total_amount`, `None` if it does not fit in a u64
+    pub fn payout_for(&self, balance: u64) -> Option<u64> {
+        let payout = (self.total_amount as u128) * (balance as u128) / (self.snapshot_supply as u128);
+        u64::try_from(payout).ok()
+    }
+}
+
+/// Marker that `holder` claimed from a distribution, stored at
+/// `[b"distribution_claim", distribution, holder]`
+#[account]
+#[derive(InitSpace)]
+pub struct DistributionClaim {
+    pub distribution: Pubkey,
+    pub holder: Pubkey,
+    pub amount: u64,
+    pub claimed_at: i64,
+    pub bump: u8,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct CreateDistribution<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump,
+        has_one = owner
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Distribution::INIT_SPACE,
+        seeds = [
+            b"distribution",
+            &asset_id.to_le_bytes(),
+            &asset.distribution_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub distribution: Account<'info, Distribution>,
+
+    #[account(
+        seeds = [b"snapshot", &asset_id.to_le_bytes(), &snapshot.snapshot_id.to_le_bytes()],
+        bump = snapshot.bump
+    )]
+    pub snapshot: Account<'info, BalanceSnapshot>,
+
+    pub payout_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = owner,
+        token::mint = payout_mint,
+        token::authority = distribution,
+        seeds = [b"distribution_vault", distribution.key().as_ref()],
+        bump
+    )]
+    pub distribution_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = payout_mint,
+        token::authority = owner
+    )]
+    pub owner_payout_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimDistribution<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"distribution",
+            &distribution.asset_id.to_le_bytes(),
+            &distribution.distribution_id.to_le_bytes()
+        ],
+        bump = distribution.bump
+    )]
+    pub distribution: Account<'info, Distribution>,
+
+    #[account(
+        init,
+        payer = holder,
+        space = 8 + DistributionClaim::INIT_SPACE,
+        seeds = [b"distribution_claim", distribution.key().as_ref(), holder.key().as_ref()],
+        bump
+    )]
+    pub claim: Account<'info, DistributionClaim>,
+
+    #[account(
+        mut,
+        seeds = [b"distribution_vault", distribution.key().as_ref()],
+        bump
+    )]
+    pub distribution_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = distribution.payout_mint,
+        token::authority = holder
+    )]
+    pub holder_payout_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SweepDistribution<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &distribution.asset_id.to_le_bytes()],
+        bump = asset.bump,
+        has_one = owner
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"distribution",
+            &distribution.asset_id.to_le_bytes(),
+            &distribution.distribution_id.to_le_bytes()
+        ],
+        bump = distribution.bump
+    )]
+    pub distribution: Account<'info, Distribution>,
+
+    #[account(
+        mut,
+        seeds = [b"distribution_vault", distribution.key().as_ref()],
+        bump
+    )]
+    pub distribution_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = distribution.payout_mint,
+        token::authority = owner
+    )]
+    pub owner_payout_account: Account<'info, TokenAccount>,
+
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+// Instructions
+pub fn create_distribution(
+    ctx: Context<CreateDistribution>,
+    asset_id: u64,
+    amount: u64,
+    claim_deadline: i64,
+) -> Result<()> {
+    ctx.accounts.registry.require_not_paused(PAUSE_DISTRIBUTIONS)?;
+    let now = Clock::get()?.unix_timestamp;
+    let snapshot = &ctx.accounts.snapshot;
+    let balances_root = snapshot.balances_root.ok_or(SnapshotError::RootNotCommitted)?;
+    let snapshot_supply = snapshot.supply;
+    require!(amount > 0, DistributionError::InvalidAmount);
+    require!(snapshot_supply > 0, DistributionError::InvalidSnapshotSupply);
+    require!(claim_deadline > now, DistributionError::InvalidDeadline);
+
+    let cpi_accounts = token::Transfer {
+        from: ctx.accounts.owner_payout_account.to_account_info(),
+        to: ctx.accounts.distribution_vault.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, amount)?;
+
+    let asset = &mut ctx.accounts.asset;
+    let distribution_id = asset.distribution_count;
+    asset.distribution_count = distribution_id.checked_add(1).unwrap();
+    asset.open_distributions = asset.open_distributions.checked_add(1).unwrap();
+
+    let distribution = &mut ctx.accounts.distribution;
+    distribution.asset_id = asset_id;
+    distribution.distribution_id = distribution_id;
+    distribution.payout_mint = ctx.accounts.payout_mint.key();
+    distribution.snapshot_id = ctx.accounts.snapshot.snapshot_id;
+    distribution.balances_root = balances_root;
+    distribution.snapshot_supply = snapshot_supply;
+    distribution.total_amount = amount;
+    distribution.claimed_amount = 0;
+    distribution.claimed_balance = 0;
+    distribution.created_at = now;
+    distribution.claim_deadline = claim_deadline;
+    distribution.swept = false;
+    distribution.bump = ctx.bumps.distribution;
+
+    emit!(DistributionCreated {
+        asset_id,
+        distribution_id,
+        payout_mint: distribution.payout_mint,
+        amount,
+        snapshot_id: distribution.snapshot_id,
+        snapshot_supply,
+        claim_deadline,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub fn claim_distribution(
+    ctx: Context<ClaimDistribution>,
+    balance: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let holder = ctx.accounts.holder.key();
+    let distribution = &ctx.accounts.distribution;
+    require!(now < distribution.claim_deadline, DistributionError::ClaimPeriodEnded);
+    require!(
+        merkle::verify_proof(
+            &proof,
+            &distribution.balances_root,
+            merkle::balance_leaf(&holder, balance)
+        ),
+        DistributionError::InvalidProof
+    );
+
+    // Leaves summing past the supply would leave later holders unpaid
+    let claimed_balance = distribution
+        .claimed_balance
+        .checked_add(balance)
+        .filter(|claimed| *claimed <= distribution.snapshot_supply)
+        .ok_or(DistributionError::BalanceExceedsSupply)?;
+    let amount = distribution
+        .payout_for(balance)
+        .ok_or(DistributionError::BalanceExceedsSupply)?;
+    require!(amount > 0, DistributionError::InvalidAmount);
+
+    let asset_id_bytes = distribution.asset_id.to_le_bytes();
+    let distribution_id_bytes = distribution.distribution_id.to_le_bytes();
+    let seeds = &[
+        b"distribution".as_ref(),
+        asset_id_bytes.as_ref(),
+        distribution_id_bytes.as_ref(),
+        &[distribution.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = token::Transfer {
+        from: ctx.accounts.distribution_vault.to_account_info(),
+        to: ctx.accounts.holder_payout_account.to_account_info(),
+        authority: ctx.accounts.distribution.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer,
+    );
+    token::transfer(cpi_ctx, amount)?;
+
+    let distribution = &mut ctx.accounts.distribution;
+    distribution.claimed_amount = distribution.claimed_amount.checked_add(amount).unwrap();
+    distribution.claimed_balance = claimed_balance;
+
+    let claim = &mut ctx.accounts.claim;
+    claim.distribution = distribution.key();
+    claim.holder = holder;
+    claim.amount = amount;
+    claim.claimed_at = now;
+    claim.bump = ctx.bumps.claim;
+
+    emit!(DistributionClaimed {
+        asset_id: distribution.asset_id,
+        distribution_id: distribution.distribution_id,
+        holder,
+        balance,
+        amount,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub fn sweep_distribution(ctx: Context<SweepDistribution>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let distribution = &ctx.accounts.distribution;
+    require!(now >= distribution.claim_deadline, DistributionError::ClaimPeriodActive);
+    require!(!distribution.swept, DistributionError::AlreadySwept);
+
+    let amount = ctx.accounts.distribution_vault.amount;
+    let asset_id_bytes = distribution.asset_id.to_le_bytes();
+    let distribution_id_bytes = distribution.distribution_id.to_le_bytes();
+    let seeds = &[
+        b"distribution".as_ref(),
+        asset_id_bytes.as_ref(),
+        distribution_id_bytes.as_ref(),
+        &[distribution.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = token::Transfer {
+        from: ctx.accounts.distribution_vault.to_account_info(),
+        to: ctx.accounts.owner_payout_account.to_account_info(),
+        authority: ctx.accounts.distribution.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer,
+    );
+    token::transfer(cpi_ctx, amount)?;
+
+    let asset = &mut ctx.accounts.asset;
+    asset.open_distributions = asset.open_distributions.checked_sub(1).unwrap();
+
+    let distribution = &mut ctx.accounts.distribution;
+    distribution.swept = true;
+
+    emit!(UnclaimedDistributionSwept {
+        asset_id: distribution.asset_id,
+        distribution_id: distribution.distribution_id,
+        amount,
+        swept_to: ctx.accounts.owner_payout_account.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// Events
+#[event]
+pub struct DistributionCreated {
+    pub asset_id: u64,
+    pub distribution_id: u64,
+    pub payout_mint: Pubkey,
+    pub amount: u64,
+    pub snapshot_id: u64,
+    pub snapshot_supply: u64,
+    pub claim_deadline: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DistributionClaimed {
+    pub asset_id: u64,
+    pub distribution_id: u64,
+    pub holder: Pubkey,
+    pub balance: u64,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UnclaimedDistributionSwept {
+    pub asset_id: u64,
+    pub distribution_id: u64,
+    pub amount: u64,
+    pub swept_to: Pubkey,
+    pub timestamp: i64,
+}
+
+// Errors
+#[error_code(offset = 6900)]
+pub enum DistributionError {
+    #[msg("Distribution amount must be positive")]
+    InvalidAmount,
+    #[msg("Snapshot supply must be positive")]
+    InvalidSnapshotSupply,
+    #[msg("Claim deadline must be in the future")]
+    InvalidDeadline,
+    #[msg("Balance proof does not match the distribution snapshot")]
+    InvalidProof,
+    #[msg("Claim period has ended")]
+    ClaimPeriodEnded,
+    #[msg("Claim period is still open")]
+    ClaimPeriodActive,
+    #[msg("Distribution was already swept")]
+    AlreadySwept,
+    #[msg("Claimed balances exceed the snapshot supply")]
+    BalanceExceedsSupply,
+}
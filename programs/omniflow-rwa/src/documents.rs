@@ -0,0 +1,183 @@
+use anchor_lang::prelude::*;
+use sha2::{Digest, Sha256};
+
+use crate::{ErrorCode, RWAAsset, Registry, PAUSE_METADATA_UPDATES};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum DocumentType {
+    OfferingMemorandum,
+    SubscriptionAgreement,
+    TitleDeed,
+    AuditReport,
+    Other,
+}
+
+/// One version of a legal document attached to an asset, stored at
+/// `[b"asset_document", asset_id, document_type, version]`. Never modified once published;
+/// a new version is a new account.
+#[account]
+#[derive(InitSpace)]
+pub struct AssetDocument {
+    pub asset_id: u64,
+    pub document_type: DocumentType,
+    /// Starts at 1 and increases by one per published version
+    pub version: u32,
+    /// SHA-256 of the document content
+    pub content_hash: [u8; 32],
+    #[max_len(200)]
+    pub uri: String,
+    pub effective_date: i64,
+    /// Content hash of the version this one supersedes
+    pub previous_hash: Option<[u8; 32]>,
+    pub published_by: Pubkey,
+    pub published_at: i64,
+    pub bump: u8,
+}
+
+impl AssetDocument {
+    pub fn address(asset_id: u64, document_type: DocumentType, version: u32) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                b"asset_document",
+                &asset_id.to_le_bytes(),
+                &[document_type as u8],
+                &version.to_le_bytes(),
+            ],
+            &crate::ID,
+        )
+        .0
+    }
+
+    /// Whether `content` is the document this version commits to
+    pub fn verify_content(&self, content: &[u8]) -> bool {
+        <[u8; 32]>::from(Sha256::digest(content)) == self.content_hash
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64, document_type: DocumentType, version: u32)]
+pub struct PublishAssetDocument<'info> {
+    #[account(
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump,
+        has_one = owner
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + AssetDocument::INIT_SPACE,
+        seeds = [
+            b"asset_document",
+            &asset_id.to_le_bytes(),
+            &[document_type as u8],
+            &version.to_le_bytes()
+        ],
+        bump
+    )]
+    pub document: Account<'info, AssetDocument>,
+
+    /// Version being superseded, required unless this is version 1
+    #[account(
+        seeds = [
+            b"asset_document",
+            &asset_id.to_le_bytes(),
+            &[document_type as u8],
+            &version.saturating_sub(1).to_le_bytes()
+        ],
+        bump = previous_document.bump
+    )]
+    pub previous_document: Option<Account<'info, AssetDocument>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Instructions
+pub fn publish_asset_document(
+    ctx: Context<PublishAssetDocument>,
+    asset_id: u64,
+    document_type: DocumentType,
+    version: u32,
+    content_hash: [u8; 32],
+    uri: String,
+    effective_date: i64,
+) -> Result<()> {
+    ctx.accounts.registry.require_not_paused(PAUSE_METADATA_UPDATES)?;
+    require!(uri.len() <= 200, ErrorCode::MetadataUriTooLong);
+    require!(version >= 1, DocumentError::InvalidVersion);
+    require!(content_hash != [0; 32], DocumentError::InvalidContentHash);
+
+    // Versions form an unbroken chain, so the latest is found by walking up from 1
+    let previous_hash = match &ctx.accounts.previous_document {
+        Some(previous) if version > 1 => {
+            require!(
+                effective_date >= previous.effective_date,
+                DocumentError::InvalidEffectiveDate
+            );
+            Some(previous.content_hash)
+        }
+        None if version == 1 => None,
+        _ => return err!(DocumentError::InvalidVersion),
+    };
+
+    let now = Clock::get()?.unix_timestamp;
+    let document = &mut ctx.accounts.document;
+    document.asset_id = asset_id;
+    document.document_type = document_type;
+    document.version = version;
+    document.content_hash = content_hash;
+    document.uri = uri.clone();
+    document.effective_date = effective_date;
+    document.previous_hash = previous_hash;
+    document.published_by = ctx.accounts.owner.key();
+    document.published_at = now;
+    document.bump = ctx.bumps.document;
+
+    emit!(AssetDocumentPublished {
+        asset_id,
+        document_type,
+        version,
+        content_hash,
+        uri,
+        effective_date,
+        previous_hash,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// Events
+#[event]
+pub struct AssetDocumentPublished {
+    pub asset_id: u64,
+    pub document_type: DocumentType,
+    pub version: u32,
+    pub content_hash: [u8; 32],
+    pub uri: String,
+    pub effective_date: i64,
+    /// Content hash of the superseded version, `None` for version 1
+    pub previous_hash: Option<[u8; 32]>,
+    pub timestamp: i64,
+}
+
+// Errors
+#[error_code(offset = 7600)]
+pub enum DocumentError {
+    #[msg("Document version must follow the latest published version")]
+    InvalidVersion,
+    #[msg("Document content hash must be set")]
+    InvalidContentHash,
+    #[msg("Document cannot take effect before the version it supersedes")]
+    InvalidEffectiveDate,
+}
@@ -4,6 +4,10 @@ use mpl_token_metadata::instruction as mpl_instruction;
 use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
 use solana_program::program::invoke;
 
+use crate::chains::{AddressFormat, ChainConfig, ChainError};
+use crate::roles::{Role, RoleMembership};
+use crate::{AuthorityTransferAccepted, AuthorityTransferCancelled, AuthorityTransferProposed};
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
 pub enum KYCLevel {
     None,
@@ -12,7 +16,7 @@ pub enum KYCLevel {
     Institutional,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, InitSpace)]
 pub enum InvestorTier {
     None,
     Retail,
@@ -23,6 +27,7 @@ pub enum InvestorTier {
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct CrossChainAddress {
+    pub chain_id: u16,
     pub chain: String,
     pub address: String,
     pub verified: bool,
@@ -60,17 +65,39 @@ impl IdentityPassport {
         1 + // is_active
         4 + 200 + // metadata_uri
         32 + // issuer
-        4 + (10 * (4 + 50 + 4 + 100 + 1 + 8)) + // cross_chain_addresses (max 10)
+        4 + (10 * (2 + 4 + 50 + 4 + 100 + 1 + 8)) + // cross_chain_addresses (max 10)
         4 + (20 * (4 + 100)) + // credentials (max 20)
         1; // bump
+
+    /// Whether the passport is active, unexpired and at or above `required`
+    pub fn satisfies_kyc(&self, required: crate::KYCLevel, now: i64) -> bool {
+        self.is_active
+            && now < self.expiration_date
+            && self.kyc_level.clone() as u8 >= required as u8
+    }
+
+    /// Whether a verified linked address on `chain_id` decodes to `recipient`
+    pub fn has_verified_address(
+        &self,
+        chain_id: u16,
+        address_format: AddressFormat,
+        recipient: &[u8; 32],
+    ) -> bool {
+        self.is_active
+            && self.cross_chain_addresses.iter().any(|linked| {
+                linked.verified
+                    && linked.chain_id == chain_id
+                    && address_format.decode_address(&linked.address).as_ref() == Some(recipient)
+            })
+    }
 }
 
 #[account]
 pub struct IdentityRegistry {
     pub authority: Pubkey,
+    pub pending_authority: Option<Pubkey>,
     pub total_passports: u64,
     pub authorized_issuers: Vec<Pubkey>,
-    pub supported_chains: Vec<String>,
     pub cross_chain_bridge: Option<Pubkey>,
     pub paused: bool,
     pub bump: u8,
@@ -79,9 +106,9 @@ pub struct IdentityRegistry {
 impl IdentityRegistry {
     pub const MAX_SIZE: usize = 8 + // discriminator
         32 + // authority
+        1 + 32 + // pending_authority
         8 + // total_passports
         4 + (10 * 32) + // authorized_issuers (max 10)
-        4 + (10 * (4 + 20)) + // supported_chains (max 10)
         1 + 32 + // cross_chain_bridge
         1 + // paused
         1; // bump
@@ -136,6 +163,12 @@ pub struct IssueIdentityPassport<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     
+    #[account(
+        seeds = [b"role", &[Role::ComplianceOfficer as u8], issuer.key().as_ref()],
+        bump = compliance_role.bump
+    )]
+    pub compliance_role: Option<Account<'info, RoleMembership>>,
+    
     pub issuer: Signer<'info>,
     
     pub rent: Sysvar<'info, Rent>,
@@ -162,10 +195,17 @@ pub struct UpdateIdentityPassport<'info> {
     )]
     pub registry: Account<'info, IdentityRegistry>,
     
+    #[account(
+        seeds = [b"role", &[Role::ComplianceOfficer as u8], issuer.key().as_ref()],
+        bump = compliance_role.bump
+    )]
+    pub compliance_role: Option<Account<'info, RoleMembership>>,
+    
     pub issuer: Signer<'info>,
 }
 
 #[derive(Accounts)]
+#[instruction(chain_id: u16)]
 pub struct LinkCrossChainAddress<'info> {
     #[account(
         mut,
@@ -175,9 +215,40 @@ pub struct LinkCrossChainAddress<'info> {
     )]
     pub identity_passport: Account<'info, IdentityPassport>,
     
+    #[account(
+        seeds = [b"chain", &chain_id.to_le_bytes()],
+        bump = chain_config.bump,
+        constraint = chain_config.enabled @ ChainError::ChainDisabled
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+    
     pub owner: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct VerifyCrossChainAddress<'info> {
+    #[account(
+        mut,
+        seeds = [b"identity_passport", identity_passport.did.as_bytes()],
+        bump = identity_passport.bump
+    )]
+    pub identity_passport: Account<'info, IdentityPassport>,
+    
+    #[account(
+        seeds = [b"identity_registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, IdentityRegistry>,
+    
+    #[account(
+        seeds = [b"role", &[Role::ComplianceOfficer as u8], verifier.key().as_ref()],
+        bump = compliance_role.bump
+    )]
+    pub compliance_role: Option<Account<'info, RoleMembership>>,
+    
+    pub verifier: Signer<'info>,
+}
+
 #[derive(Accounts)]
 pub struct AddCredential<'info> {
     #[account(
@@ -193,6 +264,12 @@ pub struct AddCredential<'info> {
     )]
     pub registry: Account<'info, IdentityRegistry>,
     
+    #[account(
+        seeds = [b"role", &[Role::ComplianceOfficer as u8], issuer.key().as_ref()],
+        bump = compliance_role.bump
+    )]
+    pub compliance_role: Option<Account<'info, RoleMembership>>,
+    
     pub issuer: Signer<'info>,
 }
 
@@ -207,6 +284,17 @@ pub struct InitializeIdentityRegistry<'info> {
     )]
     pub registry: Account<'info, IdentityRegistry>,
     
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::OmniflowRwa>,
+    
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(upgrade_authority.key())
+            @ IdentityError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    
+    pub upgrade_authority: Signer<'info>,
+    
     #[account(mut)]
     pub payer: Signer<'info>,
     
@@ -214,6 +302,33 @@ pub struct InitializeIdentityRegistry<'info> {
 }
 
 #[derive(Accounts)]
+pub struct ManageIdentityAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"identity_registry"],
+        bump = registry.bump,
+        has_one = authority @ IdentityError::Unauthorized
+    )]
+    pub registry: Account<'info, IdentityRegistry>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptIdentityAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"identity_registry"],
+        bump = registry.bump,
+        constraint = registry.pending_authority == Some(new_authority.key()) @ IdentityError::Unauthorized
+    )]
+    pub registry: Account<'info, IdentityRegistry>,
+    
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(target_chain: u16)]
 pub struct SyncCrossChain<'info> {
     #[account(
         seeds = [b"identity_passport", identity_passport.did.as_bytes()],
@@ -227,6 +342,13 @@ pub struct SyncCrossChain<'info> {
     )]
     pub registry: Account<'info, IdentityRegistry>,
     
+    #[account(
+        seeds = [b"chain", &target_chain.to_le_bytes()],
+        bump = chain_config.bump,
+        constraint = chain_config.enabled @ ChainError::ChainDisabled
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+    
     /// CHECK: Cross-chain bridge program
     pub bridge_program: UncheckedAccount<'info>,
     
@@ -242,15 +364,9 @@ pub fn initialize_identity_registry(
     let registry = &mut ctx.accounts.registry;
     
     registry.authority = authority;
+    registry.pending_authority = None;
     registry.total_passports = 0;
     registry.authorized_issuers = vec![authority]; // Authority is initial issuer
-    registry.supported_chains = vec![
-        "ethereum".to_string(),
-        "polygon".to_string(),
-        "bsc".to_string(),
-        "arbitrum".to_string(),
-        "optimism".to_string(),
-    ];
     registry.cross_chain_bridge = cross_chain_bridge;
     registry.paused = false;
     registry.bump = ctx.bumps.registry;
@@ -276,7 +392,12 @@ pub fn issue_identity_passport(
     // Check if issuer is authorized
     require!(
         registry.authorized_issuers.contains(&ctx.accounts.issuer.key()) || 
-        registry.authority == ctx.accounts.issuer.key(),
+        registry.authority == ctx.accounts.issuer.key() ||
+        RoleMembership::is_held_by(
+            &ctx.accounts.compliance_role,
+            &ctx.accounts.issuer.key(),
+            Role::ComplianceOfficer,
+        ),
         IdentityError::UnauthorizedIssuer
     );
     
@@ -407,7 +528,12 @@ pub fn update_identity_passport(
     // Check if issuer is authorized
     require!(
         registry.authorized_issuers.contains(&ctx.accounts.issuer.key()) || 
-        registry.authority == ctx.accounts.issuer.key(),
+        registry.authority == ctx.accounts.issuer.key() ||
+        RoleMembership::is_held_by(
+            &ctx.accounts.compliance_role,
+            &ctx.accounts.issuer.key(),
+            Role::ComplianceOfficer,
+        ),
         IdentityError::UnauthorizedIssuer
     );
     
@@ -443,24 +569,27 @@ pub fn update_identity_passport(
 
 pub fn link_cross_chain_address(
     ctx: Context<LinkCrossChainAddress>,
-    chain: String,
+    chain_id: u16,
     address: String,
     signature: Vec<u8>, // Simplified signature verification
 ) -> Result<()> {
     let passport = &mut ctx.accounts.identity_passport;
     
     require!(passport.is_active, IdentityError::PassportInactive);
-    require!(!chain.is_empty(), IdentityError::InvalidChain);
-    require!(!address.is_empty(), IdentityError::InvalidAddress);
+    require!(
+        ctx.accounts.chain_config.address_format.decode_address(&address).is_some(),
+        IdentityError::InvalidAddress
+    );
     
-    // In production, verify signature for cross-chain address ownership
-    // For now, we'll add the address with verification pending
+    // Ownership is not proven by the passport owner's claim, so the address only counts as a
+    // verified recipient once a compliance officer confirms it
     
     let clock = Clock::get()?;
     let cross_chain_address = CrossChainAddress {
-        chain,
+        chain_id,
+        chain: ctx.accounts.chain_config.name.clone(),
         address: address.clone(),
-        verified: true, // Would be false until signature verification
+        verified: false,
         timestamp: clock.unix_timestamp,
     };
     
@@ -468,6 +597,7 @@ pub fn link_cross_chain_address(
     
     emit!(CrossChainAddressLinked {
         passport: passport.key(),
+        chain_id,
         chain: passport.cross_chain_addresses.last().unwrap().chain.clone(),
         address,
     });
@@ -475,6 +605,42 @@ pub fn link_cross_chain_address(
     Ok(())
 }
 
+pub fn verify_cross_chain_address(
+    ctx: Context<VerifyCrossChainAddress>,
+    chain_id: u16,
+    address: String,
+) -> Result<()> {
+    let registry = &ctx.accounts.registry;
+    let verifier = ctx.accounts.verifier.key();
+    
+    require!(
+        registry.authorized_issuers.contains(&verifier) ||
+        registry.authority == verifier ||
+        RoleMembership::is_held_by(&ctx.accounts.compliance_role, &verifier, Role::ComplianceOfficer),
+        IdentityError::UnauthorizedIssuer
+    );
+    require!(!registry.paused, IdentityError::RegistryPaused);
+    
+    let passport = &mut ctx.accounts.identity_passport;
+    require!(passport.is_active, IdentityError::PassportInactive);
+    
+    let linked = passport
+        .cross_chain_addresses
+        .iter_mut()
+        .find(|linked| linked.chain_id == chain_id && linked.address == address)
+        .ok_or(IdentityError::AddressNotLinked)?;
+    linked.verified = true;
+    
+    emit!(CrossChainAddressVerified {
+        passport: passport.key(),
+        chain_id,
+        address,
+        verified_by: verifier,
+    });
+    
+    Ok(())
+}
+
 pub fn add_credential(
     ctx: Context<AddCredential>,
     credential_id: String,
@@ -485,7 +651,12 @@ pub fn add_credential(
     // Check if issuer is authorized
     require!(
         registry.authorized_issuers.contains(&ctx.accounts.issuer.key()) || 
-        registry.authority == ctx.accounts.issuer.key(),
+        registry.authority == ctx.accounts.issuer.key() ||
+        RoleMembership::is_held_by(
+            &ctx.accounts.compliance_role,
+            &ctx.accounts.issuer.key(),
+            Role::ComplianceOfficer,
+        ),
         IdentityError::UnauthorizedIssuer
     );
     
@@ -507,7 +678,7 @@ pub fn add_credential(
 
 pub fn sync_cross_chain(
     ctx: Context<SyncCrossChain>,
-    target_chain: String,
+    target_chain: u16,
     target_contract: String,
 ) -> Result<()> {
     let registry = &ctx.accounts.registry;
@@ -533,6 +704,55 @@ pub fn sync_cross_chain(
     Ok(())
 }
 
+pub fn propose_identity_authority(
+    ctx: Context<ManageIdentityAuthority>,
+    new_authority: Pubkey,
+) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.pending_authority = Some(new_authority);
+    
+    emit!(AuthorityTransferProposed {
+        registry: registry.key(),
+        authority: registry.authority,
+        pending_authority: new_authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+pub fn accept_identity_authority(ctx: Context<AcceptIdentityAuthority>) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let previous_authority = registry.authority;
+    registry.authority = ctx.accounts.new_authority.key();
+    registry.pending_authority = None;
+    
+    emit!(AuthorityTransferAccepted {
+        registry: registry.key(),
+        previous_authority,
+        new_authority: registry.authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+pub fn cancel_identity_authority_transfer(ctx: Context<ManageIdentityAuthority>) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let cancelled_authority = registry
+        .pending_authority
+        .take()
+        .ok_or(IdentityError::NoPendingAuthority)?;
+    
+    emit!(AuthorityTransferCancelled {
+        registry: registry.key(),
+        cancelled_authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
 // Events
 #[event]
 pub struct IdentityRegistryInitialized {
@@ -561,10 +781,19 @@ pub struct IdentityPassportUpdated {
 #[event]
 pub struct CrossChainAddressLinked {
     pub passport: Pubkey,
+    pub chain_id: u16,
     pub chain: String,
     pub address: String,
 }
 
+#[event]
+pub struct CrossChainAddressVerified {
+    pub passport: Pubkey,
+    pub chain_id: u16,
+    pub address: String,
+    pub verified_by: Pubkey,
+}
+
 #[event]
 pub struct CredentialAdded {
     pub passport: Pubkey,
@@ -576,7 +805,7 @@ pub struct CredentialAdded {
 pub struct CrossChainSync {
     pub passport: Pubkey,
     pub did: String,
-    pub target_chain: String,
+    pub target_chain: u16,
     pub target_contract: String,
     pub kyc_level: KYCLevel,
     pub investor_tier: InvestorTier,
@@ -602,4 +831,8 @@ pub enum IdentityError {
     InvalidAddress,
     #[msg("Unauthorized")]
     Unauthorized,
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+    #[msg("Address is not linked to the passport")]
+    AddressNotLinked,
 }
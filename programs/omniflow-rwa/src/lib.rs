@@ -2,14 +2,78 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Mint};
 use mpl_token_metadata::instruction as mpl_instruction;
 use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
+use sha2::{Digest, Sha256};
 use solana_program::program::invoke;
 
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 
+// Registry pause flags, one bit per operation class
+pub const PAUSE_REGISTRATION: u8 = 1 << 0;
+pub const PAUSE_MINTING: u8 = 1 << 1;
+pub const PAUSE_OUTBOUND_BRIDGE: u8 = 1 << 2;
+pub const PAUSE_INBOUND_BRIDGE: u8 = 1 << 3;
+pub const PAUSE_METADATA_UPDATES: u8 = 1 << 4;
+/// Redemption requests and carbon retirements
+pub const PAUSE_REDEMPTIONS: u8 = 1 << 5;
+pub const PAUSE_DISTRIBUTIONS: u8 = 1 << 6;
+pub const PAUSE_FORCED_TRANSFERS: u8 = 1 << 7;
+pub const PAUSE_ALL: u8 = PAUSE_REGISTRATION
+    | PAUSE_MINTING
+    | PAUSE_OUTBOUND_BRIDGE
+    | PAUSE_INBOUND_BRIDGE
+    | PAUSE_METADATA_UPDATES
+    | PAUSE_REDEMPTIONS
+    | PAUSE_DISTRIBUTIONS
+    | PAUSE_FORCED_TRANSFERS;
+
+/// Lowest asset id callers may choose themselves. Ids below it are handed out in order
+/// by `Registry::next_asset_id`, so a chosen id can never collide with or exhaust the counter.
+pub const CALLER_ASSET_ID_BASE: u64 = 1 << 63;
+
+/// Seconds relayers have to deliver an outbound transfer before an Admin may cancel it
+pub const OUTBOUND_TRANSFER_TIMEOUT: i64 = 7 * 24 * 60 * 60;
+
+pub mod allocation;
+pub mod asset_index;
+pub mod attributes;
+pub mod carbon;
+pub mod chains;
+#[cfg(not(target_os = "solana"))]
+pub mod client;
+pub mod distribution;
+pub mod documents;
 pub mod errors;
 pub mod identity;
+pub mod merkle;
+pub mod multisig;
+pub mod price_feed;
+pub mod redemption;
+pub mod reserve;
+pub mod roles;
+pub mod sale;
+pub mod snapshot;
+pub mod timelock;
+pub mod transfer_agent;
+pub mod valuation;
+pub use allocation::*;
+pub use asset_index::*;
+pub use attributes::*;
+pub use carbon::*;
+pub use chains::*;
+pub use distribution::*;
+pub use documents::*;
 pub use errors::*;
 pub use identity::*;
+pub use multisig::*;
+pub use price_feed::*;
+pub use redemption::*;
+pub use reserve::*;
+pub use roles::*;
+pub use sale::*;
+pub use snapshot::*;
+pub use timelock::*;
+pub use transfer_agent::*;
+pub use valuation::*;
 
 #[program]
 pub mod omniflow_rwa {
@@ -24,10 +88,17 @@ pub mod omniflow_rwa {
     ) -> Result<()> {
         let registry = &mut ctx.accounts.registry;
         registry.authority = authority;
+        registry.pending_authority = None;
         registry.total_assets = 0;
+        registry.next_asset_id = 1;
+        registry.indexed_assets = 0;
+        registry.allocate_asset_ids = false;
         registry.wormhole_bridge = wormhole_bridge;
         registry.layerzero_endpoint = layerzero_endpoint;
-        registry.paused = false;
+        registry.outbound_sequence = 0;
+        registry.pending_outbound_transfers = 0;
+        registry.pause_flags = 0;
+        registry.guardian = None;
         registry.bump = ctx.bumps.registry;
 
         emit!(RegistryInitialized {
@@ -49,27 +120,89 @@ pub mod omniflow_rwa {
         total_value: u64,
         total_supply: u64,
         chain_id: u16, // Target chain for cross-chain operations
+        attributes: Option<AssetAttributeData>,
     ) -> Result<()> {
-        require!(!ctx.accounts.registry.paused, ErrorCode::RegistryPaused);
+        require_role(
+            &ctx.accounts.registry,
+            &ctx.accounts.owner.key(),
+            &ctx.accounts.issuer_role,
+            Role::Issuer,
+        )?;
+        ctx.accounts.registry.require_not_paused(PAUSE_REGISTRATION)?;
         require!(metadata_uri.len() <= 200, ErrorCode::MetadataUriTooLong);
 
+        match (&attributes, &mut ctx.accounts.asset_attributes) {
+            (Some(attributes), Some(asset_attributes)) => {
+                attributes.validate(asset_type)?;
+                asset_attributes.asset_id = asset_id;
+                asset_attributes.attributes = attributes.clone();
+                asset_attributes.updated_at = Clock::get()?.unix_timestamp;
+                asset_attributes.bump = ctx.bumps.asset_attributes.unwrap();
+            }
+            (None, None) => {}
+            _ => return err!(AttributeError::AttributesAccountMismatch),
+        }
+
         let asset = &mut ctx.accounts.asset;
         let registry = &mut ctx.accounts.registry;
+        if asset_id < CALLER_ASSET_ID_BASE {
+            require!(asset_id == registry.next_asset_id, ErrorCode::AssetIdNotAllocated);
+            // Cannot overflow, the id is below the caller range
+            registry.next_asset_id = asset_id + 1;
+        } else {
+            require!(!registry.allocate_asset_ids, ErrorCode::AssetIdNotAllocated);
+        }
 
         asset.asset_id = asset_id;
         asset.asset_type = asset_type;
         asset.owner = ctx.accounts.owner.key();
+        asset.pending_owner = None;
+        asset.owner_transfer_approved = false;
         asset.metadata_uri = metadata_uri.clone();
         asset.kyc_level = kyc_level;
         asset.total_value = total_value;
         asset.total_supply = total_supply;
         asset.circulating_supply = 0;
         asset.is_active = true;
+        asset.paused = false;
         asset.created_at = Clock::get()?.unix_timestamp;
         asset.chain_id = chain_id;
+        asset.require_verified_recipient = false;
+        asset.index_position = registry.indexed_assets;
+        asset.retirement_count = 0;
+        asset.redemption_count = 0;
+        asset.distribution_count = 0;
+        asset.snapshot_id = 0;
+        asset.snapshot_authority = None;
+        asset.allocation_count = 0;
+        asset.valuation_required = false;
+        asset.price_feed_required = false;
+        asset.reserve_required = false;
+        asset.metadata_version = 0;
+        asset.metadata_content_hash = None;
+        asset.voided_supply = 0;
+        asset.pending_redemptions = 0;
+        asset.open_distributions = 0;
+        asset.open_allocation_rounds = 0;
+        asset.sale_open = false;
+        asset.retired = false;
+        asset.mint = Pubkey::default();
+        asset.paused_by_registry = false;
         asset.bump = ctx.bumps.asset;
 
         registry.total_assets = registry.total_assets.checked_add(1).unwrap();
+        registry.indexed_assets = registry.indexed_assets.checked_add(1).unwrap();
+
+        let asset_index_page = &mut ctx.accounts.asset_index_page;
+        require!(!asset_index_page.is_full(), AssetIndexError::WrongIndexPage);
+        asset_index_page.page = AssetIndexPage::page_of(asset.index_position);
+        asset_index_page.bump = ctx.bumps.asset_index_page;
+        asset_index_page.entries.push(AssetIndexEntry {
+            asset_id,
+            asset_type,
+            owner: asset.owner,
+            status: AssetStatus::Active,
+        });
 
         // Emit cross-chain event for Wormhole/LayerZero
         emit!(RWAAssetRegistered {
@@ -81,6 +214,7 @@ pub mod omniflow_rwa {
             total_value,
             total_supply,
             chain_id,
+            attributes,
             timestamp: Clock::get()?.unix_timestamp,
         });
 
@@ -89,7 +223,7 @@ pub mod omniflow_rwa {
             emit!(CrossChainEvent {
                 event_type: CrossChainEventType::AssetRegistered,
                 asset_id,
-                source_chain: 1, // Solana chain ID
+                source_chain: SOLANA_CHAIN_ID,
                 target_chain: chain_id,
                 bridge_address: wormhole_bridge,
                 data: asset_id.to_le_bytes().to_vec(),
@@ -105,15 +239,16 @@ pub mod omniflow_rwa {
         asset_id: u64,
         amount: u64,
     ) -> Result<()> {
+        ctx.accounts.registry.require_not_paused(PAUSE_MINTING)?;
+
         let asset = &mut ctx.accounts.asset;
-        require!(asset.is_active, ErrorCode::AssetInactive);
-        require!(
-            asset.circulating_supply.checked_add(amount).unwrap() <= asset.total_supply,
-            ErrorCode::ExceedsMaxSupply
-        );
+        asset.require_operational()?;
+        asset.require_kyc(&ctx.accounts.recipient_passport)?;
+        require_current_valuation(asset, &ctx.accounts.valuation)?;
 
         // Update circulating supply
-        asset.circulating_supply = asset.circulating_supply.checked_add(amount).unwrap();
+        asset.record_mint(amount)?;
+        require_reserves(asset, &ctx.accounts.reserve)?;
 
         // Mint tokens to user
         let cpi_accounts = token::MintTo {
@@ -152,8 +287,30 @@ pub mod omniflow_rwa {
         target_chain: u16,
         target_recipient: [u8; 32],
     ) -> Result<()> {
+        ctx.accounts.registry.require_not_paused(PAUSE_OUTBOUND_BRIDGE)?;
+        require!(amount > 0, ErrorCode::InvalidTransferAmount);
+
         let asset = &ctx.accounts.asset;
-        require!(asset.is_active, ErrorCode::AssetInactive);
+        asset.require_operational()?;
+
+        // Reject recipients that could never redeem the tokens on the target chain
+        let address_format = ctx.accounts.target_chain_config.address_format;
+        require!(
+            address_format.is_valid_recipient(&target_recipient),
+            ChainError::InvalidRecipient
+        );
+
+        if asset.require_verified_recipient {
+            let passport = ctx
+                .accounts
+                .sender_passport
+                .as_ref()
+                .ok_or(ChainError::RecipientNotVerified)?;
+            require!(
+                passport.has_verified_address(target_chain, address_format, &target_recipient),
+                ChainError::RecipientNotVerified
+            );
+        }
 
         // Burn tokens on Solana
         let cpi_accounts = token::Burn {
@@ -167,20 +324,38 @@ pub mod omniflow_rwa {
 
         token::burn(cpi_ctx, amount)?;
 
+        // Track the transfer until the relayer confirms delivery
+        let registry = &mut ctx.accounts.registry;
+        let sequence = registry.outbound_sequence;
+        registry.outbound_sequence = sequence.checked_add(1).unwrap();
+        registry.pending_outbound_transfers =
+            registry.pending_outbound_transfers.checked_add(1).unwrap();
+
+        let outbound_transfer = &mut ctx.accounts.outbound_transfer;
+        outbound_transfer.sequence = sequence;
+        outbound_transfer.asset_id = asset_id;
+        outbound_transfer.amount = amount;
+        outbound_transfer.target_chain = target_chain;
+        outbound_transfer.recipient = target_recipient;
+        outbound_transfer.sender = ctx.accounts.owner.key();
+        outbound_transfer.initiated_at = Clock::get()?.unix_timestamp;
+        outbound_transfer.bump = ctx.bumps.outbound_transfer;
+
         // Create cross-chain message
         let transfer_data = CrossChainTransferData {
             asset_id,
             amount,
             recipient: target_recipient,
-            source_chain: 1, // Solana
+            source_chain: SOLANA_CHAIN_ID,
             kyc_level: asset.kyc_level,
             metadata_uri: asset.metadata_uri.clone(),
         };
 
         emit!(CrossChainTransferInitiated {
+            sequence,
             asset_id,
             amount,
-            source_chain: 1,
+            source_chain: SOLANA_CHAIN_ID,
             target_chain,
             sender: ctx.accounts.owner.key(),
             recipient: target_recipient,
@@ -191,7 +366,7 @@ pub mod omniflow_rwa {
         emit!(CrossChainEvent {
             event_type: CrossChainEventType::TransferInitiated,
             asset_id,
-            source_chain: 1,
+            source_chain: SOLANA_CHAIN_ID,
             target_chain,
             bridge_address: ctx.accounts.registry.wormhole_bridge.unwrap_or_default(),
             data: borsh::to_vec(&transfer_data)?,
@@ -208,8 +383,21 @@ pub mod omniflow_rwa {
         source_chain: u16,
         transfer_hash: [u8; 32],
     ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry,
+            &ctx.accounts.payer.key(),
+            &ctx.accounts.relayer_role,
+            Role::BridgeRelayer,
+        )?;
+        ctx.accounts.registry.require_not_paused(PAUSE_INBOUND_BRIDGE)?;
+
         let asset = &mut ctx.accounts.asset;
-        require!(asset.is_active, ErrorCode::AssetInactive);
+        asset.require_operational()?;
+        require_current_valuation(asset, &ctx.accounts.valuation)?;
+
+        // Update circulating supply
+        asset.record_mint(amount)?;
+        require_reserves(asset, &ctx.accounts.reserve)?;
 
         // Mint tokens on Solana
         let cpi_accounts = token::MintTo {
@@ -230,14 +418,11 @@ pub mod omniflow_rwa {
 
         token::mint_to(cpi_ctx, amount)?;
 
-        // Update circulating supply
-        asset.circulating_supply = asset.circulating_supply.checked_add(amount).unwrap();
-
         emit!(CrossChainTransferCompleted {
             asset_id,
             amount,
             source_chain,
-            target_chain: 1, // Solana
+            target_chain: SOLANA_CHAIN_ID,
             recipient: ctx.accounts.recipient.key(),
             transfer_hash,
             timestamp: Clock::get()?.unix_timestamp,
@@ -246,223 +431,1881 @@ pub mod omniflow_rwa {
         Ok(())
     }
 
-    /// Update asset metadata (owner only)
+    /// Release a delivered outbound transfer (BridgeRelayer)
+    pub fn confirm_outbound_transfer(ctx: Context<ConfirmOutboundTransfer>) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry,
+            &ctx.accounts.relayer.key(),
+            &ctx.accounts.relayer_role,
+            Role::BridgeRelayer,
+        )?;
+
+        let registry = &mut ctx.accounts.registry;
+        registry.pending_outbound_transfers =
+            registry.pending_outbound_transfers.checked_sub(1).unwrap();
+
+        let outbound_transfer = &ctx.accounts.outbound_transfer;
+        emit!(OutboundTransferConfirmed {
+            sequence: outbound_transfer.sequence,
+            asset_id: outbound_transfer.asset_id,
+            amount: outbound_transfer.amount,
+            target_chain: outbound_transfer.target_chain,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Reissue the burned tokens of an outbound transfer that was never delivered (Admin, after the timeout)
+    pub fn cancel_outbound_transfer(ctx: Context<CancelOutboundTransfer>) -> Result<()> {
+        let cancelled_by = ctx.accounts.authority.key();
+        require_role(&ctx.accounts.registry, &cancelled_by, &ctx.accounts.admin_role, Role::Admin)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let outbound_transfer = &ctx.accounts.outbound_transfer;
+        require!(
+            now >= outbound_transfer.initiated_at.saturating_add(OUTBOUND_TRANSFER_TIMEOUT),
+            ErrorCode::OutboundTransferNotExpired
+        );
+
+        // The burn never reduced circulating supply, so reissuing leaves it unchanged
+        let asset = &ctx.accounts.asset;
+        let asset_seeds = &[b"asset".as_ref(), &asset.asset_id.to_le_bytes(), &[asset.bump]];
+        let signer_seeds = &[&asset_seeds[..]];
+        let cpi_accounts = token::MintTo {
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.sender_token_account.to_account_info(),
+            authority: ctx.accounts.asset.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::mint_to(
+            CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds),
+            outbound_transfer.amount,
+        )?;
+
+        let registry = &mut ctx.accounts.registry;
+        registry.pending_outbound_transfers =
+            registry.pending_outbound_transfers.checked_sub(1).unwrap();
+
+        emit!(OutboundTransferCancelled {
+            sequence: outbound_transfer.sequence,
+            asset_id: outbound_transfer.asset_id,
+            amount: outbound_transfer.amount,
+            target_chain: outbound_transfer.target_chain,
+            cancelled_by,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Update asset metadata, recording a new metadata revision (owner only).
+    /// Lowering the KYC level also needs the registry authority and an unissued asset;
+    /// live offerings go through the timelocked `SetAssetKycLevel` action instead.
     pub fn update_asset_metadata(
         ctx: Context<UpdateAssetMetadata>,
         asset_id: u64,
         new_metadata_uri: String,
+        content_hash: [u8; 32],
         new_kyc_level: Option<KYCLevel>,
+        reason: String,
     ) -> Result<()> {
+        ctx.accounts.registry.require_not_paused(PAUSE_METADATA_UPDATES)?;
         require!(new_metadata_uri.len() <= 200, ErrorCode::MetadataUriTooLong);
+        require!(reason.len() <= 100, ErrorCode::MetadataReasonTooLong);
+        require!(content_hash != [0; 32], ErrorCode::MissingContentHash);
 
+        let authority_signed = ctx.accounts.registry_authority.is_some();
         let asset = &mut ctx.accounts.asset;
-        asset.metadata_uri = new_metadata_uri.clone();
-        
+        asset.require_operational()?;
+
+        let previous_kyc_level = asset.kyc_level;
         if let Some(kyc_level) = new_kyc_level {
+            if (kyc_level as u8) < (previous_kyc_level as u8) {
+                require!(
+                    asset.circulating_supply == 0,
+                    ErrorCode::KycDowngradeRequiresTimelock
+                );
+                require!(authority_signed, ErrorCode::Unauthorized);
+            }
             asset.kyc_level = kyc_level;
         }
 
+        let previous_uri_hash: [u8; 32] = Sha256::digest(asset.metadata_uri.as_bytes()).into();
+        let previous_content_hash = asset.metadata_content_hash;
+        let metadata_version = asset.metadata_version.checked_add(1).unwrap();
+        asset.metadata_uri = new_metadata_uri.clone();
+        asset.metadata_content_hash = Some(content_hash);
+        asset.metadata_version = metadata_version;
+
+        let now = Clock::get()?.unix_timestamp;
+        let revision = &mut ctx.accounts.metadata_revision;
+        revision.asset_id = asset_id;
+        revision.version = metadata_version;
+        revision.uri_hash = Sha256::digest(new_metadata_uri.as_bytes()).into();
+        revision.content_hash = content_hash;
+        revision.previous_uri_hash = previous_uri_hash;
+        revision.previous_content_hash = previous_content_hash;
+        revision.kyc_level = asset.kyc_level;
+        revision.previous_kyc_level = previous_kyc_level;
+        revision.reason = reason.clone();
+        revision.updated_by = ctx.accounts.owner.key();
+        revision.updated_at = now;
+        revision.bump = ctx.bumps.metadata_revision;
+
         emit!(AssetMetadataUpdated {
             asset_id,
+            metadata_version,
             new_metadata_uri,
+            content_hash,
+            previous_uri_hash,
             new_kyc_level,
+            reason,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Require outbound recipients to be verified addresses of the sender's passport (owner only, when no asset policy timelock applies)
+    pub fn set_recipient_policy(
+        ctx: Context<SetRecipientPolicy>,
+        asset_id: u64,
+        require_verified_recipient: bool,
+    ) -> Result<()> {
+        let timelock_config = &ctx.accounts.timelock_config;
+        if !timelock_config.data_is_empty() {
+            let timelock_config = Account::<TimelockConfig>::try_from(timelock_config)?;
+            require!(
+                timelock_config.delay(TimelockActionClass::AssetPolicy) == 0,
+                ErrorCode::TimelockRequired
+            );
+        }
+
+        ctx.accounts.asset.require_verified_recipient = require_verified_recipient;
+
+        emit!(RecipientPolicyUpdated {
+            asset_id,
+            require_verified_recipient,
             timestamp: Clock::get()?.unix_timestamp,
         });
 
         Ok(())
     }
 
-    /// Pause/unpause registry (authority only)
+    /// Pause/unpause every operation (multisig, or Pauser before one exists; guardian can only pause)
     pub fn set_registry_pause(
         ctx: Context<SetRegistryPause>,
         paused: bool,
     ) -> Result<()> {
-        ctx.accounts.registry.paused = paused;
+        let pause_flags = if paused { PAUSE_ALL } else { 0 };
+        let action = PrivilegedAction::SetRegistryPause { paused };
+        apply_pause_flags(ctx.accounts, &action, pause_flags)
+    }
+
+    /// Pause/unpause individual operations (multisig, or Pauser before one exists; guardian can only pause)
+    pub fn set_pause_flags(ctx: Context<SetRegistryPause>, pause_flags: u8) -> Result<()> {
+        require!(pause_flags & !PAUSE_ALL == 0, ErrorCode::InvalidPauseFlags);
+
+        let action = PrivilegedAction::SetPauseFlags { pause_flags };
+        apply_pause_flags(ctx.accounts, &action, pause_flags)
+    }
+
+    /// Pause/unpause a single asset (owner or Pauser; guardian can only pause).
+    /// A pause by the guardian or a Pauser can only be lifted by a Pauser.
+    pub fn set_asset_pause(
+        ctx: Context<SetAssetPause>,
+        asset_id: u64,
+        paused: bool,
+    ) -> Result<()> {
+        let signer = ctx.accounts.authority.key();
+        let registry = &ctx.accounts.registry;
+        let asset = &mut ctx.accounts.asset;
+        let is_pauser = RoleMembership::is_held_by(&ctx.accounts.pauser_role, &signer, Role::Pauser);
+        let is_guardian = registry.guardian == Some(signer);
+
+        if paused {
+            require!(
+                signer == asset.owner || is_pauser || is_guardian,
+                RoleError::MissingRole
+            );
+            asset.paused_by_registry |= is_pauser || is_guardian;
+        } else if asset.paused_by_registry {
+            require!(is_pauser, ErrorCode::AssetPausedByRegistry);
+            asset.paused_by_registry = false;
+        } else {
+            require!(signer == asset.owner || is_pauser, RoleError::MissingRole);
+        }
+
+        asset.paused = paused;
 
-        emit!(RegistryPauseChanged {
+        emit!(AssetPauseChanged {
+            asset_id,
             paused,
+            changed_by: signer,
             timestamp: Clock::get()?.unix_timestamp,
         });
 
         Ok(())
     }
-}
 
-// Account Structs
-#[derive(Accounts)]
-pub struct InitializeRegistry<'info> {
-    #[account(
-        init,
-        payer = payer,
-        space = 8 + Registry::INIT_SPACE,
-        seeds = [b"registry"],
-        bump
-    )]
-    pub registry: Account<'info, Registry>,
-    
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    
-    pub system_program: Program<'info, System>,
-}
+    /// Deactivate an asset, blocking minting and bridging (owner or authority)
+    pub fn deactivate_asset(ctx: Context<ManageAssetLifecycle>, asset_id: u64) -> Result<()> {
+        let asset = &mut ctx.accounts.asset;
+        require!(asset.is_active, ErrorCode::AssetInactive);
 
-#[derive(Accounts)]
-#[instruction(asset_id: u64)]
-pub struct RegisterRWAAsset<'info> {
-    #[account(
-        init,
-        payer = owner,
-        space = 8 + RWAAsset::INIT_SPACE,
-        seeds = [b"asset", &asset_id.to_le_bytes()],
-        bump
-    )]
-    pub asset: Account<'info, RWAAsset>,
-    
-    #[account(
-        mut,
-        seeds = [b"registry"],
-        bump = registry.bump
-    )]
-    pub registry: Account<'info, Registry>,
-    
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    
-    pub system_program: Program<'info, System>,
-}
+        asset.is_active = false;
+        ctx.accounts
+            .asset_index_page
+            .entry_mut(asset.index_position)?
+            .status = AssetStatus::Inactive;
 
-#[derive(Accounts)]
-#[instruction(asset_id: u64)]
-pub struct MintRWATokens<'info> {
-    #[account(
-        mut,
-        seeds = [b"asset", &asset_id.to_le_bytes()],
-        bump = asset.bump,
-        has_one = owner
-    )]
-    pub asset: Account<'info, RWAAsset>,
-    
-    #[account(mut)]
-    pub mint: Account<'info, Mint>,
-    
-    #[account(
-        init_if_needed,
-        payer = owner,
-        associated_token::mint = mint,
-        associated_token::authority = recipient
-    )]
-    pub token_account: Account<'info, TokenAccount>,
-    
-    /// CHECK: Recipient can be any account
-    pub recipient: AccountInfo<'info>,
-    
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
-}
+        emit!(AssetDeactivated {
+            asset_id,
+            deactivated_by: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-#[derive(Accounts)]
-#[instruction(asset_id: u64)]
-pub struct InitiateCrossChainTransfer<'info> {
-    #[account(
-        seeds = [b"asset", &asset_id.to_le_bytes()],
-        bump = asset.bump
-    )]
-    pub asset: Account<'info, RWAAsset>,
-    
-    #[account(
-        seeds = [b"registry"],
-        bump = registry.bump
-    )]
-    pub registry: Account<'info, Registry>,
-    
-    #[account(mut)]
-    pub mint: Account<'info, Mint>,
-    
-    #[account(
-        mut,
-        associated_token::mint = mint,
-        associated_token::authority = owner
-    )]
-    pub from_token_account: Account<'info, TokenAccount>,
-    
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    
-    pub token_program: Program<'info, Token>,
-}
+        Ok(())
+    }
 
-#[derive(Accounts)]
-#[instruction(asset_id: u64)]
-pub struct CompleteCrossChainTransfer<'info> {
-    #[account(
-        mut,
-        seeds = [b"asset", &asset_id.to_le_bytes()],
-        bump = asset.bump
-    )]
-    pub asset: Account<'info, RWAAsset>,
-    
-    #[account(mut)]
-    pub mint: Account<'info, Mint>,
-    
-    #[account(
-        init_if_needed,
-        payer = payer,
-        associated_token::mint = mint,
-        associated_token::authority = recipient
-    )]
-    pub to_token_account: Account<'info, TokenAccount>,
-    
-    /// CHECK: Recipient can be any account
-    pub recipient: AccountInfo<'info>,
-    
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
-}
+    /// Reactivate a deactivated asset (owner or authority)
+    pub fn reactivate_asset(ctx: Context<ManageAssetLifecycle>, asset_id: u64) -> Result<()> {
+        let asset = &mut ctx.accounts.asset;
+        require!(!asset.retired, ErrorCode::AssetAlreadyRetired);
+        require!(!asset.is_active, ErrorCode::AssetAlreadyActive);
 
-#[derive(Accounts)]
-#[instruction(asset_id: u64)]
-pub struct UpdateAssetMetadata<'info> {
-    #[account(
-        mut,
-        seeds = [b"asset", &asset_id.to_le_bytes()],
-        bump = asset.bump,
-        has_one = owner
-    )]
-    pub asset: Account<'info, RWAAsset>,
-    
-    pub owner: Signer<'info>,
-}
+        asset.is_active = true;
+        ctx.accounts
+            .asset_index_page
+            .entry_mut(asset.index_position)?
+            .status = AssetStatus::Active;
 
-#[derive(Accounts)]
-pub struct SetRegistryPause<'info> {
-    #[account(
-        mut,
-        seeds = [b"registry"],
-        bump = registry.bump,
-        has_one = authority
-    )]
-    pub registry: Account<'info, Registry>,
-    
-    pub authority: Signer<'info>,
-}
+        emit!(AssetReactivated {
+            asset_id,
+            reactivated_by: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-// Data Structs
-#[account]
-#[derive(InitSpace)]
-pub struct Registry {
-    pub authority: Pubkey,
+        Ok(())
+    }
+
+    /// Permanently retire an asset with no circulating supply or open obligations (owner or authority)
+    pub fn retire_asset(ctx: Context<RetireAsset>, asset_id: u64) -> Result<()> {
+        let asset = &mut ctx.accounts.asset;
+        require!(!asset.retired, ErrorCode::AssetAlreadyRetired);
+        require!(asset.circulating_supply == 0, ErrorCode::CirculatingSupplyNotZero);
+        require!(
+            asset.pending_redemptions == 0
+                && asset.open_distributions == 0
+                && asset.open_allocation_rounds == 0,
+            ErrorCode::AssetHasOpenObligations
+        );
+        if asset.sale_open {
+            // A failed sale that has returned every payment no longer holds buyer funds
+            let sale = ctx
+                .accounts
+                .sale
+                .as_ref()
+                .ok_or(ErrorCode::AssetHasOpenObligations)?;
+            require!(
+                sale.has_failed(Clock::get()?.unix_timestamp) && sale.refunded == sale.raised,
+                ErrorCode::AssetHasOpenObligations
+            );
+            asset.sale_open = false;
+        }
+
+        // The account stays as a tombstone, keeping the mint authority PDA out of reach
+        asset.is_active = false;
+        asset.retired = true;
+
+        let registry = &mut ctx.accounts.registry;
+        registry.total_assets = registry.total_assets.checked_sub(1).unwrap();
+        ctx.accounts
+            .asset_index_page
+            .entry_mut(asset.index_position)?
+            .status = AssetStatus::Retired;
+
+        emit!(AssetRetired {
+            asset_id,
+            retired_by: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Propose a new asset owner (owner only)
+    pub fn propose_asset_owner(
+        ctx: Context<ManageAssetOwner>,
+        asset_id: u64,
+        new_owner: Pubkey,
+    ) -> Result<()> {
+        let asset = &mut ctx.accounts.asset;
+        asset.pending_owner = Some(new_owner);
+        asset.owner_transfer_approved = false;
+
+        emit!(AssetOwnerTransferProposed {
+            asset_id,
+            owner: asset.owner,
+            pending_owner: new_owner,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Approve a pending owner transfer, required for Institutional assets (authority only)
+    pub fn approve_asset_owner(ctx: Context<ApproveAssetOwner>, asset_id: u64) -> Result<()> {
+        let asset = &mut ctx.accounts.asset;
+        let pending_owner = asset.pending_owner.ok_or(ErrorCode::NoPendingOwner)?;
+        asset.owner_transfer_approved = true;
+
+        emit!(AssetOwnerTransferApproved {
+            asset_id,
+            pending_owner,
+            approved_by: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Accept a pending owner transfer (pending owner only)
+    pub fn accept_asset_owner(ctx: Context<AcceptAssetOwner>, asset_id: u64) -> Result<()> {
+        let asset = &mut ctx.accounts.asset;
+        if asset.kyc_level == KYCLevel::Institutional {
+            require!(asset.owner_transfer_approved, ErrorCode::OwnerTransferNotApproved);
+        }
+
+        let previous_owner = asset.owner;
+        asset.owner = ctx.accounts.new_owner.key();
+        asset.pending_owner = None;
+        asset.owner_transfer_approved = false;
+        ctx.accounts
+            .asset_index_page
+            .entry_mut(asset.index_position)?
+            .owner = asset.owner;
+
+        emit!(AssetOwnerTransferAccepted {
+            asset_id,
+            previous_owner,
+            new_owner: asset.owner,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Cancel a pending owner transfer (owner only)
+    pub fn cancel_asset_owner_transfer(ctx: Context<ManageAssetOwner>, asset_id: u64) -> Result<()> {
+        let asset = &mut ctx.accounts.asset;
+        let cancelled_owner = asset.pending_owner.take().ok_or(ErrorCode::NoPendingOwner)?;
+        asset.owner_transfer_approved = false;
+
+        emit!(AssetOwnerTransferCancelled {
+            asset_id,
+            cancelled_owner,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Require new assets to take the next registry-allocated id, closing the caller id range (multisig, or authority before one exists)
+    pub fn set_asset_id_allocation(
+        ctx: Context<SetAssetIdAllocation>,
+        allocate_asset_ids: bool,
+    ) -> Result<()> {
+        let action = PrivilegedAction::SetAssetIdAllocation { allocate_asset_ids };
+        if !consume_proposal(
+            &ctx.accounts.multisig,
+            &mut ctx.accounts.multisig_proposal,
+            &action,
+        )? {
+            require_keys_eq!(
+                ctx.accounts.registry.authority,
+                ctx.accounts.authority.key(),
+                ErrorCode::Unauthorized
+            );
+        }
+
+        let registry = &mut ctx.accounts.registry;
+        registry.allocate_asset_ids = allocate_asset_ids;
+
+        emit!(AssetIdAllocationChanged {
+            allocate_asset_ids,
+            next_asset_id: registry.next_asset_id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Map an issuer's external reference to its asset id (owner only)
+    pub fn register_asset_reference(
+        ctx: Context<RegisterAssetReference>,
+        asset_id: u64,
+        external_reference: String,
+    ) -> Result<()> {
+        require!(
+            !external_reference.is_empty() && external_reference.len() <= 64,
+            ErrorCode::InvalidExternalReference
+        );
+
+        let asset_reference = &mut ctx.accounts.asset_reference;
+        asset_reference.issuer = ctx.accounts.owner.key();
+        asset_reference.asset_id = asset_id;
+        asset_reference.external_reference = external_reference.clone();
+        asset_reference.bump = ctx.bumps.asset_reference;
+
+        emit!(AssetReferenceRegistered {
+            asset_id,
+            issuer: asset_reference.issuer,
+            external_reference,
+        });
+
+        Ok(())
+    }
+
+    /// Set the guardian key that can pause but not unpause (multisig, or authority before one exists)
+    pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Option<Pubkey>) -> Result<()> {
+        let action = PrivilegedAction::SetGuardian { guardian };
+        if !consume_proposal(
+            &ctx.accounts.multisig,
+            &mut ctx.accounts.multisig_proposal,
+            &action,
+        )? {
+            require_keys_eq!(
+                ctx.accounts.registry.authority,
+                ctx.accounts.authority.key(),
+                ErrorCode::Unauthorized
+            );
+        }
+
+        let registry = &mut ctx.accounts.registry;
+        let previous_guardian = registry.guardian;
+        registry.guardian = guardian;
+
+        emit!(GuardianUpdated {
+            previous_guardian,
+            guardian,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Set or clear the bridge endpoints (multisig, or authority before one exists; only when no bridge timelock applies)
+    pub fn update_bridge_config(
+        ctx: Context<UpdateBridgeConfig>,
+        wormhole_bridge: EndpointUpdate,
+        layerzero_endpoint: EndpointUpdate,
+    ) -> Result<()> {
+        let action = PrivilegedAction::UpdateBridgeConfig {
+            wormhole_bridge,
+            layerzero_endpoint,
+        };
+        if !consume_proposal(
+            &ctx.accounts.multisig,
+            &mut ctx.accounts.multisig_proposal,
+            &action,
+        )? {
+            require_keys_eq!(
+                ctx.accounts.registry.authority,
+                ctx.accounts.authority.key(),
+                ErrorCode::Unauthorized
+            );
+        }
+
+        let timelock_config = &ctx.accounts.timelock_config;
+        if !timelock_config.data_is_empty() {
+            let timelock_config = Account::<TimelockConfig>::try_from(timelock_config)?;
+            require!(
+                timelock_config.delay(TimelockActionClass::BridgeConfig) == 0,
+                ErrorCode::TimelockRequired
+            );
+        }
+
+        ctx.accounts
+            .registry
+            .update_bridge_config(wormhole_bridge, layerzero_endpoint)
+    }
+
+    /// Propose a new registry authority (multisig, or authority before one exists)
+    pub fn propose_authority(
+        ctx: Context<ManageRegistryAuthority>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        let action = PrivilegedAction::ProposeAuthority { new_authority };
+        if !consume_proposal(
+            &ctx.accounts.multisig,
+            &mut ctx.accounts.multisig_proposal,
+            &action,
+        )? {
+            require_keys_eq!(
+                ctx.accounts.registry.authority,
+                ctx.accounts.authority.key(),
+                ErrorCode::Unauthorized
+            );
+        }
+
+        let registry = &mut ctx.accounts.registry;
+        registry.pending_authority = Some(new_authority);
+
+        emit!(AuthorityTransferProposed {
+            registry: registry.key(),
+            authority: registry.authority,
+            pending_authority: new_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Accept a pending authority transfer (pending authority only)
+    pub fn accept_authority(ctx: Context<AcceptRegistryAuthority>) -> Result<()> {
+        let registry = &mut ctx.accounts.registry;
+        let previous_authority = registry.authority;
+        registry.authority = ctx.accounts.new_authority.key();
+        registry.pending_authority = None;
+
+        emit!(AuthorityTransferAccepted {
+            registry: registry.key(),
+            previous_authority,
+            new_authority: registry.authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Cancel a pending authority transfer (multisig, or authority before one exists)
+    pub fn cancel_authority_transfer(ctx: Context<ManageRegistryAuthority>) -> Result<()> {
+        let action = PrivilegedAction::CancelAuthorityTransfer;
+        if !consume_proposal(
+            &ctx.accounts.multisig,
+            &mut ctx.accounts.multisig_proposal,
+            &action,
+        )? {
+            require_keys_eq!(
+                ctx.accounts.registry.authority,
+                ctx.accounts.authority.key(),
+                ErrorCode::Unauthorized
+            );
+        }
+
+        let registry = &mut ctx.accounts.registry;
+        let cancelled_authority = registry
+            .pending_authority
+            .take()
+            .ok_or(ErrorCode::NoPendingAuthority)?;
+
+        emit!(AuthorityTransferCancelled {
+            registry: registry.key(),
+            cancelled_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Set or replace the typed attributes of an asset (owner only)
+    pub fn update_asset_attributes(
+        ctx: Context<UpdateAssetAttributes>,
+        asset_id: u64,
+        attributes: AssetAttributeData,
+    ) -> Result<()> {
+        attributes::update_asset_attributes(ctx, asset_id, attributes)
+    }
+
+    /// Burn carbon credit tokens and record a retirement certificate (holder)
+    pub fn retire_carbon_credits(
+        ctx: Context<RetireCarbonCredits>,
+        asset_id: u64,
+        amount: u64,
+        beneficiary_name: String,
+        reason: String,
+    ) -> Result<()> {
+        carbon::retire_carbon_credits(ctx, asset_id, amount, beneficiary_name, reason)
+    }
+
+    /// Mint the non-transferable NFT for a retirement certificate (retiring holder only)
+    pub fn mint_retirement_certificate(
+        ctx: Context<MintRetirementCertificate>,
+        metadata_uri: String,
+    ) -> Result<()> {
+        carbon::mint_retirement_certificate(ctx, metadata_uri)
+    }
+
+    /// Escrow tokens and request redemption against the underlying asset (holder)
+    pub fn request_redemption(
+        ctx: Context<RequestRedemption>,
+        asset_id: u64,
+        amount: u64,
+    ) -> Result<()> {
+        redemption::request_redemption(ctx, asset_id, amount)
+    }
+
+    /// Burn escrowed tokens and record the off-chain settlement (owner only)
+    pub fn fulfill_redemption(
+        ctx: Context<FulfillRedemption>,
+        asset_id: u64,
+        settlement: SettlementReference,
+    ) -> Result<()> {
+        redemption::fulfill_redemption(ctx, asset_id, settlement)
+    }
+
+    /// Return escrowed tokens to the holder (owner only)
+    pub fn reject_redemption(ctx: Context<RejectRedemption>, asset_id: u64) -> Result<()> {
+        redemption::reject_redemption(ctx, asset_id)
+    }
+
+    /// Deposit a payout for holders as of a committed balance snapshot (owner only)
+    pub fn create_distribution(
+        ctx: Context<CreateDistribution>,
+        asset_id: u64,
+        amount: u64,
+        claim_deadline: i64,
+    ) -> Result<()> {
+        distribution::create_distribution(ctx, asset_id, amount, claim_deadline)
+    }
+
+    /// Claim a pro-rata payout by proving the record-date balance (holder)
+    pub fn claim_distribution(
+        ctx: Context<ClaimDistribution>,
+        balance: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        distribution::claim_distribution(ctx, balance, proof)
+    }
+
+    /// Return unclaimed funds after the claim deadline (owner only)
+    pub fn sweep_distribution(ctx: Context<SweepDistribution>) -> Result<()> {
+        distribution::sweep_distribution(ctx)
+    }
+
+    /// Record a new snapshot id at the supply held by holders (owner only)
+    pub fn take_snapshot(ctx: Context<TakeSnapshot>, asset_id: u64) -> Result<()> {
+        snapshot::take_snapshot(ctx, asset_id)
+    }
+
+    /// Commit the holder balance root of a snapshot (snapshot authority)
+    pub fn commit_snapshot_root(
+        ctx: Context<CommitSnapshotRoot>,
+        balances_root: [u8; 32],
+        holder_count: u32,
+        leaf_total: u64,
+    ) -> Result<()> {
+        snapshot::commit_snapshot_root(ctx, balances_root, holder_count, leaf_total)
+    }
+
+    /// Delegate snapshot root commits to another key (owner only)
+    pub fn set_snapshot_authority(
+        ctx: Context<SetSnapshotAuthority>,
+        asset_id: u64,
+        snapshot_authority: Option<Pubkey>,
+    ) -> Result<()> {
+        snapshot::set_snapshot_authority(ctx, asset_id, snapshot_authority)
+    }
+
+    /// Commit a Merkle root of primary offering allocations (owner only)
+    pub fn commit_allocations(
+        ctx: Context<CommitAllocations>,
+        asset_id: u64,
+        allocations_root: [u8; 32],
+        leaf_count: u32,
+        total_allocated: u64,
+    ) -> Result<()> {
+        allocation::commit_allocations(ctx, asset_id, allocations_root, leaf_count, total_allocated)
+    }
+
+    /// Mint an allocation to its recipient by proving it against the round root (recipient)
+    pub fn claim_allocation(
+        ctx: Context<ClaimAllocation>,
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        allocation::claim_allocation(ctx, index, amount, proof)
+    }
+
+    /// Stop further claims on an allocation round (owner only)
+    pub fn close_allocation_round(ctx: Context<CloseAllocationRound>) -> Result<()> {
+        allocation::close_allocation_round(ctx)
+    }
+
+    /// Open a primary sale of the asset's tokens for a quote token (owner only)
+    pub fn create_primary_sale(
+        ctx: Context<CreatePrimarySale>,
+        asset_id: u64,
+        price_per_token: u64,
+        start_time: i64,
+        end_time: i64,
+        min_ticket: u64,
+        max_ticket: u64,
+        soft_cap: u64,
+        hard_cap: u64,
+        required_kyc: KYCLevel,
+        required_tier: InvestorTier,
+    ) -> Result<()> {
+        sale::create_primary_sale(
+            ctx,
+            asset_id,
+            price_per_token,
+            start_time,
+            end_time,
+            min_ticket,
+            max_ticket,
+            soft_cap,
+            hard_cap,
+            required_kyc,
+            required_tier,
+        )
+    }
+
+    /// Pay the quote token for newly minted tokens held in the sale escrow (eligible buyer)
+    pub fn buy_primary_sale(ctx: Context<BuyPrimarySale>, amount: u64) -> Result<()> {
+        sale::buy_primary_sale(ctx, amount)
+    }
+
+    /// Receive escrowed tokens once the sale reached its soft cap (buyer)
+    pub fn claim_sale_tokens(ctx: Context<ClaimSaleTokens>) -> Result<()> {
+        sale::claim_sale_tokens(ctx)
+    }
+
+    /// Burn escrowed tokens and refund the payment once a sale missed its soft cap (buyer)
+    pub fn refund_primary_sale(ctx: Context<RefundPrimarySale>) -> Result<()> {
+        sale::refund_primary_sale(ctx)
+    }
+
+    /// Withdraw the proceeds of a successful sale (owner only)
+    pub fn withdraw_sale_proceeds(ctx: Context<WithdrawSaleProceeds>) -> Result<()> {
+        sale::withdraw_sale_proceeds(ctx)
+    }
+
+    /// Set the valuation currency, staleness and deviation limits (owner only)
+    pub fn configure_valuation(
+        ctx: Context<ConfigureValuation>,
+        asset_id: u64,
+        currency: [u8; 3],
+        max_staleness: i64,
+        max_deviation_bps: u16,
+    ) -> Result<()> {
+        valuation::configure_valuation(ctx, asset_id, currency, max_staleness, max_deviation_bps)
+    }
+
+    /// Record a new appraisal or NAV for an asset (ValuationOracle)
+    pub fn submit_valuation(
+        ctx: Context<SubmitValuation>,
+        value: u64,
+        currency: [u8; 3],
+        effective_date: i64,
+        methodology_hash: [u8; 32],
+    ) -> Result<()> {
+        valuation::submit_valuation(ctx, value, currency, effective_date, methodology_hash)
+    }
+
+    /// Apply a valuation held back by the deviation threshold (Admin)
+    pub fn confirm_valuation(ctx: Context<ConfirmValuation>) -> Result<()> {
+        valuation::confirm_valuation(ctx)
+    }
+
+    /// Value a metal or commodity asset from a Pyth price account and custody quantity (Admin)
+    pub fn configure_price_feed(
+        ctx: Context<ConfigurePriceFeed>,
+        asset_id: u64,
+        max_staleness: i64,
+        max_confidence_bps: u16,
+        custody_quantity: u64,
+        quantity_decimals: u8,
+        value_decimals: u8,
+    ) -> Result<()> {
+        price_feed::configure_price_feed(
+            ctx,
+            asset_id,
+            max_staleness,
+            max_confidence_bps,
+            custody_quantity,
+            quantity_decimals,
+            value_decimals,
+        )
+    }
+
+    /// Update total_value from the current feed price (permissionless)
+    pub fn refresh_price_valuation(ctx: Context<RefreshPriceValuation>) -> Result<()> {
+        price_feed::refresh_price_valuation(ctx)
+    }
+
+    /// Set the reserve staleness and token ratio (owner only)
+    pub fn configure_reserve(
+        ctx: Context<ConfigureReserve>,
+        asset_id: u64,
+        max_staleness: i64,
+        tokens_per_unit: u64,
+    ) -> Result<()> {
+        reserve::configure_reserve(ctx, asset_id, max_staleness, tokens_per_unit)
+    }
+
+    /// Appoint or replace the reserve custodian (Admin, or an executed multisig proposal)
+    pub fn appoint_reserve_custodian(
+        ctx: Context<AppointReserveCustodian>,
+        asset_id: u64,
+        custodian: Pubkey,
+    ) -> Result<()> {
+        reserve::appoint_reserve_custodian(ctx, asset_id, custodian)
+    }
+
+    /// Attest the quantity held in custody, revaluing price-fed assets at it (custodian only)
+    pub fn post_reserve_attestation(
+        ctx: Context<PostReserveAttestation>,
+        quantity: u64,
+        audit_report_hash: [u8; 32],
+        auditor: Pubkey,
+    ) -> Result<()> {
+        reserve::post_reserve_attestation(ctx, quantity, audit_report_hash, auditor)
+    }
+
+    /// Publish a new version of a legal document for an asset (owner only)
+    pub fn publish_asset_document(
+        ctx: Context<PublishAssetDocument>,
+        asset_id: u64,
+        document_type: DocumentType,
+        version: u32,
+        content_hash: [u8; 32],
+        uri: String,
+        effective_date: i64,
+    ) -> Result<()> {
+        documents::publish_asset_document(
+            ctx,
+            asset_id,
+            document_type,
+            version,
+            content_hash,
+            uri,
+            effective_date,
+        )
+    }
+
+    /// Create the asset's token mint with the asset PDA as mint and freeze authority (owner only)
+    pub fn create_asset_mint(
+        ctx: Context<CreateAssetMint>,
+        asset_id: u64,
+        decimals: u8,
+    ) -> Result<()> {
+        transfer_agent::create_asset_mint(ctx, asset_id, decimals)
+    }
+
+    /// Freeze a holder's token account (TransferAgent)
+    pub fn freeze_holder(ctx: Context<FreezeHolder>, asset_id: u64) -> Result<()> {
+        transfer_agent::freeze_holder(ctx, asset_id)
+    }
+
+    /// Thaw a holder's token account (TransferAgent)
+    pub fn thaw_holder(ctx: Context<ThawHolder>, asset_id: u64) -> Result<()> {
+        transfer_agent::thaw_holder(ctx, asset_id)
+    }
+
+    /// Move a holder's balance under a court order or other legal instruction (TransferAgent)
+    pub fn forced_transfer(
+        ctx: Context<ForcedTransfer>,
+        asset_id: u64,
+        legal_reference_hash: [u8; 32],
+    ) -> Result<()> {
+        transfer_agent::forced_transfer(ctx, asset_id, legal_reference_hash)
+    }
+
+    /// Create the identity registry (program upgrade authority only)
+    pub fn initialize_identity_registry(
+        ctx: Context<InitializeIdentityRegistry>,
+        authority: Pubkey,
+        cross_chain_bridge: Option<Pubkey>,
+    ) -> Result<()> {
+        identity::initialize_identity_registry(ctx, authority, cross_chain_bridge)
+    }
+
+    /// Issue an identity passport NFT to an investor (authorized issuer or ComplianceOfficer)
+    pub fn issue_identity_passport(
+        ctx: Context<IssueIdentityPassport>,
+        did: String,
+        kyc_level: identity::KYCLevel,
+        investor_tier: InvestorTier,
+        metadata_uri: String,
+        validity_period: i64,
+    ) -> Result<()> {
+        identity::issue_identity_passport(
+            ctx,
+            did,
+            kyc_level,
+            investor_tier,
+            metadata_uri,
+            validity_period,
+        )
+    }
+
+    /// Update a passport's KYC level, tier, reputation or metadata (authorized issuer or ComplianceOfficer)
+    pub fn update_identity_passport(
+        ctx: Context<UpdateIdentityPassport>,
+        new_kyc_level: Option<identity::KYCLevel>,
+        new_investor_tier: Option<InvestorTier>,
+        new_reputation_score: Option<u64>,
+        new_metadata_uri: Option<String>,
+    ) -> Result<()> {
+        identity::update_identity_passport(
+            ctx,
+            new_kyc_level,
+            new_investor_tier,
+            new_reputation_score,
+            new_metadata_uri,
+        )
+    }
+
+    /// Link an address on another chain to a passport (passport owner)
+    pub fn link_cross_chain_address(
+        ctx: Context<LinkCrossChainAddress>,
+        chain_id: u16,
+        address: String,
+        signature: Vec<u8>,
+    ) -> Result<()> {
+        identity::link_cross_chain_address(ctx, chain_id, address, signature)
+    }
+
+    /// Mark a linked address as verified once its ownership is confirmed (authorized issuer or ComplianceOfficer)
+    pub fn verify_cross_chain_address(
+        ctx: Context<VerifyCrossChainAddress>,
+        chain_id: u16,
+        address: String,
+    ) -> Result<()> {
+        identity::verify_cross_chain_address(ctx, chain_id, address)
+    }
+
+    /// Attach a credential to a passport (authorized issuer or ComplianceOfficer)
+    pub fn add_credential(
+        ctx: Context<AddCredential>,
+        credential_id: String,
+        credential_type: String,
+    ) -> Result<()> {
+        identity::add_credential(ctx, credential_id, credential_type)
+    }
+
+    /// Publish a passport to another chain (identity registry authority)
+    pub fn sync_cross_chain(
+        ctx: Context<SyncCrossChain>,
+        target_chain: u16,
+        target_contract: String,
+    ) -> Result<()> {
+        identity::sync_cross_chain(ctx, target_chain, target_contract)
+    }
+
+    /// Propose a new identity registry authority (identity authority only)
+    pub fn propose_identity_authority(
+        ctx: Context<ManageIdentityAuthority>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        identity::propose_identity_authority(ctx, new_authority)
+    }
+
+    /// Accept a pending identity authority transfer (pending authority only)
+    pub fn accept_identity_authority(ctx: Context<AcceptIdentityAuthority>) -> Result<()> {
+        identity::accept_identity_authority(ctx)
+    }
+
+    /// Cancel a pending identity authority transfer (identity authority only)
+    pub fn cancel_identity_authority_transfer(ctx: Context<ManageIdentityAuthority>) -> Result<()> {
+        identity::cancel_identity_authority_transfer(ctx)
+    }
+
+    /// Register a chain reachable through the bridges (multisig, or Admin before one exists)
+    pub fn add_supported_chain(
+        ctx: Context<AddSupportedChain>,
+        wormhole_chain_id: u16,
+        name: String,
+        evm_chain_id: Option<u64>,
+        address_format: AddressFormat,
+    ) -> Result<()> {
+        chains::add_supported_chain(ctx, wormhole_chain_id, name, evm_chain_id, address_format)
+    }
+
+    /// Disable a supported chain (multisig, or Admin before one exists)
+    pub fn disable_supported_chain(ctx: Context<DisableSupportedChain>) -> Result<()> {
+        chains::disable_supported_chain(ctx)
+    }
+
+    /// Re-enable a disabled chain (multisig, or Admin before one exists)
+    pub fn enable_supported_chain(ctx: Context<EnableSupportedChain>) -> Result<()> {
+        chains::enable_supported_chain(ctx)
+    }
+
+    /// Grant a role to a member (Admin; Admin itself by the multisig, or the authority before one exists)
+    pub fn grant_role(ctx: Context<GrantRole>, role: Role, member: Pubkey) -> Result<()> {
+        roles::grant_role(ctx, role, member)
+    }
+
+    /// Revoke a role membership (Admin; Admin itself by the multisig, or the authority before one exists)
+    pub fn revoke_role(ctx: Context<RevokeRole>) -> Result<()> {
+        roles::revoke_role(ctx)
+    }
+
+    /// Create the M-of-N multisig for privileged operations (authority only)
+    pub fn create_multisig(
+        ctx: Context<CreateMultisig>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+        proposal_lifetime: i64,
+    ) -> Result<()> {
+        multisig::create_multisig(ctx, signers, threshold, proposal_lifetime)
+    }
+
+    /// Propose a privileged action (multisig signer)
+    pub fn propose_privileged_action(
+        ctx: Context<ProposePrivilegedAction>,
+        action: PrivilegedAction,
+    ) -> Result<()> {
+        multisig::propose_privileged_action(ctx, action)
+    }
+
+    /// Approve a pending proposal (multisig signer)
+    pub fn approve_privileged_action(ctx: Context<ApprovePrivilegedAction>) -> Result<()> {
+        multisig::approve_privileged_action(ctx)
+    }
+
+    /// Mark a proposal that reached the threshold as executed (permissionless)
+    pub fn execute_privileged_action(ctx: Context<ExecutePrivilegedAction>) -> Result<()> {
+        multisig::execute_privileged_action(ctx)
+    }
+
+    /// Add or remove a multisig signer or change the threshold (multisig)
+    pub fn update_multisig(ctx: Context<UpdateMultisig>, action: PrivilegedAction) -> Result<()> {
+        multisig::update_multisig(ctx, action)
+    }
+
+    /// Create the governance timelock with a delay per action class (authority only)
+    pub fn initialize_timelock(ctx: Context<InitializeTimelock>, delays: [i64; 3]) -> Result<()> {
+        timelock::initialize_timelock(ctx, delays)
+    }
+
+    /// Queue a configuration change behind its class delay (Admin)
+    pub fn queue_timelock_action(
+        ctx: Context<QueueTimelockAction>,
+        action: TimelockAction,
+    ) -> Result<()> {
+        timelock::queue_timelock_action(ctx, action)
+    }
+
+    /// Cancel a queued change during its delay (Admin)
+    pub fn cancel_timelock_action(ctx: Context<CancelTimelockAction>) -> Result<()> {
+        timelock::cancel_timelock_action(ctx)
+    }
+
+    /// Apply a queued change once its delay elapsed (permissionless)
+    pub fn execute_timelock_action(ctx: Context<ExecuteTimelockAction>) -> Result<()> {
+        timelock::execute_timelock_action(ctx)
+    }
+}
+
+/// Authorize and apply a registry pause change. The guardian may only add
+/// flags; anyone else needs an executed multisig proposal, or the Pauser role
+/// while no multisig exists.
+fn apply_pause_flags(
+    accounts: &mut SetRegistryPause,
+    action: &PrivilegedAction,
+    pause_flags: u8,
+) -> Result<()> {
+    let changed_by = accounts.authority.key();
+    let registry = &accounts.registry;
+    let only_adds_flags = pause_flags & registry.pause_flags == registry.pause_flags;
+    let by_guardian = only_adds_flags && registry.guardian == Some(changed_by);
+    if !by_guardian
+        && !consume_proposal(&accounts.multisig, &mut accounts.multisig_proposal, action)?
+    {
+        require_role(
+            &accounts.registry,
+            &changed_by,
+            &accounts.pauser_role,
+            Role::Pauser,
+        )?;
+    }
+
+    accounts.registry.pause_flags = pause_flags;
+
+    emit!(RegistryPauseChanged {
+        pause_flags,
+        changed_by,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Account Structs
+#[derive(Accounts)]
+pub struct InitializeRegistry<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Registry::INIT_SPACE,
+        seeds = [b"registry"],
+        bump
+    )]
+    pub registry: Account<'info, Registry>,
+    
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::OmniflowRwa>,
+    
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(upgrade_authority.key())
+            @ ErrorCode::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    
+    pub upgrade_authority: Signer<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct RegisterRWAAsset<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + RWAAsset::INIT_SPACE,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump
+    )]
+    pub asset: Account<'info, RWAAsset>,
+    
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+    
+    #[account(
+        seeds = [b"role", &[Role::Issuer as u8], owner.key().as_ref()],
+        bump = issuer_role.bump
+    )]
+    pub issuer_role: Option<Account<'info, RoleMembership>>,
+    
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + AssetIndexPage::INIT_SPACE,
+        seeds = [b"asset_index", &AssetIndexPage::page_of(registry.indexed_assets).to_le_bytes()],
+        bump
+    )]
+    pub asset_index_page: Account<'info, AssetIndexPage>,
+    
+    /// Only supplied when registering with typed attributes
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + AssetAttributes::INIT_SPACE,
+        seeds = [b"asset_attributes", &asset_id.to_le_bytes()],
+        bump
+    )]
+    pub asset_attributes: Option<Account<'info, AssetAttributes>>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct MintRWATokens<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump,
+        has_one = owner
+    )]
+    pub asset: Account<'info, RWAAsset>,
+    
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+    
+    #[account(mut, address = asset.mint @ ErrorCode::MintMismatch)]
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = recipient
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: Recipient can be any account
+    pub recipient: AccountInfo<'info>,
+    
+    /// Required when the asset has a KYC requirement
+    #[account(constraint = recipient_passport.owner == recipient.key() @ ErrorCode::Unauthorized)]
+    pub recipient_passport: Option<Account<'info, IdentityPassport>>,
+    
+    /// Required when the asset has a valuation policy
+    #[account(seeds = [b"valuation", &asset_id.to_le_bytes()], bump = valuation.bump)]
+    pub valuation: Option<Account<'info, ValuationHistory>>,
+    
+    /// Required when the asset has a custodian
+    #[account(seeds = [b"reserve", &asset_id.to_le_bytes()], bump = reserve.bump)]
+    pub reserve: Option<Account<'info, ReserveAttestation>>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64, amount: u64, target_chain: u16)]
+pub struct InitiateCrossChainTransfer<'info> {
+    #[account(
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump
+    )]
+    pub asset: Account<'info, RWAAsset>,
+    
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+    
+    #[account(
+        seeds = [b"chain", &target_chain.to_le_bytes()],
+        bump = target_chain_config.bump,
+        constraint = target_chain_config.enabled @ ChainError::ChainDisabled
+    )]
+    pub target_chain_config: Account<'info, ChainConfig>,
+    
+    #[account(mut, address = asset.mint @ ErrorCode::MintMismatch)]
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = owner
+    )]
+    pub from_token_account: Account<'info, TokenAccount>,
+    
+    /// Required when the asset only allows verified recipients
+    #[account(constraint = sender_passport.owner == owner.key() @ ErrorCode::Unauthorized)]
+    pub sender_passport: Option<Account<'info, IdentityPassport>>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + OutboundTransfer::INIT_SPACE,
+        seeds = [b"outbound_transfer", &registry.outbound_sequence.to_le_bytes()],
+        bump
+    )]
+    pub outbound_transfer: Account<'info, OutboundTransfer>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConfirmOutboundTransfer<'info> {
+    #[account(
+        mut,
+        close = sender,
+        seeds = [b"outbound_transfer", &outbound_transfer.sequence.to_le_bytes()],
+        bump = outbound_transfer.bump
+    )]
+    pub outbound_transfer: Account<'info, OutboundTransfer>,
+    
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+    
+    /// CHECK: Receives the transfer record's rent, must be the original sender
+    #[account(mut, address = outbound_transfer.sender)]
+    pub sender: UncheckedAccount<'info>,
+    
+    #[account(
+        seeds = [b"role", &[Role::BridgeRelayer as u8], relayer.key().as_ref()],
+        bump = relayer_role.bump
+    )]
+    pub relayer_role: Option<Account<'info, RoleMembership>>,
+    
+    pub relayer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelOutboundTransfer<'info> {
+    #[account(
+        mut,
+        close = sender,
+        seeds = [b"outbound_transfer", &outbound_transfer.sequence.to_le_bytes()],
+        bump = outbound_transfer.bump
+    )]
+    pub outbound_transfer: Account<'info, OutboundTransfer>,
+    
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+    
+    #[account(
+        seeds = [b"asset", &outbound_transfer.asset_id.to_le_bytes()],
+        bump = asset.bump
+    )]
+    pub asset: Account<'info, RWAAsset>,
+    
+    #[account(mut, address = asset.mint @ ErrorCode::MintMismatch)]
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = sender
+    )]
+    pub sender_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: Receives the transfer record's rent, must be the original sender
+    #[account(mut, address = outbound_transfer.sender)]
+    pub sender: UncheckedAccount<'info>,
+    
+    #[account(
+        seeds = [b"role", &[Role::Admin as u8], authority.key().as_ref()],
+        bump = admin_role.bump
+    )]
+    pub admin_role: Option<Account<'info, RoleMembership>>,
+    
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct CompleteCrossChainTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump
+    )]
+    pub asset: Account<'info, RWAAsset>,
+    
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+    
+    #[account(mut, address = asset.mint @ ErrorCode::MintMismatch)]
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = recipient
+    )]
+    pub to_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: Recipient can be any account
+    pub recipient: AccountInfo<'info>,
+    
+    #[account(
+        seeds = [b"role", &[Role::BridgeRelayer as u8], payer.key().as_ref()],
+        bump = relayer_role.bump
+    )]
+    pub relayer_role: Option<Account<'info, RoleMembership>>,
+    
+    /// Required when the asset has a valuation policy
+    #[account(seeds = [b"valuation", &asset_id.to_le_bytes()], bump = valuation.bump)]
+    pub valuation: Option<Account<'info, ValuationHistory>>,
+    
+    /// Required when the asset has a custodian
+    #[account(seeds = [b"reserve", &asset_id.to_le_bytes()], bump = reserve.bump)]
+    pub reserve: Option<Account<'info, ReserveAttestation>>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct UpdateAssetMetadata<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump,
+        has_one = owner
+    )]
+    pub asset: Account<'info, RWAAsset>,
+    
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + MetadataRevision::INIT_SPACE,
+        seeds = [
+            b"metadata_revision",
+            &asset_id.to_le_bytes(),
+            &(asset.metadata_version + 1).to_le_bytes()
+        ],
+        bump
+    )]
+    pub metadata_revision: Account<'info, MetadataRevision>,
+    
+    /// Required to lower the KYC level
+    #[account(address = registry.authority @ ErrorCode::Unauthorized)]
+    pub registry_authority: Option<Signer<'info>>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct SetRecipientPolicy<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump,
+        has_one = owner
+    )]
+    pub asset: Account<'info, RWAAsset>,
+    
+    /// CHECK: Timelock config PDA, uninitialized when no timelock was created
+    #[account(seeds = [b"timelock"], bump)]
+    pub timelock_config: UncheckedAccount<'info>,
+    
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetRegistryPause<'info> {
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+    
+    #[account(
+        seeds = [b"role", &[Role::Pauser as u8], authority.key().as_ref()],
+        bump = pauser_role.bump
+    )]
+    pub pauser_role: Option<Account<'info, RoleMembership>>,
+    
+    /// CHECK: Multisig PDA; once created, the single-key path is closed
+    #[account(seeds = [b"multisig"], bump)]
+    pub multisig: UncheckedAccount<'info>,
+    
+    /// Executed multisig proposal authorizing this call in place of the authority
+    #[account(
+        mut,
+        seeds = [b"multisig_proposal", &multisig_proposal.proposal_id.to_le_bytes()],
+        bump = multisig_proposal.bump
+    )]
+    pub multisig_proposal: Option<Account<'info, MultisigProposal>>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct SetAssetPause<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump
+    )]
+    pub asset: Account<'info, RWAAsset>,
+    
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+    
+    #[account(
+        seeds = [b"role", &[Role::Pauser as u8], authority.key().as_ref()],
+        bump = pauser_role.bump
+    )]
+    pub pauser_role: Option<Account<'info, RoleMembership>>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct ManageAssetLifecycle<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump,
+        constraint = authority.key() == asset.owner || authority.key() == registry.authority
+            @ ErrorCode::Unauthorized
+    )]
+    pub asset: Account<'info, RWAAsset>,
+    
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+    
+    #[account(
+        mut,
+        seeds = [b"asset_index", &AssetIndexPage::page_of(asset.index_position).to_le_bytes()],
+        bump = asset_index_page.bump
+    )]
+    pub asset_index_page: Account<'info, AssetIndexPage>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct RetireAsset<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump,
+        constraint = authority.key() == asset.owner || authority.key() == registry.authority
+            @ ErrorCode::Unauthorized
+    )]
+    pub asset: Account<'info, RWAAsset>,
+    
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+    
+    #[account(
+        mut,
+        seeds = [b"asset_index", &AssetIndexPage::page_of(asset.index_position).to_le_bytes()],
+        bump = asset_index_page.bump
+    )]
+    pub asset_index_page: Account<'info, AssetIndexPage>,
+    
+    /// Required while the asset has an open primary sale
+    #[account(seeds = [b"primary_sale", &asset_id.to_le_bytes()], bump = sale.bump)]
+    pub sale: Option<Account<'info, PrimarySale>>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct ManageAssetOwner<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump,
+        has_one = owner
+    )]
+    pub asset: Account<'info, RWAAsset>,
+    
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct ApproveAssetOwner<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump
+    )]
+    pub asset: Account<'info, RWAAsset>,
+    
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump,
+        has_one = authority
+    )]
+    pub registry: Account<'info, Registry>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct AcceptAssetOwner<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump,
+        constraint = asset.pending_owner == Some(new_owner.key()) @ ErrorCode::Unauthorized
+    )]
+    pub asset: Account<'info, RWAAsset>,
+    
+    #[account(
+        mut,
+        seeds = [b"asset_index", &AssetIndexPage::page_of(asset.index_position).to_le_bytes()],
+        bump = asset_index_page.bump
+    )]
+    pub asset_index_page: Account<'info, AssetIndexPage>,
+    
+    pub new_owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetAssetIdAllocation<'info> {
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+    
+    /// CHECK: Multisig PDA; once created, the single-key path is closed
+    #[account(seeds = [b"multisig"], bump)]
+    pub multisig: UncheckedAccount<'info>,
+    
+    /// Executed multisig proposal authorizing this call in place of the authority
+    #[account(
+        mut,
+        seeds = [b"multisig_proposal", &multisig_proposal.proposal_id.to_le_bytes()],
+        bump = multisig_proposal.bump
+    )]
+    pub multisig_proposal: Option<Account<'info, MultisigProposal>>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64, external_reference: String)]
+pub struct RegisterAssetReference<'info> {
+    #[account(
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump,
+        has_one = owner
+    )]
+    pub asset: Account<'info, RWAAsset>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + AssetReference::INIT_SPACE,
+        seeds = [
+            b"asset_ref",
+            owner.key().as_ref(),
+            &AssetReference::hash_reference(&external_reference),
+        ],
+        bump
+    )]
+    pub asset_reference: Account<'info, AssetReference>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+    
+    /// CHECK: Multisig PDA; once created, the single-key path is closed
+    #[account(seeds = [b"multisig"], bump)]
+    pub multisig: UncheckedAccount<'info>,
+    
+    /// Executed multisig proposal authorizing this call in place of the authority
+    #[account(
+        mut,
+        seeds = [b"multisig_proposal", &multisig_proposal.proposal_id.to_le_bytes()],
+        bump = multisig_proposal.bump
+    )]
+    pub multisig_proposal: Option<Account<'info, MultisigProposal>>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateBridgeConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+    
+    /// CHECK: Timelock config PDA, uninitialized when no timelock was created
+    #[account(seeds = [b"timelock"], bump)]
+    pub timelock_config: UncheckedAccount<'info>,
+    
+    /// CHECK: Multisig PDA; once created, the single-key path is closed
+    #[account(seeds = [b"multisig"], bump)]
+    pub multisig: UncheckedAccount<'info>,
+    
+    /// Executed multisig proposal authorizing this call in place of the authority
+    #[account(
+        mut,
+        seeds = [b"multisig_proposal", &multisig_proposal.proposal_id.to_le_bytes()],
+        bump = multisig_proposal.bump
+    )]
+    pub multisig_proposal: Option<Account<'info, MultisigProposal>>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ManageRegistryAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+    
+    /// CHECK: Multisig PDA; once created, the single-key path is closed
+    #[account(seeds = [b"multisig"], bump)]
+    pub multisig: UncheckedAccount<'info>,
+    
+    /// Executed multisig proposal authorizing this call in place of the authority
+    #[account(
+        mut,
+        seeds = [b"multisig_proposal", &multisig_proposal.proposal_id.to_le_bytes()],
+        bump = multisig_proposal.bump
+    )]
+    pub multisig_proposal: Option<Account<'info, MultisigProposal>>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptRegistryAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump = registry.bump,
+        constraint = registry.pending_authority == Some(new_authority.key()) @ ErrorCode::Unauthorized
+    )]
+    pub registry: Account<'info, Registry>,
+    
+    pub new_authority: Signer<'info>,
+}
+
+// Data Structs
+#[account]
+#[derive(InitSpace)]
+pub struct Registry {
+    pub authority: Pubkey,
+    pub pending_authority: Option<Pubkey>,
     pub total_assets: u64,
+    /// Next id below `CALLER_ASSET_ID_BASE` to register; caller-chosen ids never move it
+    pub next_asset_id: u64,
+    pub allocate_asset_ids: bool,
+    /// Number of entries appended to the asset index
+    pub indexed_assets: u64,
     pub wormhole_bridge: Option<Pubkey>,
     pub layerzero_endpoint: Option<Pubkey>,
-    pub paused: bool,
+    pub outbound_sequence: u64,
+    pub pending_outbound_transfers: u64,
+    /// Bitmask of `PAUSE_*` flags
+    pub pause_flags: u8,
+    pub guardian: Option<Pubkey>,
+    pub bump: u8,
+}
+
+impl Registry {
+    pub fn require_not_paused(&self, operation: u8) -> Result<()> {
+        require!(self.pause_flags & operation == 0, ErrorCode::RegistryPaused);
+        Ok(())
+    }
+
+    /// Apply endpoint changes. Refused while outbound transfers are in flight,
+    /// since their delivery depends on the current endpoints.
+    pub fn update_bridge_config(
+        &mut self,
+        wormhole_bridge: EndpointUpdate,
+        layerzero_endpoint: EndpointUpdate,
+    ) -> Result<()> {
+        require!(
+            self.pending_outbound_transfers == 0,
+            ErrorCode::OutboundTransfersPending
+        );
+
+        let old_wormhole_bridge = self.wormhole_bridge;
+        let old_layerzero_endpoint = self.layerzero_endpoint;
+        self.wormhole_bridge = wormhole_bridge.apply(old_wormhole_bridge);
+        self.layerzero_endpoint = layerzero_endpoint.apply(old_layerzero_endpoint);
+
+        emit!(BridgeConfigUpdated {
+            old_wormhole_bridge,
+            new_wormhole_bridge: self.wormhole_bridge,
+            old_layerzero_endpoint,
+            new_layerzero_endpoint: self.layerzero_endpoint,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum EndpointUpdate {
+    Keep,
+    Set(Pubkey),
+    Clear,
+}
+
+impl EndpointUpdate {
+    pub fn apply(self, current: Option<Pubkey>) -> Option<Pubkey> {
+        match self {
+            EndpointUpdate::Keep => current,
+            EndpointUpdate::Set(endpoint) => Some(endpoint),
+            EndpointUpdate::Clear => None,
+        }
+    }
+}
+
+/// Outbound transfer burned on Solana and awaiting delivery on the target chain
+#[account]
+#[derive(InitSpace)]
+pub struct OutboundTransfer {
+    pub sequence: u64,
+    pub asset_id: u64,
+    pub amount: u64,
+    pub target_chain: u16,
+    pub recipient: [u8; 32],
+    pub sender: Pubkey,
+    pub initiated_at: i64,
     pub bump: u8,
 }
 
@@ -472,6 +2315,8 @@ pub struct RWAAsset {
     pub asset_id: u64,
     pub asset_type: AssetType,
     pub owner: Pubkey,
+    pub pending_owner: Option<Pubkey>,
+    pub owner_transfer_approved: bool,
     #[max_len(200)]
     pub metadata_uri: String,
     pub kyc_level: KYCLevel,
@@ -479,11 +2324,155 @@ pub struct RWAAsset {
     pub total_supply: u64,
     pub circulating_supply: u64,
     pub is_active: bool,
+    pub paused: bool,
     pub created_at: i64,
     pub chain_id: u16,
+    pub require_verified_recipient: bool,
+    /// Position of this asset in the asset index
+    pub index_position: u64,
+    /// Number of carbon retirement certificates issued
+    pub retirement_count: u64,
+    /// Number of redemption requests received
+    pub redemption_count: u64,
+    /// Number of holder distributions created
+    pub distribution_count: u64,
+    /// Most recent balance snapshot id, 0 before the first snapshot
+    pub snapshot_id: u64,
+    /// Commits snapshot balance roots in place of the owner
+    pub snapshot_authority: Option<Pubkey>,
+    /// Number of Merkle allocation rounds committed
+    pub allocation_count: u64,
+    /// Set once a valuation policy exists; issuance then needs a current valuation
+    pub valuation_required: bool,
+    /// Set once a price feed is configured; `total_value` then tracks the market
+    pub price_feed_required: bool,
+    /// Set once a reserve policy is configured; issuance then needs current reserve attestations
+    pub reserve_required: bool,
+    /// Number of metadata updates, each recorded as a `MetadataRevision`
+    pub metadata_version: u32,
+    /// Content hash of the current metadata, `None` until the first update
+    pub metadata_content_hash: Option<[u8; 32]>,
+    /// Tokens left frozen in accounts whose balance a forced transfer reissued
+    pub voided_supply: u64,
+    /// Redemption requests awaiting fulfilment or rejection
+    pub pending_redemptions: u64,
+    /// Distributions that have not been swept
+    pub open_distributions: u64,
+    /// Allocation rounds with allocations left to claim
+    pub open_allocation_rounds: u64,
+    /// Set while the primary sale may still hold buyer funds
+    pub sale_open: bool,
+    /// Set by `retire_asset`. The account is kept so the id can never be registered again.
+    pub retired: bool,
+    /// Token mint created by `create_asset_mint`, the default key until then
+    pub mint: Pubkey,
+    /// Set while a pause by the guardian or a Pauser is in force; the owner cannot lift it
+    pub paused_by_registry: bool,
+    pub bump: u8,
+}
+
+impl RWAAsset {
+    pub fn address(asset_id: u64) -> Pubkey {
+        Pubkey::find_program_address(&[b"asset", &asset_id.to_le_bytes()], &crate::ID).0
+    }
+
+    /// Require the asset to be active and not individually paused
+    pub fn require_operational(&self) -> Result<()> {
+        require!(self.is_active, ErrorCode::AssetInactive);
+        require!(!self.paused, ErrorCode::AssetPaused);
+        Ok(())
+    }
+
+    /// Require `passport` to meet the asset's KYC level, unless it has none
+    pub fn require_kyc(&self, passport: &Option<Account<IdentityPassport>>) -> Result<()> {
+        if self.kyc_level != KYCLevel::None {
+            let now = Clock::get()?.unix_timestamp;
+            require!(
+                matches!(passport, Some(p) if p.satisfies_kyc(self.kyc_level, now)),
+                ErrorCode::KycRequirementNotMet
+            );
+        }
+        Ok(())
+    }
+
+    /// Count `amount` newly minted tokens against the supply cap
+    pub fn record_mint(&mut self, amount: u64) -> Result<()> {
+        self.circulating_supply = self
+            .circulating_supply
+            .checked_add(amount)
+            .filter(|supply| *supply <= self.total_supply)
+            .ok_or(ErrorCode::ExceedsMaxSupply)?;
+        Ok(())
+    }
+
+    /// Account allowed to commit snapshot balance roots
+    pub fn snapshot_authority(&self) -> Pubkey {
+        self.snapshot_authority.unwrap_or(self.owner)
+    }
+}
+
+/// Record of one `update_asset_metadata` call or timelocked KYC level change, stored at
+/// `[b"metadata_revision", asset_id, version]` and never modified
+#[account]
+#[derive(InitSpace)]
+pub struct MetadataRevision {
+    pub asset_id: u64,
+    pub version: u32,
+    /// SHA-256 of the new metadata URI
+    pub uri_hash: [u8; 32],
+    pub content_hash: [u8; 32],
+    /// SHA-256 of the URI this revision replaced
+    pub previous_uri_hash: [u8; 32],
+    pub previous_content_hash: Option<[u8; 32]>,
+    pub kyc_level: KYCLevel,
+    pub previous_kyc_level: KYCLevel,
+    #[max_len(100)]
+    pub reason: String,
+    pub updated_by: Pubkey,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl MetadataRevision {
+    pub fn address(asset_id: u64, version: u32) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"metadata_revision", &asset_id.to_le_bytes(), &version.to_le_bytes()],
+            &crate::ID,
+        )
+        .0
+    }
+}
+
+/// Lookup from an issuer's own reference to its asset,
+/// stored at `[b"asset_ref", issuer, sha256(external_reference)]`
+#[account]
+#[derive(InitSpace)]
+pub struct AssetReference {
+    pub issuer: Pubkey,
+    pub asset_id: u64,
+    #[max_len(64)]
+    pub external_reference: String,
     pub bump: u8,
 }
 
+impl AssetReference {
+    pub fn hash_reference(external_reference: &str) -> [u8; 32] {
+        Sha256::digest(external_reference.as_bytes()).into()
+    }
+
+    pub fn address(issuer: &Pubkey, external_reference: &str) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                b"asset_ref",
+                issuer.as_ref(),
+                &Self::hash_reference(external_reference),
+            ],
+            &crate::ID,
+        )
+        .0
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
 pub enum AssetType {
     RealEstate,
@@ -530,6 +2519,29 @@ pub struct RegistryInitialized {
     pub layerzero_endpoint: Option<Pubkey>,
 }
 
+#[event]
+pub struct AuthorityTransferProposed {
+    pub registry: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferAccepted {
+    pub registry: Pubkey,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferCancelled {
+    pub registry: Pubkey,
+    pub cancelled_authority: Pubkey,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct RWAAssetRegistered {
     pub asset_id: u64,
@@ -541,6 +2553,7 @@ pub struct RWAAssetRegistered {
     pub total_value: u64,
     pub total_supply: u64,
     pub chain_id: u16,
+    pub attributes: Option<AssetAttributeData>,
     pub timestamp: i64,
 }
 
@@ -554,6 +2567,7 @@ pub struct RWATokensMinted {
 
 #[event]
 pub struct CrossChainTransferInitiated {
+    pub sequence: u64,
     pub asset_id: u64,
     pub amount: u64,
     pub source_chain: u16,
@@ -574,6 +2588,34 @@ pub struct CrossChainTransferCompleted {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct OutboundTransferConfirmed {
+    pub sequence: u64,
+    pub asset_id: u64,
+    pub amount: u64,
+    pub target_chain: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OutboundTransferCancelled {
+    pub sequence: u64,
+    pub asset_id: u64,
+    pub amount: u64,
+    pub target_chain: u16,
+    pub cancelled_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BridgeConfigUpdated {
+    pub old_wormhole_bridge: Option<Pubkey>,
+    pub new_wormhole_bridge: Option<Pubkey>,
+    pub old_layerzero_endpoint: Option<Pubkey>,
+    pub new_layerzero_endpoint: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct CrossChainEvent {
     pub event_type: CrossChainEventType,
@@ -587,14 +2629,107 @@ pub struct CrossChainEvent {
 #[event]
 pub struct AssetMetadataUpdated {
     pub asset_id: u64,
+    pub metadata_version: u32,
     pub new_metadata_uri: String,
+    pub content_hash: [u8; 32],
+    pub previous_uri_hash: [u8; 32],
     pub new_kyc_level: Option<KYCLevel>,
+    pub reason: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RecipientPolicyUpdated {
+    pub asset_id: u64,
+    pub require_verified_recipient: bool,
     pub timestamp: i64,
 }
 
 #[event]
 pub struct RegistryPauseChanged {
+    pub pause_flags: u8,
+    pub changed_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AssetPauseChanged {
+    pub asset_id: u64,
     pub paused: bool,
+    pub changed_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AssetDeactivated {
+    pub asset_id: u64,
+    pub deactivated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AssetReactivated {
+    pub asset_id: u64,
+    pub reactivated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AssetRetired {
+    pub asset_id: u64,
+    pub retired_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AssetOwnerTransferProposed {
+    pub asset_id: u64,
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AssetOwnerTransferApproved {
+    pub asset_id: u64,
+    pub pending_owner: Pubkey,
+    pub approved_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AssetOwnerTransferAccepted {
+    pub asset_id: u64,
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AssetOwnerTransferCancelled {
+    pub asset_id: u64,
+    pub cancelled_owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AssetIdAllocationChanged {
+    pub allocate_asset_ids: bool,
+    pub next_asset_id: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AssetReferenceRegistered {
+    pub asset_id: u64,
+    pub issuer: Pubkey,
+    pub external_reference: String,
+}
+
+#[event]
+pub struct GuardianUpdated {
+    pub previous_guardian: Option<Pubkey>,
+    pub guardian: Option<Pubkey>,
     pub timestamp: i64,
 }
 
@@ -615,4 +2750,46 @@ pub enum ErrorCode {
     InvalidCrossChainOperation,
     #[msg("Unauthorized access")]
     Unauthorized,
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+    #[msg("Outbound transfers are still pending")]
+    OutboundTransfersPending,
+    #[msg("Change must go through the timelock")]
+    TimelockRequired,
+    #[msg("Asset is paused")]
+    AssetPaused,
+    #[msg("Unknown pause flags")]
+    InvalidPauseFlags,
+    #[msg("Asset is already active")]
+    AssetAlreadyActive,
+    #[msg("Asset still has circulating supply")]
+    CirculatingSupplyNotZero,
+    #[msg("No owner transfer is pending")]
+    NoPendingOwner,
+    #[msg("Owner transfer requires registry authority approval")]
+    OwnerTransferNotApproved,
+    #[msg("Asset id must be the next registry-allocated id, or a caller id while allocation is off")]
+    AssetIdNotAllocated,
+    #[msg("External reference must be 1-64 characters")]
+    InvalidExternalReference,
+    #[msg("Mint does not belong to this asset")]
+    MintMismatch,
+    #[msg("Recipient passport does not meet the asset's KYC level")]
+    KycRequirementNotMet,
+    #[msg("Metadata update reason is too long (max 100 characters)")]
+    MetadataReasonTooLong,
+    #[msg("Metadata content hash must be set")]
+    MissingContentHash,
+    #[msg("Lowering the KYC level of an issued asset requires the timelock")]
+    KycDowngradeRequiresTimelock,
+    #[msg("Asset is retired")]
+    AssetAlreadyRetired,
+    #[msg("Asset has pending redemptions, open distributions, unclaimed allocations or an open sale")]
+    AssetHasOpenObligations,
+    #[msg("Transfer amount must be positive")]
+    InvalidTransferAmount,
+    #[msg("Outbound transfer can only be cancelled after the delivery timeout")]
+    OutboundTransferNotExpired,
+    #[msg("Asset was paused by the guardian or a Pauser and only a Pauser can unpause it")]
+    AssetPausedByRegistry,
 }
@@ -0,0 +1,89 @@
+//! SHA-256 Merkle proofs with sorted pairs. Leaves and inner nodes are
+//! domain-separated so an inner node can never be passed off as a leaf.
+
+use anchor_lang::prelude::*;
+use sha2::{Digest, Sha256};
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+pub fn leaf_hash(parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+pub fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(first);
+    hasher.update(second);
+    hasher.finalize().into()
+}
+
+pub fn verify_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof
+        .iter()
+        .fold(leaf, |node, sibling| hash_pair(&node, sibling));
+    computed == *root
+}
+
+/// Leaf committing to `holder` owning `balance` base units
+pub fn balance_leaf(holder: &Pubkey, balance: u64) -> [u8; 32] {
+    leaf_hash(&[holder.as_ref(), &balance.to_le_bytes()])
+}
+
+/// Leaf committing to allocation `index` of `amount` base units to `recipient`
+pub fn allocation_leaf(index: u32, recipient: &Pubkey, amount: u64) -> [u8; 32] {
+    leaf_hash(&[&index.to_le_bytes(), recipient.as_ref(), &amount.to_le_bytes()])
+}
+
+/// Host-side tree builder producing roots and proofs accepted by `verify_proof`
+#[cfg(not(target_os = "solana"))]
+pub struct MerkleTree {
+    /// `layers[0]` holds the leaves, the last layer holds the root
+    layers: Vec<Vec<[u8; 32]>>,
+}
+
+#[cfg(not(target_os = "solana"))]
+impl MerkleTree {
+    pub fn new(leaves: Vec<[u8; 32]>) -> Self {
+        assert!(!leaves.is_empty(), "Merkle tree needs at least one leaf");
+        let mut layers = vec![leaves];
+        while layers.last().unwrap().len() > 1 {
+            let next = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash_pair(left, right),
+                    // An unpaired node moves up unchanged
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+        Self { layers }
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.layers.last().unwrap()[0]
+    }
+
+    pub fn proof(&self, index: usize) -> Vec<[u8; 32]> {
+        let mut proof = Vec::new();
+        let mut position = index;
+        for layer in &self.layers[..self.layers.len() - 1] {
+            if let Some(sibling) = layer.get(position ^ 1) {
+                proof.push(*sibling);
+            }
+            position /= 2;
+        }
+        proof
+    }
+}
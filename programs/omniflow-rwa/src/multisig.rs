@@ -0,0 +1,492 @@
+use anchor_lang::prelude::*;
+
+use crate::chains::AddressFormat;
+use crate::{EndpointUpdate, Registry};
+
+pub const MAX_MULTISIG_SIGNERS: usize = 10;
+pub const MAX_ACTION_LEN: usize = 128;
+
+/// Privileged registry operation that a multisig proposal can authorize
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub enum PrivilegedAction {
+    SetRegistryPause {
+        paused: bool,
+    },
+    SetPauseFlags {
+        pause_flags: u8,
+    },
+    ProposeAuthority {
+        new_authority: Pubkey,
+    },
+    CancelAuthorityTransfer,
+    AddSupportedChain {
+        wormhole_chain_id: u16,
+        name: String,
+        evm_chain_id: Option<u64>,
+        address_format: AddressFormat,
+    },
+    DisableSupportedChain {
+        wormhole_chain_id: u16,
+    },
+    EnableSupportedChain {
+        wormhole_chain_id: u16,
+    },
+    AddMultisigSigner {
+        signer: Pubkey,
+    },
+    RemoveMultisigSigner {
+        signer: Pubkey,
+    },
+    ChangeMultisigThreshold {
+        threshold: u8,
+    },
+    SetAssetIdAllocation {
+        allocate_asset_ids: bool,
+    },
+    SetGuardian {
+        guardian: Option<Pubkey>,
+    },
+    UpdateBridgeConfig {
+        wormhole_bridge: EndpointUpdate,
+        layerzero_endpoint: EndpointUpdate,
+    },
+    GrantAdmin {
+        member: Pubkey,
+    },
+    RevokeAdmin {
+        member: Pubkey,
+    },
+    AppointReserveCustodian {
+        asset_id: u64,
+        custodian: Pubkey,
+    },
+}
+
+/// M-of-N signer set guarding privileged registry operations
+#[account]
+#[derive(InitSpace)]
+pub struct Multisig {
+    #[max_len(10)]
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
+    pub proposal_lifetime: i64,
+    pub proposal_count: u64,
+    /// Bumped whenever the signers or threshold change. Approvals are bits indexed by
+    /// signer position, so proposals from an earlier generation cannot be approved or
+    /// executed.
+    pub generation: u64,
+    pub bump: u8,
+}
+
+impl Multisig {
+    fn signer_index(&self, key: &Pubkey) -> Result<usize> {
+        self.signers
+            .iter()
+            .position(|signer| signer == key)
+            .ok_or_else(|| error!(MultisigError::NotASigner))
+    }
+
+    fn require_current(&self, proposal: &MultisigProposal) -> Result<()> {
+        require!(
+            proposal.generation == self.generation,
+            MultisigError::ProposalStale
+        );
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct MultisigProposal {
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    /// Borsh-serialized `PrivilegedAction`
+    #[max_len(128)]
+    pub action: Vec<u8>,
+    /// Bit `i` is set once `signers[i]` approved
+    pub approvals: u16,
+    pub created_at: i64,
+    pub expires_at: i64,
+    pub executed: bool,
+    pub consumed: bool,
+    /// Multisig generation the proposal was created in
+    pub generation: u64,
+    pub bump: u8,
+}
+
+impl MultisigProposal {
+    /// Use an executed proposal for `action`, so it authorizes exactly one call
+    pub fn consume(&mut self, action: &PrivilegedAction) -> Result<()> {
+        require!(self.executed, MultisigError::ProposalNotExecuted);
+        require!(!self.consumed, MultisigError::ProposalConsumed);
+        require!(
+            Clock::get()?.unix_timestamp <= self.expires_at,
+            MultisigError::ProposalExpired
+        );
+        require!(
+            self.action == action.try_to_vec()?,
+            MultisigError::ActionMismatch
+        );
+
+        self.consumed = true;
+        Ok(())
+    }
+}
+
+/// Consume `proposal` for `action` if one was supplied. Returns false when the
+/// caller must fall back to the single-key authorization path, which is only
+/// open until a multisig is created. Proposals executed before the signers or
+/// threshold changed cannot be consumed.
+pub fn consume_proposal(
+    multisig: &UncheckedAccount,
+    proposal: &mut Option<Account<MultisigProposal>>,
+    action: &PrivilegedAction,
+) -> Result<bool> {
+    match proposal {
+        Some(proposal) => {
+            Account::<Multisig>::try_from(multisig)?.require_current(proposal)?;
+            proposal.consume(action)?;
+            Ok(true)
+        }
+        None => {
+            require!(multisig.data_is_empty(), MultisigError::MultisigRequired);
+            Ok(false)
+        }
+    }
+}
+
+#[derive(Accounts)]
+pub struct CreateMultisig<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Multisig::INIT_SPACE,
+        seeds = [b"multisig"],
+        bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump,
+        has_one = authority
+    )]
+    pub registry: Account<'info, Registry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposePrivilegedAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + MultisigProposal::INIT_SPACE,
+        seeds = [b"multisig_proposal", &multisig.proposal_count.to_le_bytes()],
+        bump
+    )]
+    pub proposal: Account<'info, MultisigProposal>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApprovePrivilegedAction<'info> {
+    #[account(
+        seeds = [b"multisig"],
+        bump = multisig.bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig_proposal", &proposal.proposal_id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, MultisigProposal>,
+
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecutePrivilegedAction<'info> {
+    #[account(
+        seeds = [b"multisig"],
+        bump = multisig.bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig_proposal", &proposal.proposal_id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, MultisigProposal>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMultisig<'info> {
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    /// Executed proposal authorizing the change
+    #[account(
+        mut,
+        seeds = [b"multisig_proposal", &multisig_proposal.proposal_id.to_le_bytes()],
+        bump = multisig_proposal.bump
+    )]
+    pub multisig_proposal: Account<'info, MultisigProposal>,
+}
+
+// Instructions
+pub fn create_multisig(
+    ctx: Context<CreateMultisig>,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+    proposal_lifetime: i64,
+) -> Result<()> {
+    require!(
+        !signers.is_empty() && signers.len() <= MAX_MULTISIG_SIGNERS,
+        MultisigError::InvalidSignerSet
+    );
+    require!(
+        threshold > 0 && threshold as usize <= signers.len(),
+        MultisigError::InvalidThreshold
+    );
+    require!(proposal_lifetime > 0, MultisigError::InvalidProposalLifetime);
+    for (i, signer) in signers.iter().enumerate() {
+        require!(!signers[..i].contains(signer), MultisigError::InvalidSignerSet);
+    }
+
+    let multisig = &mut ctx.accounts.multisig;
+    multisig.signers = signers.clone();
+    multisig.threshold = threshold;
+    multisig.proposal_lifetime = proposal_lifetime;
+    multisig.proposal_count = 0;
+    multisig.generation = 0;
+    multisig.bump = ctx.bumps.multisig;
+
+    emit!(MultisigCreated {
+        signers,
+        threshold,
+        proposal_lifetime,
+    });
+
+    Ok(())
+}
+
+pub fn propose_privileged_action(
+    ctx: Context<ProposePrivilegedAction>,
+    action: PrivilegedAction,
+) -> Result<()> {
+    let multisig = &mut ctx.accounts.multisig;
+    let proposer = ctx.accounts.proposer.key();
+    let signer_index = multisig.signer_index(&proposer)?;
+
+    let action_data = action.try_to_vec()?;
+    require!(action_data.len() <= MAX_ACTION_LEN, MultisigError::ActionTooLarge);
+
+    let now = Clock::get()?.unix_timestamp;
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.proposal_id = multisig.proposal_count;
+    proposal.proposer = proposer;
+    proposal.action = action_data;
+    proposal.approvals = 1 << signer_index;
+    proposal.created_at = now;
+    proposal.expires_at = now.checked_add(multisig.proposal_lifetime).unwrap();
+    proposal.executed = false;
+    proposal.consumed = false;
+    proposal.generation = multisig.generation;
+    proposal.bump = ctx.bumps.proposal;
+
+    multisig.proposal_count = multisig.proposal_count.checked_add(1).unwrap();
+
+    emit!(PrivilegedActionProposed {
+        proposal_id: proposal.proposal_id,
+        proposer,
+        action,
+        expires_at: proposal.expires_at,
+    });
+
+    Ok(())
+}
+
+pub fn approve_privileged_action(ctx: Context<ApprovePrivilegedAction>) -> Result<()> {
+    let signer = ctx.accounts.signer.key();
+    let multisig = &ctx.accounts.multisig;
+    let signer_index = multisig.signer_index(&signer)?;
+    multisig.require_current(&ctx.accounts.proposal)?;
+    let proposal = &mut ctx.accounts.proposal;
+
+    require!(!proposal.executed, MultisigError::ProposalAlreadyExecuted);
+    require!(
+        Clock::get()?.unix_timestamp <= proposal.expires_at,
+        MultisigError::ProposalExpired
+    );
+    require!(
+        proposal.approvals & (1 << signer_index) == 0,
+        MultisigError::AlreadyApproved
+    );
+
+    proposal.approvals |= 1 << signer_index;
+
+    emit!(PrivilegedActionApproved {
+        proposal_id: proposal.proposal_id,
+        signer,
+        approvals: proposal.approvals.count_ones() as u8,
+    });
+
+    Ok(())
+}
+
+pub fn execute_privileged_action(ctx: Context<ExecutePrivilegedAction>) -> Result<()> {
+    let multisig = &ctx.accounts.multisig;
+    multisig.require_current(&ctx.accounts.proposal)?;
+    let threshold = multisig.threshold;
+    let proposal = &mut ctx.accounts.proposal;
+
+    require!(!proposal.executed, MultisigError::ProposalAlreadyExecuted);
+    require!(
+        Clock::get()?.unix_timestamp <= proposal.expires_at,
+        MultisigError::ProposalExpired
+    );
+    require!(
+        proposal.approvals.count_ones() >= threshold as u32,
+        MultisigError::ThresholdNotMet
+    );
+
+    proposal.executed = true;
+
+    emit!(PrivilegedActionExecuted {
+        proposal_id: proposal.proposal_id,
+        approvals: proposal.approvals.count_ones() as u8,
+    });
+
+    Ok(())
+}
+
+/// Add or remove a signer or change the threshold. Signers are rotated by adding the
+/// new key and removing the old one, each through its own proposal.
+pub fn update_multisig(ctx: Context<UpdateMultisig>, action: PrivilegedAction) -> Result<()> {
+    ctx.accounts.multisig.require_current(&ctx.accounts.multisig_proposal)?;
+    ctx.accounts.multisig_proposal.consume(&action)?;
+
+    let multisig = &mut ctx.accounts.multisig;
+    match action {
+        PrivilegedAction::AddMultisigSigner { signer } => {
+            require!(
+                multisig.signers.len() < MAX_MULTISIG_SIGNERS && !multisig.signers.contains(&signer),
+                MultisigError::InvalidSignerSet
+            );
+            multisig.signers.push(signer);
+        }
+        PrivilegedAction::RemoveMultisigSigner { signer } => {
+            let index = multisig.signer_index(&signer)?;
+            multisig.signers.remove(index);
+        }
+        PrivilegedAction::ChangeMultisigThreshold { threshold } => {
+            multisig.threshold = threshold;
+        }
+        _ => return err!(MultisigError::ActionMismatch),
+    }
+    require!(!multisig.signers.is_empty(), MultisigError::InvalidSignerSet);
+    require!(
+        multisig.threshold > 0 && multisig.threshold as usize <= multisig.signers.len(),
+        MultisigError::InvalidThreshold
+    );
+    multisig.generation = multisig.generation.checked_add(1).unwrap();
+
+    emit!(MultisigUpdated {
+        signers: multisig.signers.clone(),
+        threshold: multisig.threshold,
+        generation: multisig.generation,
+    });
+
+    Ok(())
+}
+
+// Events
+#[event]
+pub struct MultisigCreated {
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
+    pub proposal_lifetime: i64,
+}
+
+#[event]
+pub struct PrivilegedActionProposed {
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub action: PrivilegedAction,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct PrivilegedActionApproved {
+    pub proposal_id: u64,
+    pub signer: Pubkey,
+    pub approvals: u8,
+}
+
+#[event]
+pub struct PrivilegedActionExecuted {
+    pub proposal_id: u64,
+    pub approvals: u8,
+}
+
+#[event]
+pub struct MultisigUpdated {
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
+    pub generation: u64,
+}
+
+// Errors
+#[error_code(offset = 6300)]
+pub enum MultisigError {
+    #[msg("Signer set must hold 1-10 distinct keys")]
+    InvalidSignerSet,
+    #[msg("Threshold must be between 1 and the number of signers")]
+    InvalidThreshold,
+    #[msg("Proposal lifetime must be positive")]
+    InvalidProposalLifetime,
+    #[msg("Signer is not part of the multisig")]
+    NotASigner,
+    #[msg("Serialized action is too large")]
+    ActionTooLarge,
+    #[msg("Signer already approved this proposal")]
+    AlreadyApproved,
+    #[msg("Proposal has expired")]
+    ProposalExpired,
+    #[msg("Proposal was already executed")]
+    ProposalAlreadyExecuted,
+    #[msg("Not enough approvals to execute the proposal")]
+    ThresholdNotMet,
+    #[msg("Proposal has not been executed")]
+    ProposalNotExecuted,
+    #[msg("Proposal was already used")]
+    ProposalConsumed,
+    #[msg("Proposal does not authorize this action")]
+    ActionMismatch,
+    #[msg("A multisig exists, so this action needs an executed proposal")]
+    MultisigRequired,
+    #[msg("Multisig signers or threshold changed since the proposal was created")]
+    ProposalStale,
+}
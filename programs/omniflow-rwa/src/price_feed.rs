@@ -0,0 +1,356 @@
+//! Market pricing for fungible underlyings read from Pyth v2 price accounts.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey;
+
+use crate::roles::{require_role, Role, RoleMembership};
+use crate::{AssetType, RWAAsset, Registry};
+
+/// Pyth oracle program owning mainnet and devnet price accounts
+pub const PYTH_ORACLE_PROGRAM_ID: Pubkey = pubkey!("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH");
+/// `mock-pyth` program standing in for Pyth on localnet
+pub const MOCK_PRICE_FEED_PROGRAM_ID: Pubkey = pubkey!("G2FDWn5jwDwszophCnsjkecjkMJ8mvXTof3BhNhu1DaD");
+
+/// Only program whose price accounts are trusted
+#[cfg(not(feature = "mock-price-feed"))]
+pub const PRICE_FEED_PROGRAM_ID: Pubkey = PYTH_ORACLE_PROGRAM_ID;
+#[cfg(feature = "mock-price-feed")]
+pub const PRICE_FEED_PROGRAM_ID: Pubkey = MOCK_PRICE_FEED_PROGRAM_ID;
+
+const PYTH_MAGIC: u32 = 0xa1b2_c3d4;
+const PYTH_VERSION_2: u32 = 2;
+const PYTH_PRICE_ACCOUNT: u32 = 3;
+const PYTH_STATUS_TRADING: u32 = 1;
+/// Bytes up to and including the aggregate price, which is all that is read
+pub const PYTH_PRICE_ACCOUNT_LEN: usize = 240;
+
+/// Aggregate price read from a Pyth v2 price account
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PythPrice {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+impl PythPrice {
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        require!(data.len() >= PYTH_PRICE_ACCOUNT_LEN, PriceFeedError::InvalidPriceAccount);
+        let u32_at = |offset: usize| u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+        let u64_at = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+        require!(
+            u32_at(0) == PYTH_MAGIC && u32_at(4) == PYTH_VERSION_2 && u32_at(8) == PYTH_PRICE_ACCOUNT,
+            PriceFeedError::InvalidPriceAccount
+        );
+        require!(u32_at(224) == PYTH_STATUS_TRADING, PriceFeedError::PriceUnavailable);
+        Ok(Self {
+            price: u64_at(208) as i64,
+            conf: u64_at(216),
+            expo: u32_at(20) as i32,
+            publish_time: u64_at(96) as i64,
+        })
+    }
+}
+
+/// Price feed an asset is valued against, stored at `[b"price_feed", asset_id]`
+#[account]
+#[derive(InitSpace)]
+pub struct PriceFeed {
+    pub asset_id: u64,
+    /// Pyth price account quoting one unit of the underlying
+    pub price_account: Pubkey,
+    /// Program owning `price_account`, always `PRICE_FEED_PROGRAM_ID`
+    pub feed_program: Pubkey,
+    pub max_staleness: i64,
+    /// Widest accepted confidence interval relative to the price
+    pub max_confidence_bps: u16,
+    /// Underlying held in custody, with `quantity_decimals` decimals
+    pub custody_quantity: u64,
+    pub quantity_decimals: u8,
+    /// Decimals of the valuation written to `total_value`, e.g. 2 for cents
+    pub value_decimals: u8,
+    pub last_value: u64,
+    pub last_publish_time: i64,
+    pub bump: u8,
+}
+
+impl PriceFeed {
+    /// Value of the custody quantity at `price`, after staleness and confidence checks
+    pub fn value_at(&self, price: &PythPrice, now: i64) -> Result<u64> {
+        require!(price.price > 0, PriceFeedError::PriceUnavailable);
+        require!(
+            now - price.publish_time <= self.max_staleness,
+            PriceFeedError::StalePrice
+        );
+        require!(
+            (price.conf as u128) * 10_000 <= (price.price as u128) * self.max_confidence_bps as u128,
+            PriceFeedError::ConfidenceTooWide
+        );
+        custody_value(
+            price.price as u64,
+            price.expo,
+            self.custody_quantity,
+            self.quantity_decimals,
+            self.value_decimals,
+        )
+        .ok_or_else(|| PriceFeedError::ValuationOverflow.into())
+    }
+
+    /// Read `price_account` and value the custody quantity at the current price
+    pub fn read_value(&self, price_account: &AccountInfo) -> Result<(u64, PythPrice)> {
+        require_keys_eq!(
+            price_account.key(),
+            self.price_account,
+            PriceFeedError::PriceAccountMismatch
+        );
+        require_keys_eq!(
+            *price_account.owner,
+            self.feed_program,
+            PriceFeedError::PriceAccountMismatch
+        );
+        let price = PythPrice::parse(&price_account.try_borrow_data()?)?;
+        let value = self.value_at(&price, Clock::get()?.unix_timestamp)?;
+        Ok((value, price))
+    }
+}
+
+/// `quantity` units priced at `price * 10^expo` each, in units of `10^-value_decimals`
+pub fn custody_value(
+    price: u64,
+    expo: i32,
+    quantity: u64,
+    quantity_decimals: u8,
+    value_decimals: u8,
+) -> Option<u64> {
+    let scale = expo + value_decimals as i32 - quantity_decimals as i32;
+    let raw = (price as u128).checked_mul(quantity as u128)?;
+    let value = if scale >= 0 {
+        raw.checked_mul(10u128.checked_pow(scale as u32)?)?
+    } else {
+        raw / 10u128.checked_pow(scale.unsigned_abs())?
+    };
+    u64::try_from(value).ok()
+}
+
+/// Bring `total_value` up to the market price before it is used, for assets with a price feed
+pub fn refresh_market_value(
+    asset: &mut RWAAsset,
+    price_feed: &Option<Account<PriceFeed>>,
+    price_account: &Option<UncheckedAccount>,
+) -> Result<()> {
+    if asset.price_feed_required {
+        let (Some(price_feed), Some(price_account)) = (price_feed, price_account) else {
+            return err!(PriceFeedError::PriceFeedMissing);
+        };
+        require!(price_feed.asset_id == asset.asset_id, PriceFeedError::PriceFeedMissing);
+        asset.total_value = price_feed.read_value(price_account)?.0;
+    }
+    Ok(())
+}
+
+/// Builder for Pyth v2 price account data, used by tests and the `mock-pyth` program
+pub fn mock_price_account_data(price: i64, conf: u64, expo: i32, publish_time: i64) -> Vec<u8> {
+    let mut data = vec![0u8; PYTH_PRICE_ACCOUNT_LEN];
+    data[0..4].copy_from_slice(&PYTH_MAGIC.to_le_bytes());
+    data[4..8].copy_from_slice(&PYTH_VERSION_2.to_le_bytes());
+    data[8..12].copy_from_slice(&PYTH_PRICE_ACCOUNT.to_le_bytes());
+    data[12..16].copy_from_slice(&(PYTH_PRICE_ACCOUNT_LEN as u32).to_le_bytes());
+    data[20..24].copy_from_slice(&expo.to_le_bytes());
+    data[96..104].copy_from_slice(&publish_time.to_le_bytes());
+    data[208..216].copy_from_slice(&price.to_le_bytes());
+    data[216..224].copy_from_slice(&conf.to_le_bytes());
+    data[224..228].copy_from_slice(&PYTH_STATUS_TRADING.to_le_bytes());
+    data
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct ConfigurePriceFeed<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + PriceFeed::INIT_SPACE,
+        seeds = [b"price_feed", &asset_id.to_le_bytes()],
+        bump
+    )]
+    pub price_feed: Account<'info, PriceFeed>,
+
+    /// CHECK: Parsed and validated as a Pyth v2 price account
+    pub price_account: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        seeds = [b"role", &[Role::Admin as u8], authority.key().as_ref()],
+        bump = admin_role.bump
+    )]
+    pub admin_role: Option<Account<'info, RoleMembership>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshPriceValuation<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &price_feed.asset_id.to_le_bytes()],
+        bump = asset.bump
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        mut,
+        seeds = [b"price_feed", &price_feed.asset_id.to_le_bytes()],
+        bump = price_feed.bump
+    )]
+    pub price_feed: Account<'info, PriceFeed>,
+
+    /// CHECK: Must match the configured price account and feed program
+    pub price_account: UncheckedAccount<'info>,
+}
+
+// Instructions
+pub fn configure_price_feed(
+    ctx: Context<ConfigurePriceFeed>,
+    asset_id: u64,
+    max_staleness: i64,
+    max_confidence_bps: u16,
+    custody_quantity: u64,
+    quantity_decimals: u8,
+    value_decimals: u8,
+) -> Result<()> {
+    require_role(
+        &ctx.accounts.registry,
+        &ctx.accounts.authority.key(),
+        &ctx.accounts.admin_role,
+        Role::Admin,
+    )?;
+    require!(
+        matches!(
+            ctx.accounts.asset.asset_type,
+            AssetType::PreciousMetals | AssetType::Commodities
+        ),
+        PriceFeedError::UnsupportedAssetType
+    );
+    require!(max_staleness > 0, PriceFeedError::InvalidFeedPolicy);
+    require!(
+        max_confidence_bps > 0 && max_confidence_bps <= 10_000,
+        PriceFeedError::InvalidFeedPolicy
+    );
+    // Reject accounts that are not Pyth price accounts up front. Anyone can write the
+    // Pyth layout into an account they own, so the owner is pinned, not taken on trust.
+    require_keys_eq!(
+        *ctx.accounts.price_account.owner,
+        PRICE_FEED_PROGRAM_ID,
+        PriceFeedError::UntrustedFeedProgram
+    );
+    PythPrice::parse(&ctx.accounts.price_account.try_borrow_data()?)?;
+
+    let price_feed = &mut ctx.accounts.price_feed;
+    price_feed.asset_id = asset_id;
+    price_feed.price_account = ctx.accounts.price_account.key();
+    price_feed.feed_program = PRICE_FEED_PROGRAM_ID;
+    price_feed.max_staleness = max_staleness;
+    price_feed.max_confidence_bps = max_confidence_bps;
+    price_feed.custody_quantity = custody_quantity;
+    price_feed.quantity_decimals = quantity_decimals;
+    price_feed.value_decimals = value_decimals;
+    price_feed.bump = ctx.bumps.price_feed;
+
+    ctx.accounts.asset.price_feed_required = true;
+
+    emit!(PriceFeedConfigured {
+        asset_id,
+        price_account: price_feed.price_account,
+        feed_program: price_feed.feed_program,
+        max_staleness,
+        max_confidence_bps,
+        custody_quantity,
+    });
+
+    Ok(())
+}
+
+pub fn refresh_price_valuation(ctx: Context<RefreshPriceValuation>) -> Result<()> {
+    let price_feed = &mut ctx.accounts.price_feed;
+    let (value, price) = price_feed.read_value(&ctx.accounts.price_account)?;
+    price_feed.last_value = value;
+    price_feed.last_publish_time = price.publish_time;
+
+    let asset = &mut ctx.accounts.asset;
+    let previous_value = asset.total_value;
+    asset.total_value = value;
+
+    emit!(MarketValuationUpdated {
+        asset_id: asset.asset_id,
+        previous_value,
+        value,
+        price: price.price,
+        conf: price.conf,
+        expo: price.expo,
+        publish_time: price.publish_time,
+    });
+
+    Ok(())
+}
+
+// Events
+#[event]
+pub struct PriceFeedConfigured {
+    pub asset_id: u64,
+    pub price_account: Pubkey,
+    pub feed_program: Pubkey,
+    pub max_staleness: i64,
+    pub max_confidence_bps: u16,
+    pub custody_quantity: u64,
+}
+
+#[event]
+pub struct MarketValuationUpdated {
+    pub asset_id: u64,
+    pub previous_value: u64,
+    pub value: u64,
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+// Errors
+#[error_code(offset = 7400)]
+pub enum PriceFeedError {
+    #[msg("Price feeds are only supported for precious metals and commodities")]
+    UnsupportedAssetType,
+    #[msg("Price feed staleness and confidence limits are invalid")]
+    InvalidFeedPolicy,
+    #[msg("Account is not a Pyth v2 price account")]
+    InvalidPriceAccount,
+    #[msg("Price account does not match the configured feed")]
+    PriceAccountMismatch,
+    #[msg("Feed is not trading or has no positive price")]
+    PriceUnavailable,
+    #[msg("Feed price is stale")]
+    StalePrice,
+    #[msg("Feed confidence interval is too wide")]
+    ConfidenceTooWide,
+    #[msg("Market valuation overflows")]
+    ValuationOverflow,
+    #[msg("Asset requires its price feed accounts")]
+    PriceFeedMissing,
+    #[msg("Price account is not owned by the trusted oracle program")]
+    UntrustedFeedProgram,
+}
@@ -0,0 +1,373 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+use crate::price_feed::{refresh_market_value, PriceFeed};
+use crate::{ErrorCode, RWAAsset, Registry, PAUSE_REDEMPTIONS};
+
+/// Off-chain settlement of a fulfilled redemption
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum SettlementReference {
+    /// Hash of the physical delivery or collection reference
+    PhysicalDelivery([u8; 32]),
+    /// Hash of the fiat wire reference
+    FiatWire([u8; 32]),
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum RedemptionStatus {
+    Pending,
+    Fulfilled,
+    Rejected,
+}
+
+/// Holder request to redeem tokens against the underlying asset, stored at
+/// `[b"redemption", asset_id, request_id]`
+#[account]
+#[derive(InitSpace)]
+pub struct RedemptionRequest {
+    pub asset_id: u64,
+    pub request_id: u64,
+    pub holder: Pubkey,
+    pub amount: u64,
+    pub status: RedemptionStatus,
+    pub requested_at: i64,
+    pub resolved_at: Option<i64>,
+    pub settlement: Option<SettlementReference>,
+    pub bump: u8,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct RequestRedemption<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        init,
+        payer = holder,
+        space = 8 + RedemptionRequest::INIT_SPACE,
+        seeds = [
+            b"redemption",
+            &asset_id.to_le_bytes(),
+            &asset.redemption_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+
+    #[account(
+        address = asset.mint @ ErrorCode::MintMismatch
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// Escrow for tokens awaiting redemption, owned by the asset PDA
+    #[account(
+        init_if_needed,
+        payer = holder,
+        token::mint = mint,
+        token::authority = asset,
+        seeds = [b"redemption_vault", &asset_id.to_le_bytes()],
+        bump
+    )]
+    pub redemption_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = holder
+    )]
+    pub holder_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct FulfillRedemption<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump,
+        has_one = owner
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"redemption",
+            &asset_id.to_le_bytes(),
+            &redemption_request.request_id.to_le_bytes()
+        ],
+        bump = redemption_request.bump
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+
+    #[account(
+        mut,
+        address = asset.mint @ ErrorCode::MintMismatch
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"redemption_vault", &asset_id.to_le_bytes()],
+        bump
+    )]
+    pub redemption_vault: Account<'info, TokenAccount>,
+
+    /// Required when the asset is valued from a price feed
+    #[account(
+        seeds = [b"price_feed", &asset_id.to_le_bytes()],
+        bump = price_feed.bump
+    )]
+    pub price_feed: Option<Account<'info, PriceFeed>>,
+
+    /// CHECK: Validated against `price_feed` by `refresh_market_value`
+    pub price_account: Option<UncheckedAccount<'info>>,
+
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct RejectRedemption<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump,
+        has_one = owner
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"redemption",
+            &asset_id.to_le_bytes(),
+            &redemption_request.request_id.to_le_bytes()
+        ],
+        bump = redemption_request.bump
+    )]
+    pub redemption_request: Account<'info, RedemptionRequest>,
+
+    #[account(
+        mut,
+        seeds = [b"redemption_vault", &asset_id.to_le_bytes()],
+        bump
+    )]
+    pub redemption_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = holder_token_account.owner == redemption_request.holder @ ErrorCode::Unauthorized,
+        constraint = holder_token_account.mint == redemption_vault.mint @ ErrorCode::MintMismatch
+    )]
+    pub holder_token_account: Account<'info, TokenAccount>,
+
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+// Instructions
+pub fn request_redemption(
+    ctx: Context<RequestRedemption>,
+    asset_id: u64,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, RedemptionError::InvalidAmount);
+    ctx.accounts.registry.require_not_paused(PAUSE_REDEMPTIONS)?;
+    let asset = &mut ctx.accounts.asset;
+    asset.require_operational()?;
+
+    // Escrow the tokens until the issuer settles or rejects the request
+    let cpi_accounts = token::Transfer {
+        from: ctx.accounts.holder_token_account.to_account_info(),
+        to: ctx.accounts.redemption_vault.to_account_info(),
+        authority: ctx.accounts.holder.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, amount)?;
+
+    let request_id = asset.redemption_count;
+    asset.redemption_count = request_id.checked_add(1).unwrap();
+    asset.pending_redemptions = asset.pending_redemptions.checked_add(1).unwrap();
+
+    let redemption_request = &mut ctx.accounts.redemption_request;
+    redemption_request.asset_id = asset_id;
+    redemption_request.request_id = request_id;
+    redemption_request.holder = ctx.accounts.holder.key();
+    redemption_request.amount = amount;
+    redemption_request.status = RedemptionStatus::Pending;
+    redemption_request.requested_at = Clock::get()?.unix_timestamp;
+    redemption_request.resolved_at = None;
+    redemption_request.settlement = None;
+    redemption_request.bump = ctx.bumps.redemption_request;
+
+    emit!(RedemptionRequested {
+        asset_id,
+        request_id,
+        holder: redemption_request.holder,
+        amount,
+        timestamp: redemption_request.requested_at,
+    });
+
+    Ok(())
+}
+
+pub fn fulfill_redemption(
+    ctx: Context<FulfillRedemption>,
+    asset_id: u64,
+    settlement: SettlementReference,
+) -> Result<()> {
+    let redemption_request = &mut ctx.accounts.redemption_request;
+    require!(
+        redemption_request.status == RedemptionStatus::Pending,
+        RedemptionError::RequestNotPending
+    );
+    let amount = redemption_request.amount;
+
+    let asset = &ctx.accounts.asset;
+    let asset_seeds = &[b"asset".as_ref(), &asset.asset_id.to_le_bytes(), &[asset.bump]];
+    let signer_seeds = &[&asset_seeds[..]];
+
+    let cpi_accounts = token::Burn {
+        mint: ctx.accounts.mint.to_account_info(),
+        from: ctx.accounts.redemption_vault.to_account_info(),
+        authority: ctx.accounts.asset.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token::burn(cpi_ctx, amount)?;
+
+    // The redeemed share of the underlying leaves the asset, so value per token is unchanged
+    let asset = &mut ctx.accounts.asset;
+    refresh_market_value(asset, &ctx.accounts.price_feed, &ctx.accounts.price_account)?;
+    let value_redeemed = (asset.total_value as u128)
+        .checked_mul(amount as u128)
+        .unwrap()
+        .checked_div(asset.total_supply as u128)
+        .unwrap() as u64;
+    asset.total_value = asset.total_value.checked_sub(value_redeemed).unwrap();
+    asset.total_supply = asset.total_supply.checked_sub(amount).unwrap();
+    asset.circulating_supply = asset.circulating_supply.checked_sub(amount).unwrap();
+    asset.pending_redemptions = asset.pending_redemptions.checked_sub(1).unwrap();
+
+    let now = Clock::get()?.unix_timestamp;
+    redemption_request.status = RedemptionStatus::Fulfilled;
+    redemption_request.resolved_at = Some(now);
+    redemption_request.settlement = Some(settlement);
+
+    emit!(RedemptionFulfilled {
+        asset_id,
+        request_id: redemption_request.request_id,
+        holder: redemption_request.holder,
+        amount,
+        value_redeemed,
+        settlement,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub fn reject_redemption(ctx: Context<RejectRedemption>, asset_id: u64) -> Result<()> {
+    let redemption_request = &mut ctx.accounts.redemption_request;
+    require!(
+        redemption_request.status == RedemptionStatus::Pending,
+        RedemptionError::RequestNotPending
+    );
+
+    let asset = &ctx.accounts.asset;
+    let asset_seeds = &[b"asset".as_ref(), &asset.asset_id.to_le_bytes(), &[asset.bump]];
+    let signer_seeds = &[&asset_seeds[..]];
+
+    // Return the escrowed tokens to the holder
+    let cpi_accounts = token::Transfer {
+        from: ctx.accounts.redemption_vault.to_account_info(),
+        to: ctx.accounts.holder_token_account.to_account_info(),
+        authority: ctx.accounts.asset.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token::transfer(cpi_ctx, redemption_request.amount)?;
+
+    let asset = &mut ctx.accounts.asset;
+    asset.pending_redemptions = asset.pending_redemptions.checked_sub(1).unwrap();
+
+    let now = Clock::get()?.unix_timestamp;
+    redemption_request.status = RedemptionStatus::Rejected;
+    redemption_request.resolved_at = Some(now);
+
+    emit!(RedemptionRejected {
+        asset_id,
+        request_id: redemption_request.request_id,
+        holder: redemption_request.holder,
+        amount: redemption_request.amount,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// Events
+#[event]
+pub struct RedemptionRequested {
+    pub asset_id: u64,
+    pub request_id: u64,
+    pub holder: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RedemptionFulfilled {
+    pub asset_id: u64,
+    pub request_id: u64,
+    pub holder: Pubkey,
+    pub amount: u64,
+    pub value_redeemed: u64,
+    pub settlement: SettlementReference,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RedemptionRejected {
+    pub asset_id: u64,
+    pub request_id: u64,
+    pub holder: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+// Errors
+#[error_code(offset = 6800)]
+pub enum RedemptionError {
+    #[msg("Redemption amount must be positive")]
+    InvalidAmount,
+    #[msg("Redemption request is not pending")]
+    RequestNotPending,
+}
@@ -0,0 +1,323 @@
+use anchor_lang::prelude::*;
+
+use crate::multisig::{consume_proposal, MultisigProposal, PrivilegedAction};
+use crate::price_feed::{PriceFeed, PriceFeedError};
+use crate::roles::{require_role, Role, RoleMembership};
+use crate::{RWAAsset, Registry};
+
+/// Custodian proof of reserve for an asset, stored at `[b"reserve", asset_id]`
+#[account]
+#[derive(InitSpace)]
+pub struct ReserveAttestation {
+    pub asset_id: u64,
+    /// Only signer allowed to post attestations, unset until an Admin appoints one
+    pub custodian: Pubkey,
+    /// Minting stops once the attestation is older than this many seconds
+    pub max_staleness: i64,
+    /// Token base units backed by one unit of attested quantity
+    pub tokens_per_unit: u64,
+    /// Underlying held in custody, e.g. bars, ounces or titles
+    pub quantity: u64,
+    /// SHA-256 of the audit report backing the attestation
+    pub audit_report_hash: [u8; 32],
+    pub auditor: Pubkey,
+    /// 0 until the first attestation is posted
+    pub attested_at: i64,
+    pub attestation_count: u64,
+    pub bump: u8,
+}
+
+impl ReserveAttestation {
+    pub fn address(asset_id: u64) -> Pubkey {
+        Pubkey::find_program_address(&[b"reserve", &asset_id.to_le_bytes()], &crate::ID).0
+    }
+
+    /// Most tokens the attested quantity can back
+    pub fn backed_supply(&self) -> u64 {
+        ((self.quantity as u128) * (self.tokens_per_unit as u128)).min(u64::MAX as u128) as u64
+    }
+
+    pub fn is_current(&self, now: i64) -> bool {
+        self.attestation_count > 0 && now - self.attested_at <= self.max_staleness
+    }
+
+    /// Reserves relative to `circulating_supply` in basis points, `None` with nothing issued
+    pub fn collateralization_bps(&self, circulating_supply: u64) -> Option<u64> {
+        if circulating_supply == 0 {
+            return None;
+        }
+        let ratio = (self.backed_supply() as u128) * 10_000 / circulating_supply as u128;
+        Some(ratio.min(u64::MAX as u128) as u64)
+    }
+}
+
+/// Require a current attestation covering the circulating supply, for assets with a custodian.
+/// Call after `record_mint` so the new supply is checked.
+pub fn require_reserves(
+    asset: &RWAAsset,
+    reserve: &Option<Account<ReserveAttestation>>,
+) -> Result<()> {
+    if asset.reserve_required {
+        let reserve = reserve.as_ref().ok_or(ReserveError::ReserveMissing)?;
+        require!(reserve.asset_id == asset.asset_id, ReserveError::ReserveMissing);
+        require!(
+            reserve.is_current(Clock::get()?.unix_timestamp),
+            ReserveError::AttestationStale
+        );
+        require!(
+            asset.circulating_supply <= reserve.backed_supply(),
+            ReserveError::InsufficientReserves
+        );
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct ConfigureReserve<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump,
+        has_one = owner
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + ReserveAttestation::INIT_SPACE,
+        seeds = [b"reserve", &asset_id.to_le_bytes()],
+        bump
+    )]
+    pub reserve: Account<'info, ReserveAttestation>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct AppointReserveCustodian<'info> {
+    #[account(
+        mut,
+        seeds = [b"reserve", &asset_id.to_le_bytes()],
+        bump = reserve.bump
+    )]
+    pub reserve: Account<'info, ReserveAttestation>,
+
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        seeds = [b"role", &[Role::Admin as u8], authority.key().as_ref()],
+        bump = admin_role.bump
+    )]
+    pub admin_role: Option<Account<'info, RoleMembership>>,
+
+    /// CHECK: Multisig PDA, checked only when a proposal is supplied
+    #[account(seeds = [b"multisig"], bump)]
+    pub multisig: UncheckedAccount<'info>,
+
+    /// Executed multisig proposal authorizing this call in place of an Admin
+    #[account(
+        mut,
+        seeds = [b"multisig_proposal", &multisig_proposal.proposal_id.to_le_bytes()],
+        bump = multisig_proposal.bump
+    )]
+    pub multisig_proposal: Option<Account<'info, MultisigProposal>>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PostReserveAttestation<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &reserve.asset_id.to_le_bytes()],
+        bump = asset.bump
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        mut,
+        seeds = [b"reserve", &reserve.asset_id.to_le_bytes()],
+        bump = reserve.bump,
+        has_one = custodian
+    )]
+    pub reserve: Account<'info, ReserveAttestation>,
+
+    /// Required when the asset is valued from a price feed
+    #[account(
+        mut,
+        seeds = [b"price_feed", &reserve.asset_id.to_le_bytes()],
+        bump = price_feed.bump
+    )]
+    pub price_feed: Option<Account<'info, PriceFeed>>,
+
+    /// CHECK: Validated against `price_feed` when reading the price
+    pub price_account: Option<UncheckedAccount<'info>>,
+
+    pub custodian: Signer<'info>,
+}
+
+// Instructions
+/// The owner sets the policy only; the custodian is appointed separately so an
+/// issuer cannot attest to its own reserves.
+pub fn configure_reserve(
+    ctx: Context<ConfigureReserve>,
+    asset_id: u64,
+    max_staleness: i64,
+    tokens_per_unit: u64,
+) -> Result<()> {
+    require!(max_staleness > 0, ReserveError::InvalidReservePolicy);
+    require!(tokens_per_unit > 0, ReserveError::InvalidReservePolicy);
+
+    let reserve = &mut ctx.accounts.reserve;
+    // Changing the unit would reinterpret an existing attestation
+    require!(
+        reserve.attestation_count == 0 || reserve.tokens_per_unit == tokens_per_unit,
+        ReserveError::InvalidReservePolicy
+    );
+    reserve.asset_id = asset_id;
+    reserve.max_staleness = max_staleness;
+    reserve.tokens_per_unit = tokens_per_unit;
+    reserve.bump = ctx.bumps.reserve;
+
+    ctx.accounts.asset.reserve_required = true;
+
+    emit!(ReservePolicyUpdated {
+        asset_id,
+        custodian: reserve.custodian,
+        max_staleness,
+        tokens_per_unit,
+    });
+
+    Ok(())
+}
+
+pub fn appoint_reserve_custodian(
+    ctx: Context<AppointReserveCustodian>,
+    asset_id: u64,
+    custodian: Pubkey,
+) -> Result<()> {
+    if ctx.accounts.multisig_proposal.is_some() {
+        let action = PrivilegedAction::AppointReserveCustodian { asset_id, custodian };
+        consume_proposal(&ctx.accounts.multisig, &mut ctx.accounts.multisig_proposal, &action)?;
+    } else {
+        require_role(
+            &ctx.accounts.registry,
+            &ctx.accounts.authority.key(),
+            &ctx.accounts.admin_role,
+            Role::Admin,
+        )?;
+    }
+
+    let reserve = &mut ctx.accounts.reserve;
+    let previous_custodian = reserve.custodian;
+    reserve.custodian = custodian;
+
+    emit!(ReserveCustodianAppointed {
+        asset_id,
+        previous_custodian,
+        custodian,
+        appointed_by: ctx.accounts.authority.key(),
+    });
+
+    Ok(())
+}
+
+pub fn post_reserve_attestation(
+    ctx: Context<PostReserveAttestation>,
+    quantity: u64,
+    audit_report_hash: [u8; 32],
+    auditor: Pubkey,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let reserve = &mut ctx.accounts.reserve;
+    reserve.quantity = quantity;
+    reserve.audit_report_hash = audit_report_hash;
+    reserve.auditor = auditor;
+    reserve.attested_at = now;
+    reserve.attestation_count = reserve.attestation_count.checked_add(1).unwrap();
+
+    // Price-fed assets are valued at the attested custody, not a quantity set at configuration
+    let asset = &mut ctx.accounts.asset;
+    if asset.price_feed_required {
+        let (Some(price_feed), Some(price_account)) =
+            (&mut ctx.accounts.price_feed, &ctx.accounts.price_account)
+        else {
+            return err!(PriceFeedError::PriceFeedMissing);
+        };
+        price_feed.custody_quantity = 10u64
+            .checked_pow(price_feed.quantity_decimals as u32)
+            .and_then(|scale| quantity.checked_mul(scale))
+            .ok_or(PriceFeedError::ValuationOverflow)?;
+        let (value, price) = price_feed.read_value(price_account)?;
+        price_feed.last_value = value;
+        price_feed.last_publish_time = price.publish_time;
+        asset.total_value = value;
+    }
+
+    let circulating_supply = asset.circulating_supply;
+    emit!(ReserveAttested {
+        asset_id: reserve.asset_id,
+        custodian: reserve.custodian,
+        quantity,
+        audit_report_hash,
+        auditor,
+        backed_supply: reserve.backed_supply(),
+        circulating_supply,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// Events
+#[event]
+pub struct ReservePolicyUpdated {
+    pub asset_id: u64,
+    pub custodian: Pubkey,
+    pub max_staleness: i64,
+    pub tokens_per_unit: u64,
+}
+
+#[event]
+pub struct ReserveCustodianAppointed {
+    pub asset_id: u64,
+    pub previous_custodian: Pubkey,
+    pub custodian: Pubkey,
+    pub appointed_by: Pubkey,
+}
+
+#[event]
+pub struct ReserveAttested {
+    pub asset_id: u64,
+    pub custodian: Pubkey,
+    pub quantity: u64,
+    pub audit_report_hash: [u8; 32],
+    pub auditor: Pubkey,
+    /// Below `circulating_supply` when the asset is undercollateralized
+    pub backed_supply: u64,
+    pub circulating_supply: u64,
+    pub timestamp: i64,
+}
+
+// Errors
+#[error_code(offset = 7500)]
+pub enum ReserveError {
+    #[msg("Reserve staleness and token ratio must be positive and the ratio fixed once attested")]
+    InvalidReservePolicy,
+    #[msg("Asset requires a reserve attestation account")]
+    ReserveMissing,
+    #[msg("Reserve attestation is missing or stale")]
+    AttestationStale,
+    #[msg("Minting would exceed attested reserves")]
+    InsufficientReserves,
+}
@@ -0,0 +1,228 @@
+use anchor_lang::prelude::*;
+
+use crate::multisig::{consume_proposal, MultisigProposal, PrivilegedAction};
+use crate::Registry;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum Role {
+    /// Grants and revokes every role except Admin
+    Admin,
+    /// Pauses and unpauses the registry
+    Pauser,
+    /// Issues and updates identity passports
+    ComplianceOfficer,
+    /// Completes inbound cross-chain transfers
+    BridgeRelayer,
+    /// Freezes holders and executes forced transfers
+    TransferAgent,
+    /// Registers new RWA assets
+    Issuer,
+    /// Submits asset valuations
+    ValuationOracle,
+}
+
+/// Membership of `member` in `role`, stored at `[b"role", role, member]`
+#[account]
+#[derive(InitSpace)]
+pub struct RoleMembership {
+    pub role: Role,
+    pub member: Pubkey,
+    pub granted_by: Pubkey,
+    pub granted_at: i64,
+    pub bump: u8,
+}
+
+impl RoleMembership {
+    /// Whether `membership` proves that `member` holds `role`
+    pub fn is_held_by(
+        membership: &Option<Account<RoleMembership>>,
+        member: &Pubkey,
+        role: Role,
+    ) -> bool {
+        matches!(membership, Some(m) if m.member == *member && m.role == role)
+    }
+}
+
+/// Require `signer` to hold `role`. The registry authority holds Admin implicitly and has
+/// to grant itself any other role, so every operational grant is visible and revocable.
+pub fn require_role(
+    registry: &Registry,
+    signer: &Pubkey,
+    membership: &Option<Account<RoleMembership>>,
+    role: Role,
+) -> Result<()> {
+    let is_authority_admin = role == Role::Admin && registry.authority == *signer;
+    require!(
+        is_authority_admin || RoleMembership::is_held_by(membership, signer, role),
+        RoleError::MissingRole
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(role: Role, member: Pubkey)]
+pub struct GrantRole<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RoleMembership::INIT_SPACE,
+        seeds = [b"role", &[role as u8], member.as_ref()],
+        bump
+    )]
+    pub membership: Account<'info, RoleMembership>,
+
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    /// Admin membership of the signer, when the signer is not the registry authority
+    #[account(
+        seeds = [b"role", &[Role::Admin as u8], authority.key().as_ref()],
+        bump = admin_role.bump
+    )]
+    pub admin_role: Option<Account<'info, RoleMembership>>,
+
+    /// CHECK: Multisig PDA; once created, only the multisig grants and revokes Admin
+    #[account(seeds = [b"multisig"], bump)]
+    pub multisig: UncheckedAccount<'info>,
+
+    /// Executed multisig proposal authorizing an Admin grant or revocation
+    #[account(
+        mut,
+        seeds = [b"multisig_proposal", &multisig_proposal.proposal_id.to_le_bytes()],
+        bump = multisig_proposal.bump
+    )]
+    pub multisig_proposal: Option<Account<'info, MultisigProposal>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeRole<'info> {
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"role", &[membership.role as u8], membership.member.as_ref()],
+        bump = membership.bump
+    )]
+    pub membership: Account<'info, RoleMembership>,
+
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    /// Admin membership of the signer, when the signer is not the registry authority
+    #[account(
+        seeds = [b"role", &[Role::Admin as u8], authority.key().as_ref()],
+        bump = admin_role.bump
+    )]
+    pub admin_role: Option<Account<'info, RoleMembership>>,
+
+    /// CHECK: Multisig PDA; once created, only the multisig grants and revokes Admin
+    #[account(seeds = [b"multisig"], bump)]
+    pub multisig: UncheckedAccount<'info>,
+
+    /// Executed multisig proposal authorizing an Admin grant or revocation
+    #[account(
+        mut,
+        seeds = [b"multisig_proposal", &multisig_proposal.proposal_id.to_le_bytes()],
+        bump = multisig_proposal.bump
+    )]
+    pub multisig_proposal: Option<Account<'info, MultisigProposal>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+// Instructions
+pub fn grant_role(ctx: Context<GrantRole>, role: Role, member: Pubkey) -> Result<()> {
+    let granted_by = ctx.accounts.authority.key();
+    if role == Role::Admin {
+        // New admins come from the multisig, or the registry authority before one exists
+        let action = PrivilegedAction::GrantAdmin { member };
+        if !consume_proposal(&ctx.accounts.multisig, &mut ctx.accounts.multisig_proposal, &action)? {
+            require!(
+                ctx.accounts.registry.authority == granted_by,
+                RoleError::AdminRoleReserved
+            );
+        }
+    } else {
+        require_role(&ctx.accounts.registry, &granted_by, &ctx.accounts.admin_role, Role::Admin)?;
+    }
+
+    let membership = &mut ctx.accounts.membership;
+    membership.role = role;
+    membership.member = member;
+    membership.granted_by = granted_by;
+    membership.granted_at = Clock::get()?.unix_timestamp;
+    membership.bump = ctx.bumps.membership;
+
+    emit!(RoleGranted {
+        role,
+        member,
+        granted_by,
+        timestamp: membership.granted_at,
+    });
+
+    Ok(())
+}
+
+pub fn revoke_role(ctx: Context<RevokeRole>) -> Result<()> {
+    let revoked_by = ctx.accounts.authority.key();
+    if ctx.accounts.membership.role == Role::Admin {
+        let action = PrivilegedAction::RevokeAdmin {
+            member: ctx.accounts.membership.member,
+        };
+        if !consume_proposal(&ctx.accounts.multisig, &mut ctx.accounts.multisig_proposal, &action)? {
+            require!(
+                ctx.accounts.registry.authority == revoked_by,
+                RoleError::AdminRoleReserved
+            );
+        }
+    } else {
+        require_role(&ctx.accounts.registry, &revoked_by, &ctx.accounts.admin_role, Role::Admin)?;
+    }
+    let membership = &ctx.accounts.membership;
+
+    emit!(RoleRevoked {
+        role: membership.role,
+        member: membership.member,
+        revoked_by,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Events
+#[event]
+pub struct RoleGranted {
+    pub role: Role,
+    pub member: Pubkey,
+    pub granted_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub role: Role,
+    pub member: Pubkey,
+    pub revoked_by: Pubkey,
+    pub timestamp: i64,
+}
+
+// Errors
+#[error_code(offset = 6200)]
+pub enum RoleError {
+    #[msg("Signer does not hold the required role")]
+    MissingRole,
+    #[msg("Only the multisig, or the registry authority before one exists, can grant or revoke Admin")]
+    AdminRoleReserved,
+}
@@ -0,0 +1,736 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+use crate::identity::{IdentityPassport, InvestorTier};
+use crate::price_feed::{refresh_market_value, PriceFeed};
+use crate::reserve::{require_reserves, ReserveAttestation};
+use crate::valuation::{require_current_valuation, ValuationHistory};
+use crate::{ErrorCode, KYCLevel, RWAAsset, RWATokensMinted, Registry, PAUSE_MINTING};
+
+/// Primary issuance of an asset's tokens against a quote token, stored at
+/// `[b"primary_sale", asset_id]`. Amounts are in RWA token base units.
+#[account]
+#[derive(InitSpace)]
+pub struct PrimarySale {
+    pub asset_id: u64,
+    pub quote_mint: Pubkey,
+    /// Quote base units per whole RWA token
+    pub price_per_token: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    /// Smallest single purchase
+    pub min_ticket: u64,
+    /// Largest total purchase per buyer
+    pub max_ticket: u64,
+    /// Sale is refunded unless this much is sold by `end_time`
+    pub soft_cap: u64,
+    pub hard_cap: u64,
+    pub required_kyc: KYCLevel,
+    pub required_tier: InvestorTier,
+    pub sold: u64,
+    pub raised: u64,
+    pub refunded: u64,
+    pub bump: u8,
+}
+
+impl PrimarySale {
+    pub fn address(asset_id: u64) -> Pubkey {
+        Pubkey::find_program_address(&[b"primary_sale", &asset_id.to_le_bytes()], &crate::ID).0
+    }
+
+    /// Quote cost of `amount` base units, rounded up in favour of the issuer
+    pub fn cost_of(&self, amount: u64, decimals: u8) -> Option<u64> {
+        let unit = 10u128.pow(decimals as u32);
+        let cost = (amount as u128)
+            .checked_mul(self.price_per_token as u128)?
+            .checked_add(unit - 1)?
+            / unit;
+        u64::try_from(cost).ok()
+    }
+
+    pub fn has_ended(&self, now: i64) -> bool {
+        now >= self.end_time || self.sold >= self.hard_cap
+    }
+
+    /// Whether the sale ended without reaching its soft cap
+    pub fn has_failed(&self, now: i64) -> bool {
+        self.has_ended(now) && self.sold < self.soft_cap
+    }
+
+    fn require_eligible(
+        &self,
+        passport: &Option<Account<IdentityPassport>>,
+        now: i64,
+    ) -> Result<()> {
+        if self.required_kyc == KYCLevel::None && self.required_tier == InvestorTier::None {
+            return Ok(());
+        }
+        let passport = passport.as_ref().ok_or(SaleError::InvestorNotEligible)?;
+        require!(
+            passport.satisfies_kyc(self.required_kyc, now)
+                && passport.investor_tier.clone() as u8 >= self.required_tier.clone() as u8,
+            SaleError::InvestorNotEligible
+        );
+        Ok(())
+    }
+}
+
+/// Running total bought by one buyer, stored at `[b"sale_purchase", sale, buyer]`.
+/// Tokens stay in the sale escrow until the soft cap is met, so a refund can always burn them.
+#[account]
+#[derive(InitSpace)]
+pub struct SalePurchase {
+    pub sale: Pubkey,
+    pub buyer: Pubkey,
+    pub amount: u64,
+    pub paid: u64,
+    pub refunded: bool,
+    /// Tokens already released from escrow to the buyer
+    pub delivered: u64,
+    pub bump: u8,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct CreatePrimarySale<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump,
+        has_one = owner
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + PrimarySale::INIT_SPACE,
+        seeds = [b"primary_sale", &asset_id.to_le_bytes()],
+        bump
+    )]
+    pub sale: Account<'info, PrimarySale>,
+
+    pub quote_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = owner,
+        token::mint = quote_mint,
+        token::authority = sale,
+        seeds = [b"sale_treasury", sale.key().as_ref()],
+        bump
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+
+    #[account(
+        address = asset.mint @ ErrorCode::MintMismatch
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// Holds purchased tokens until the soft cap is met
+    #[account(
+        init,
+        payer = owner,
+        token::mint = mint,
+        token::authority = sale,
+        seeds = [b"sale_escrow", sale.key().as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BuyPrimarySale<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &sale.asset_id.to_le_bytes()],
+        bump = asset.bump
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        mut,
+        seeds = [b"primary_sale", &sale.asset_id.to_le_bytes()],
+        bump = sale.bump
+    )]
+    pub sale: Account<'info, PrimarySale>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = 8 + SalePurchase::INIT_SPACE,
+        seeds = [b"sale_purchase", sale.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub purchase: Account<'info, SalePurchase>,
+
+    #[account(
+        mut,
+        seeds = [b"sale_treasury", sale.key().as_ref()],
+        bump
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = asset.mint @ ErrorCode::MintMismatch
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"sale_escrow", sale.key().as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = sale.quote_mint,
+        token::authority = buyer
+    )]
+    pub buyer_quote_account: Account<'info, TokenAccount>,
+
+    /// Required when the sale or the asset has a KYC or investor tier requirement
+    #[account(constraint = buyer_passport.owner == buyer.key() @ ErrorCode::Unauthorized)]
+    pub buyer_passport: Option<Account<'info, IdentityPassport>>,
+
+    /// Required when the asset has a valuation policy
+    #[account(
+        seeds = [b"valuation", &sale.asset_id.to_le_bytes()],
+        bump = valuation.bump
+    )]
+    pub valuation: Option<Account<'info, ValuationHistory>>,
+
+    /// Required when the asset has a custodian
+    #[account(
+        seeds = [b"reserve", &sale.asset_id.to_le_bytes()],
+        bump = reserve.bump
+    )]
+    pub reserve: Option<Account<'info, ReserveAttestation>>,
+
+    /// Required when the asset is valued from a price feed
+    #[account(
+        seeds = [b"price_feed", &sale.asset_id.to_le_bytes()],
+        bump = price_feed.bump
+    )]
+    pub price_feed: Option<Account<'info, PriceFeed>>,
+
+    /// CHECK: Validated against `price_feed` by `refresh_market_value`
+    pub price_account: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimSaleTokens<'info> {
+    #[account(
+        seeds = [b"primary_sale", &sale.asset_id.to_le_bytes()],
+        bump = sale.bump
+    )]
+    pub sale: Account<'info, PrimarySale>,
+
+    #[account(
+        mut,
+        seeds = [b"sale_purchase", sale.key().as_ref(), buyer.key().as_ref()],
+        bump = purchase.bump
+    )]
+    pub purchase: Account<'info, SalePurchase>,
+
+    #[account(
+        mut,
+        seeds = [b"sale_escrow", sale.key().as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(address = escrow.mint @ ErrorCode::MintMismatch)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = mint,
+        associated_token::authority = buyer
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RefundPrimarySale<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &sale.asset_id.to_le_bytes()],
+        bump = asset.bump
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        mut,
+        seeds = [b"primary_sale", &sale.asset_id.to_le_bytes()],
+        bump = sale.bump
+    )]
+    pub sale: Account<'info, PrimarySale>,
+
+    #[account(
+        mut,
+        seeds = [b"sale_purchase", sale.key().as_ref(), buyer.key().as_ref()],
+        bump = purchase.bump
+    )]
+    pub purchase: Account<'info, SalePurchase>,
+
+    #[account(
+        mut,
+        seeds = [b"sale_treasury", sale.key().as_ref()],
+        bump
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = asset.mint @ ErrorCode::MintMismatch
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"sale_escrow", sale.key().as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = sale.quote_mint,
+        token::authority = buyer
+    )]
+    pub buyer_quote_account: Account<'info, TokenAccount>,
+
+    pub buyer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawSaleProceeds<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &sale.asset_id.to_le_bytes()],
+        bump = asset.bump,
+        has_one = owner
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        seeds = [b"primary_sale", &sale.asset_id.to_le_bytes()],
+        bump = sale.bump
+    )]
+    pub sale: Account<'info, PrimarySale>,
+
+    #[account(
+        mut,
+        seeds = [b"sale_treasury", sale.key().as_ref()],
+        bump
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = sale.quote_mint,
+        token::authority = owner
+    )]
+    pub owner_quote_account: Account<'info, TokenAccount>,
+
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+// Instructions
+pub fn create_primary_sale(
+    ctx: Context<CreatePrimarySale>,
+    asset_id: u64,
+    price_per_token: u64,
+    start_time: i64,
+    end_time: i64,
+    min_ticket: u64,
+    max_ticket: u64,
+    soft_cap: u64,
+    hard_cap: u64,
+    required_kyc: KYCLevel,
+    required_tier: InvestorTier,
+) -> Result<()> {
+    let asset = &mut ctx.accounts.asset;
+    asset.require_operational()?;
+    require!(price_per_token > 0, SaleError::InvalidPrice);
+    require!(
+        start_time < end_time && end_time > Clock::get()?.unix_timestamp,
+        SaleError::InvalidSaleWindow
+    );
+    require!(
+        min_ticket > 0 && min_ticket <= max_ticket && max_ticket <= hard_cap,
+        SaleError::InvalidTicketSize
+    );
+    require!(soft_cap <= hard_cap, SaleError::InvalidCaps);
+    // The sale can tighten the asset's KYC requirement but never loosen it
+    require!(
+        required_kyc as u8 >= asset.kyc_level as u8,
+        SaleError::KycBelowAssetLevel
+    );
+    require!(
+        asset
+            .circulating_supply
+            .checked_add(hard_cap)
+            .is_some_and(|supply| supply <= asset.total_supply),
+        ErrorCode::ExceedsMaxSupply
+    );
+    asset.sale_open = true;
+
+    let sale = &mut ctx.accounts.sale;
+    sale.asset_id = asset_id;
+    sale.quote_mint = ctx.accounts.quote_mint.key();
+    sale.price_per_token = price_per_token;
+    sale.start_time = start_time;
+    sale.end_time = end_time;
+    sale.min_ticket = min_ticket;
+    sale.max_ticket = max_ticket;
+    sale.soft_cap = soft_cap;
+    sale.hard_cap = hard_cap;
+    sale.required_kyc = required_kyc;
+    sale.required_tier = required_tier.clone();
+    sale.sold = 0;
+    sale.raised = 0;
+    sale.refunded = 0;
+    sale.bump = ctx.bumps.sale;
+
+    emit!(PrimarySaleCreated {
+        asset_id,
+        quote_mint: sale.quote_mint,
+        price_per_token,
+        start_time,
+        end_time,
+        soft_cap,
+        hard_cap,
+        required_kyc,
+        required_tier,
+    });
+
+    Ok(())
+}
+
+pub fn buy_primary_sale(ctx: Context<BuyPrimarySale>, amount: u64) -> Result<()> {
+    ctx.accounts.registry.require_not_paused(PAUSE_MINTING)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let buyer = ctx.accounts.buyer.key();
+    let sale = &mut ctx.accounts.sale;
+    require!(
+        now >= sale.start_time && !sale.has_ended(now),
+        SaleError::SaleNotActive
+    );
+    sale.require_eligible(&ctx.accounts.buyer_passport, now)?;
+    require!(amount >= sale.min_ticket, SaleError::TicketTooSmall);
+
+    let purchase = &mut ctx.accounts.purchase;
+    let purchased = purchase.amount.checked_add(amount).unwrap();
+    require!(purchased <= sale.max_ticket, SaleError::TicketTooLarge);
+    let sold = sale.sold.checked_add(amount).unwrap();
+    require!(sold <= sale.hard_cap, SaleError::HardCapExceeded);
+
+    let cost = sale
+        .cost_of(amount, ctx.accounts.mint.decimals)
+        .ok_or(SaleError::InvalidPrice)?;
+
+    let asset = &mut ctx.accounts.asset;
+    asset.require_operational()?;
+    asset.require_kyc(&ctx.accounts.buyer_passport)?;
+    require_current_valuation(asset, &ctx.accounts.valuation)?;
+    // Sales stop while the market price is stale or too uncertain
+    refresh_market_value(asset, &ctx.accounts.price_feed, &ctx.accounts.price_account)?;
+    asset.record_mint(amount)?;
+    require_reserves(asset, &ctx.accounts.reserve)?;
+
+    // Payment and issuance happen in the same instruction, so neither can happen alone.
+    // Tokens are minted into the sale escrow and only released once the soft cap is met.
+    let cpi_accounts = token::Transfer {
+        from: ctx.accounts.buyer_quote_account.to_account_info(),
+        to: ctx.accounts.treasury.to_account_info(),
+        authority: ctx.accounts.buyer.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, cost)?;
+
+    let asset_seeds = &[b"asset".as_ref(), &asset.asset_id.to_le_bytes(), &[asset.bump]];
+    let signer_seeds = &[&asset_seeds[..]];
+    let cpi_accounts = token::MintTo {
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.escrow.to_account_info(),
+        authority: ctx.accounts.asset.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token::mint_to(cpi_ctx, amount)?;
+
+    sale.sold = sold;
+    sale.raised = sale.raised.checked_add(cost).unwrap();
+
+    purchase.sale = sale.key();
+    purchase.buyer = buyer;
+    purchase.amount = purchased;
+    purchase.paid = purchase.paid.checked_add(cost).unwrap();
+    purchase.bump = ctx.bumps.purchase;
+
+    emit!(PrimarySalePurchase {
+        asset_id: sale.asset_id,
+        buyer,
+        amount,
+        cost,
+        total_sold: sold,
+        timestamp: now,
+    });
+
+    emit!(RWATokensMinted {
+        asset_id: sale.asset_id,
+        recipient: buyer,
+        amount,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub fn refund_primary_sale(ctx: Context<RefundPrimarySale>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let sale = &ctx.accounts.sale;
+    require!(sale.has_failed(now), SaleError::RefundUnavailable);
+
+    let purchase = &ctx.accounts.purchase;
+    require!(!purchase.refunded, SaleError::AlreadyRefunded);
+    let (amount, paid) = (purchase.amount, purchase.paid);
+
+    let asset_id_bytes = sale.asset_id.to_le_bytes();
+    let seeds = &[b"primary_sale".as_ref(), asset_id_bytes.as_ref(), &[sale.bump]];
+    let signer = &[&seeds[..]];
+
+    // Burn the escrowed tokens before releasing the payment
+    let cpi_accounts = token::Burn {
+        mint: ctx.accounts.mint.to_account_info(),
+        from: ctx.accounts.escrow.to_account_info(),
+        authority: ctx.accounts.sale.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer,
+    );
+    token::burn(cpi_ctx, amount)?;
+
+    let cpi_accounts = token::Transfer {
+        from: ctx.accounts.treasury.to_account_info(),
+        to: ctx.accounts.buyer_quote_account.to_account_info(),
+        authority: ctx.accounts.sale.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer,
+    );
+    token::transfer(cpi_ctx, paid)?;
+
+    let asset = &mut ctx.accounts.asset;
+    asset.circulating_supply = asset.circulating_supply.checked_sub(amount).unwrap();
+
+    let sale = &mut ctx.accounts.sale;
+    sale.refunded = sale.refunded.checked_add(paid).unwrap();
+    ctx.accounts.purchase.refunded = true;
+
+    emit!(PrimarySaleRefunded {
+        asset_id: sale.asset_id,
+        buyer: ctx.accounts.buyer.key(),
+        amount,
+        refunded: paid,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub fn claim_sale_tokens(ctx: Context<ClaimSaleTokens>) -> Result<()> {
+    let sale = &ctx.accounts.sale;
+    // Sold never decreases, so once the soft cap is met the sale can no longer fail
+    require!(sale.sold >= sale.soft_cap, SaleError::SoftCapNotMet);
+
+    let purchase = &ctx.accounts.purchase;
+    let amount = purchase.amount - purchase.delivered;
+    require!(amount > 0, SaleError::NothingToDeliver);
+
+    let asset_id_bytes = sale.asset_id.to_le_bytes();
+    let seeds = &[b"primary_sale".as_ref(), asset_id_bytes.as_ref(), &[sale.bump]];
+    let signer = &[&seeds[..]];
+    let cpi_accounts = token::Transfer {
+        from: ctx.accounts.escrow.to_account_info(),
+        to: ctx.accounts.buyer_token_account.to_account_info(),
+        authority: ctx.accounts.sale.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer,
+    );
+    token::transfer(cpi_ctx, amount)?;
+
+    ctx.accounts.purchase.delivered = ctx.accounts.purchase.amount;
+
+    emit!(SaleTokensDelivered {
+        asset_id: ctx.accounts.sale.asset_id,
+        buyer: ctx.accounts.buyer.key(),
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn withdraw_sale_proceeds(ctx: Context<WithdrawSaleProceeds>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let sale = &ctx.accounts.sale;
+    require!(sale.has_ended(now), SaleError::SaleNotEnded);
+    require!(sale.sold >= sale.soft_cap, SaleError::SoftCapNotMet);
+
+    let amount = ctx.accounts.treasury.amount;
+    let asset_id_bytes = sale.asset_id.to_le_bytes();
+    let seeds = &[b"primary_sale".as_ref(), asset_id_bytes.as_ref(), &[sale.bump]];
+    let signer = &[&seeds[..]];
+    let cpi_accounts = token::Transfer {
+        from: ctx.accounts.treasury.to_account_info(),
+        to: ctx.accounts.owner_quote_account.to_account_info(),
+        authority: ctx.accounts.sale.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer,
+    );
+    token::transfer(cpi_ctx, amount)?;
+    ctx.accounts.asset.sale_open = false;
+
+    emit!(SaleProceedsWithdrawn {
+        asset_id: sale.asset_id,
+        amount,
+        withdrawn_to: ctx.accounts.owner_quote_account.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// Events
+#[event]
+pub struct PrimarySaleCreated {
+    pub asset_id: u64,
+    pub quote_mint: Pubkey,
+    pub price_per_token: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub soft_cap: u64,
+    pub hard_cap: u64,
+    pub required_kyc: KYCLevel,
+    pub required_tier: InvestorTier,
+}
+
+#[event]
+pub struct PrimarySalePurchase {
+    pub asset_id: u64,
+    pub buyer: Pubkey,
+    pub amount: u64,
+    pub cost: u64,
+    pub total_sold: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SaleTokensDelivered {
+    pub asset_id: u64,
+    pub buyer: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PrimarySaleRefunded {
+    pub asset_id: u64,
+    pub buyer: Pubkey,
+    pub amount: u64,
+    pub refunded: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SaleProceedsWithdrawn {
+    pub asset_id: u64,
+    pub amount: u64,
+    pub withdrawn_to: Pubkey,
+    pub timestamp: i64,
+}
+
+// Errors
+#[error_code(offset = 7200)]
+pub enum SaleError {
+    #[msg("Price per token must be positive")]
+    InvalidPrice,
+    #[msg("Sale window must end after it starts and in the future")]
+    InvalidSaleWindow,
+    #[msg("Ticket sizes must satisfy 0 < min <= max <= hard cap")]
+    InvalidTicketSize,
+    #[msg("Soft cap cannot exceed hard cap")]
+    InvalidCaps,
+    #[msg("Sale is not open")]
+    SaleNotActive,
+    #[msg("Buyer passport does not meet the sale requirements")]
+    InvestorNotEligible,
+    #[msg("Purchase is below the minimum ticket")]
+    TicketTooSmall,
+    #[msg("Purchase exceeds the maximum ticket")]
+    TicketTooLarge,
+    #[msg("Purchase exceeds the hard cap")]
+    HardCapExceeded,
+    #[msg("Sale has not ended")]
+    SaleNotEnded,
+    #[msg("Sale did not reach its soft cap")]
+    SoftCapNotMet,
+    #[msg("Refunds are only available after a sale misses its soft cap")]
+    RefundUnavailable,
+    #[msg("Purchase was already refunded")]
+    AlreadyRefunded,
+    #[msg("Sale KYC level cannot be below the asset's")]
+    KycBelowAssetLevel,
+    #[msg("No purchased tokens are left to deliver")]
+    NothingToDeliver,
+}
@@ -0,0 +1,257 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, TokenAccount};
+
+use crate::merkle;
+use crate::{ErrorCode, RWAAsset};
+
+/// Holder balances of an asset as of one snapshot id, stored at
+/// `[b"snapshot", asset_id, snapshot_id]`. The balance root is computed off-chain
+/// from holder balances at `taken_at` and committed once by the snapshot authority.
+#[account]
+#[derive(InitSpace)]
+pub struct BalanceSnapshot {
+    pub asset_id: u64,
+    pub snapshot_id: u64,
+    /// Tokens in holder accounts when the snapshot was taken; leaves must sum to it
+    pub supply: u64,
+    pub taken_at: i64,
+    /// Merkle root of `(holder, balance)` leaves, see `merkle::balance_leaf`
+    pub balances_root: Option<[u8; 32]>,
+    pub holder_count: u32,
+    pub committed_by: Option<Pubkey>,
+    pub bump: u8,
+}
+
+impl BalanceSnapshot {
+    pub fn address(asset_id: u64, snapshot_id: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"snapshot", &asset_id.to_le_bytes(), &snapshot_id.to_le_bytes()],
+            &crate::ID,
+        )
+        .0
+    }
+
+    /// Whether `proof` shows `holder` held `balance` at this snapshot
+    pub fn verify_balance(&self, holder: &Pubkey, balance: u64, proof: &[[u8; 32]]) -> bool {
+        match &self.balances_root {
+            Some(root) => merkle::verify_proof(proof, root, merkle::balance_leaf(holder, balance)),
+            None => false,
+        }
+    }
+}
+
+/// Balance of a program-owned token account, 0 if it was never created
+fn program_held(account: &UncheckedAccount) -> Result<u64> {
+    if account.data_is_empty() {
+        return Ok(0);
+    }
+    Ok(Account::<TokenAccount>::try_from(account)?.amount)
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct TakeSnapshot<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump,
+        has_one = owner
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + BalanceSnapshot::INIT_SPACE,
+        seeds = [
+            b"snapshot",
+            &asset_id.to_le_bytes(),
+            &asset.snapshot_id.checked_add(1).unwrap().to_le_bytes()
+        ],
+        bump
+    )]
+    pub snapshot: Account<'info, BalanceSnapshot>,
+
+    #[account(address = asset.mint @ ErrorCode::MintMismatch)]
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: Redemption vault PDA, uninitialized before the first redemption request
+    #[account(seeds = [b"redemption_vault", &asset_id.to_le_bytes()], bump)]
+    pub redemption_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Primary sale PDA, only used to derive the sale escrow
+    #[account(seeds = [b"primary_sale", &asset_id.to_le_bytes()], bump)]
+    pub primary_sale: UncheckedAccount<'info>,
+
+    /// CHECK: Sale escrow PDA, uninitialized when no sale was created
+    #[account(seeds = [b"sale_escrow", primary_sale.key().as_ref()], bump)]
+    pub sale_escrow: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CommitSnapshotRoot<'info> {
+    #[account(
+        seeds = [b"asset", &snapshot.asset_id.to_le_bytes()],
+        bump = asset.bump,
+        constraint = asset.snapshot_authority() == snapshot_authority.key()
+            @ SnapshotError::NotSnapshotAuthority
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"snapshot",
+            &snapshot.asset_id.to_le_bytes(),
+            &snapshot.snapshot_id.to_le_bytes()
+        ],
+        bump = snapshot.bump
+    )]
+    pub snapshot: Account<'info, BalanceSnapshot>,
+
+    pub snapshot_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct SetSnapshotAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump,
+        has_one = owner
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    pub owner: Signer<'info>,
+}
+
+// Instructions
+pub fn take_snapshot(ctx: Context<TakeSnapshot>, asset_id: u64) -> Result<()> {
+    let asset = &mut ctx.accounts.asset;
+    asset.snapshot_id = asset.snapshot_id.checked_add(1).unwrap();
+
+    // Circulating supply still counts tokens burned for outbound transfers, and the mint
+    // supply counts voided balances and tokens the program holds in escrow, none of which
+    // belong to a holder on record
+    let redeeming = program_held(&ctx.accounts.redemption_vault)?;
+    let escrowed = program_held(&ctx.accounts.sale_escrow)?;
+    let supply = ctx
+        .accounts
+        .mint
+        .supply
+        .checked_sub(asset.voided_supply)
+        .and_then(|supply| supply.checked_sub(redeeming))
+        .and_then(|supply| supply.checked_sub(escrowed))
+        .ok_or(SnapshotError::InvalidSupply)?;
+
+    let snapshot = &mut ctx.accounts.snapshot;
+    snapshot.asset_id = asset_id;
+    snapshot.snapshot_id = asset.snapshot_id;
+    snapshot.supply = supply;
+    snapshot.taken_at = Clock::get()?.unix_timestamp;
+    snapshot.balances_root = None;
+    snapshot.holder_count = 0;
+    snapshot.committed_by = None;
+    snapshot.bump = ctx.bumps.snapshot;
+
+    emit!(SnapshotTaken {
+        asset_id,
+        snapshot_id: snapshot.snapshot_id,
+        supply: snapshot.supply,
+        timestamp: snapshot.taken_at,
+    });
+
+    Ok(())
+}
+
+pub fn commit_snapshot_root(
+    ctx: Context<CommitSnapshotRoot>,
+    balances_root: [u8; 32],
+    holder_count: u32,
+    leaf_total: u64,
+) -> Result<()> {
+    let snapshot = &mut ctx.accounts.snapshot;
+    require!(snapshot.balances_root.is_none(), SnapshotError::RootAlreadyCommitted);
+    // Claims against the root are also capped at the supply, see `claim_distribution`
+    require!(leaf_total == snapshot.supply, SnapshotError::LeafTotalMismatch);
+
+    let committed_by = ctx.accounts.snapshot_authority.key();
+    snapshot.balances_root = Some(balances_root);
+    snapshot.holder_count = holder_count;
+    snapshot.committed_by = Some(committed_by);
+
+    emit!(SnapshotRootCommitted {
+        asset_id: snapshot.asset_id,
+        snapshot_id: snapshot.snapshot_id,
+        balances_root,
+        holder_count,
+        committed_by,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn set_snapshot_authority(
+    ctx: Context<SetSnapshotAuthority>,
+    asset_id: u64,
+    snapshot_authority: Option<Pubkey>,
+) -> Result<()> {
+    let asset = &mut ctx.accounts.asset;
+    asset.snapshot_authority = snapshot_authority;
+
+    emit!(SnapshotAuthorityUpdated {
+        asset_id,
+        snapshot_authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Events
+#[event]
+pub struct SnapshotTaken {
+    pub asset_id: u64,
+    pub snapshot_id: u64,
+    pub supply: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SnapshotRootCommitted {
+    pub asset_id: u64,
+    pub snapshot_id: u64,
+    pub balances_root: [u8; 32],
+    pub holder_count: u32,
+    pub committed_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SnapshotAuthorityUpdated {
+    pub asset_id: u64,
+    pub snapshot_authority: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+// Errors
+#[error_code(offset = 7000)]
+pub enum SnapshotError {
+    #[msg("Signer is not the asset's snapshot authority")]
+    NotSnapshotAuthority,
+    #[msg("Snapshot balance root was already committed")]
+    RootAlreadyCommitted,
+    #[msg("Snapshot balance root has not been committed")]
+    RootNotCommitted,
+    #[msg("Held supply could not be computed from the mint")]
+    InvalidSupply,
+    #[msg("Snapshot balance leaves do not sum to the snapshot supply")]
+    LeafTotalMismatch,
+}
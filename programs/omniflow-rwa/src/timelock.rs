@@ -0,0 +1,410 @@
+use anchor_lang::prelude::*;
+use sha2::{Digest, Sha256};
+
+use crate::roles::{require_role, Role, RoleMembership};
+use crate::{AssetMetadataUpdated, EndpointUpdate, KYCLevel, MetadataRevision, RWAAsset, Registry};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum TimelockActionClass {
+    /// Wormhole bridge and LayerZero endpoint changes
+    BridgeConfig,
+    /// Per-asset investor protections
+    AssetPolicy,
+    /// Changes to the timelock itself
+    Governance,
+}
+
+/// Registry configuration change that only takes effect after its class delay
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, InitSpace)]
+pub enum TimelockAction {
+    UpdateBridgeConfig {
+        wormhole_bridge: EndpointUpdate,
+        layerzero_endpoint: EndpointUpdate,
+    },
+    SetAssetKycLevel {
+        asset_id: u64,
+        kyc_level: KYCLevel,
+    },
+    SetRecipientPolicy {
+        asset_id: u64,
+        require_verified_recipient: bool,
+    },
+    SetDelay {
+        class: TimelockActionClass,
+        delay: i64,
+    },
+}
+
+impl TimelockAction {
+    pub fn class(&self) -> TimelockActionClass {
+        match self {
+            TimelockAction::UpdateBridgeConfig { .. } => TimelockActionClass::BridgeConfig,
+            TimelockAction::SetAssetKycLevel { .. } | TimelockAction::SetRecipientPolicy { .. } => {
+                TimelockActionClass::AssetPolicy
+            }
+            TimelockAction::SetDelay { .. } => TimelockActionClass::Governance,
+        }
+    }
+
+    /// Asset the action applies to, if any
+    pub fn asset_id(&self) -> Option<u64> {
+        match self {
+            TimelockAction::SetAssetKycLevel { asset_id, .. }
+            | TimelockAction::SetRecipientPolicy { asset_id, .. } => Some(*asset_id),
+            _ => None,
+        }
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct TimelockConfig {
+    /// Minimum delay in seconds, one per `TimelockActionClass`
+    pub delays: [i64; 3],
+    pub action_count: u64,
+    pub bump: u8,
+}
+
+impl TimelockConfig {
+    pub fn delay(&self, class: TimelockActionClass) -> i64 {
+        self.delays[class as usize]
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct QueuedAction {
+    pub action_id: u64,
+    pub action: TimelockAction,
+    pub queued_by: Pubkey,
+    pub queued_at: i64,
+    /// Earliest timestamp at which the action can be executed
+    pub eta: i64,
+    pub bump: u8,
+}
+
+#[derive(Accounts)]
+pub struct InitializeTimelock<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + TimelockConfig::INIT_SPACE,
+        seeds = [b"timelock"],
+        bump
+    )]
+    pub timelock_config: Account<'info, TimelockConfig>,
+
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump,
+        has_one = authority
+    )]
+    pub registry: Account<'info, Registry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct QueueTimelockAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"timelock"],
+        bump = timelock_config.bump
+    )]
+    pub timelock_config: Account<'info, TimelockConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + QueuedAction::INIT_SPACE,
+        seeds = [b"timelock_action", &timelock_config.action_count.to_le_bytes()],
+        bump
+    )]
+    pub queued_action: Account<'info, QueuedAction>,
+
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        seeds = [b"role", &[Role::Admin as u8], authority.key().as_ref()],
+        bump = admin_role.bump
+    )]
+    pub admin_role: Option<Account<'info, RoleMembership>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelTimelockAction<'info> {
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"timelock_action", &queued_action.action_id.to_le_bytes()],
+        bump = queued_action.bump
+    )]
+    pub queued_action: Account<'info, QueuedAction>,
+
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        seeds = [b"role", &[Role::Admin as u8], authority.key().as_ref()],
+        bump = admin_role.bump
+    )]
+    pub admin_role: Option<Account<'info, RoleMembership>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteTimelockAction<'info> {
+    #[account(
+        mut,
+        close = rent_receiver,
+        seeds = [b"timelock_action", &queued_action.action_id.to_le_bytes()],
+        bump = queued_action.bump
+    )]
+    pub queued_action: Account<'info, QueuedAction>,
+
+    #[account(
+        mut,
+        seeds = [b"timelock"],
+        bump = timelock_config.bump
+    )]
+    pub timelock_config: Account<'info, TimelockConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    /// Target of asset policy actions
+    #[account(
+        mut,
+        seeds = [b"asset", &asset.asset_id.to_le_bytes()],
+        bump = asset.bump
+    )]
+    pub asset: Option<Account<'info, RWAAsset>>,
+
+    /// Revision recording a `SetAssetKycLevel` change, at the asset's next metadata version
+    #[account(
+        init,
+        payer = executor,
+        space = 8 + MetadataRevision::INIT_SPACE,
+        seeds = [
+            b"metadata_revision",
+            &asset.as_ref().map_or(0, |asset| asset.asset_id).to_le_bytes(),
+            &asset.as_ref().map_or(0, |asset| asset.metadata_version + 1).to_le_bytes()
+        ],
+        bump
+    )]
+    pub metadata_revision: Option<Account<'info, MetadataRevision>>,
+
+    /// CHECK: Receives the queued action's rent, must be the account that queued it
+    #[account(mut, address = queued_action.queued_by)]
+    pub rent_receiver: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub executor: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Instructions
+pub fn initialize_timelock(
+    ctx: Context<InitializeTimelock>,
+    delays: [i64; 3],
+) -> Result<()> {
+    require!(delays.iter().all(|delay| *delay >= 0), TimelockError::InvalidDelay);
+
+    let timelock_config = &mut ctx.accounts.timelock_config;
+    timelock_config.delays = delays;
+    timelock_config.action_count = 0;
+    timelock_config.bump = ctx.bumps.timelock_config;
+
+    emit!(TimelockInitialized { delays });
+
+    Ok(())
+}
+
+pub fn queue_timelock_action(
+    ctx: Context<QueueTimelockAction>,
+    action: TimelockAction,
+) -> Result<()> {
+    let queued_by = ctx.accounts.authority.key();
+    require_role(&ctx.accounts.registry, &queued_by, &ctx.accounts.admin_role, Role::Admin)?;
+    if let TimelockAction::SetDelay { delay, .. } = action {
+        require!(delay >= 0, TimelockError::InvalidDelay);
+    }
+
+    let timelock_config = &mut ctx.accounts.timelock_config;
+    let now = Clock::get()?.unix_timestamp;
+    let eta = now.checked_add(timelock_config.delay(action.class())).unwrap();
+
+    let queued_action = &mut ctx.accounts.queued_action;
+    queued_action.action_id = timelock_config.action_count;
+    queued_action.action = action.clone();
+    queued_action.queued_by = queued_by;
+    queued_action.queued_at = now;
+    queued_action.eta = eta;
+    queued_action.bump = ctx.bumps.queued_action;
+
+    timelock_config.action_count = timelock_config.action_count.checked_add(1).unwrap();
+
+    emit!(TimelockActionQueued {
+        action_id: queued_action.action_id,
+        action,
+        queued_by,
+        eta,
+    });
+
+    Ok(())
+}
+
+pub fn cancel_timelock_action(ctx: Context<CancelTimelockAction>) -> Result<()> {
+    let cancelled_by = ctx.accounts.authority.key();
+    require_role(&ctx.accounts.registry, &cancelled_by, &ctx.accounts.admin_role, Role::Admin)?;
+
+    emit!(TimelockActionCancelled {
+        action_id: ctx.accounts.queued_action.action_id,
+        cancelled_by,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn execute_timelock_action(ctx: Context<ExecuteTimelockAction>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let queued_action = &ctx.accounts.queued_action;
+    require!(now >= queued_action.eta, TimelockError::DelayNotElapsed);
+
+    let action = queued_action.action.clone();
+    if let Some(asset_id) = action.asset_id() {
+        let asset = ctx.accounts.asset.as_ref().ok_or(TimelockError::AssetMismatch)?;
+        require!(asset.asset_id == asset_id, TimelockError::AssetMismatch);
+    }
+
+    match action.clone() {
+        TimelockAction::UpdateBridgeConfig {
+            wormhole_bridge,
+            layerzero_endpoint,
+        } => {
+            ctx.accounts
+                .registry
+                .update_bridge_config(wormhole_bridge, layerzero_endpoint)?;
+        }
+        TimelockAction::SetAssetKycLevel { asset_id, kyc_level } => {
+            let asset = ctx.accounts.asset.as_mut().unwrap();
+            let revision = ctx
+                .accounts
+                .metadata_revision
+                .as_mut()
+                .ok_or(TimelockError::RevisionMissing)?;
+
+            // Recorded like an owner metadata update so the revision chain has no gaps
+            let previous_kyc_level = asset.kyc_level;
+            let metadata_version = asset.metadata_version.checked_add(1).unwrap();
+            let uri_hash: [u8; 32] = Sha256::digest(asset.metadata_uri.as_bytes()).into();
+            let content_hash = asset.metadata_content_hash.unwrap_or_default();
+            asset.kyc_level = kyc_level;
+            asset.metadata_version = metadata_version;
+
+            let reason = format!("timelock action {}", queued_action.action_id);
+            revision.asset_id = asset_id;
+            revision.version = metadata_version;
+            revision.uri_hash = uri_hash;
+            revision.content_hash = content_hash;
+            revision.previous_uri_hash = uri_hash;
+            revision.previous_content_hash = asset.metadata_content_hash;
+            revision.kyc_level = kyc_level;
+            revision.previous_kyc_level = previous_kyc_level;
+            revision.reason = reason.clone();
+            revision.updated_by = queued_action.queued_by;
+            revision.updated_at = now;
+            revision.bump = ctx.bumps.metadata_revision.unwrap();
+
+            emit!(AssetMetadataUpdated {
+                asset_id,
+                metadata_version,
+                new_metadata_uri: asset.metadata_uri.clone(),
+                content_hash,
+                previous_uri_hash: uri_hash,
+                new_kyc_level: Some(kyc_level),
+                reason,
+                timestamp: now,
+            });
+        }
+        TimelockAction::SetRecipientPolicy { require_verified_recipient, .. } => {
+            ctx.accounts.asset.as_mut().unwrap().require_verified_recipient =
+                require_verified_recipient;
+        }
+        TimelockAction::SetDelay { class, delay } => {
+            ctx.accounts.timelock_config.delays[class as usize] = delay;
+        }
+    }
+
+    emit!(TimelockActionExecuted {
+        action_id: queued_action.action_id,
+        action,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// Events
+#[event]
+pub struct TimelockInitialized {
+    pub delays: [i64; 3],
+}
+
+#[event]
+pub struct TimelockActionQueued {
+    pub action_id: u64,
+    pub action: TimelockAction,
+    pub queued_by: Pubkey,
+    pub eta: i64,
+}
+
+#[event]
+pub struct TimelockActionCancelled {
+    pub action_id: u64,
+    pub cancelled_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TimelockActionExecuted {
+    pub action_id: u64,
+    pub action: TimelockAction,
+    pub timestamp: i64,
+}
+
+// Errors
+#[error_code(offset = 6400)]
+pub enum TimelockError {
+    #[msg("Timelock delay must not be negative")]
+    InvalidDelay,
+    #[msg("Timelock delay has not elapsed")]
+    DelayNotElapsed,
+    #[msg("Asset account does not match the queued action")]
+    AssetMismatch,
+    #[msg("KYC level changes need the asset's next metadata revision account")]
+    RevisionMissing,
+}
@@ -0,0 +1,461 @@
+//! Transfer-agent controls over holder token accounts. The asset PDA is both mint and
+//! freeze authority of the mint recorded in `RWAAsset::mint`, so it can freeze
+//! sanctioned holders and reissue seized balances.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+use crate::reserve::{require_reserves, ReserveAttestation};
+use crate::roles::{require_role, Role, RoleMembership};
+use crate::sale::PrimarySale;
+use crate::{ErrorCode, RWAAsset, Registry, PAUSE_FORCED_TRANSFERS};
+
+/// Record of a court-ordered transfer out of a token account, stored at
+/// `[b"forced_transfer", source_token_account]`. Its existence keeps the source frozen.
+#[account]
+#[derive(InitSpace)]
+pub struct ForcedTransferRecord {
+    pub asset_id: u64,
+    pub source_token_account: Pubkey,
+    pub from_owner: Pubkey,
+    pub destination_token_account: Pubkey,
+    pub amount: u64,
+    /// Hash of the court order or other legal instruction
+    pub legal_reference_hash: [u8; 32],
+    pub executed_by: Pubkey,
+    pub executed_at: i64,
+    pub bump: u8,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64, decimals: u8)]
+pub struct CreateAssetMint<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump,
+        has_one = owner
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        init,
+        payer = owner,
+        mint::decimals = decimals,
+        mint::authority = asset,
+        mint::freeze_authority = asset,
+        seeds = [b"asset_mint", &asset_id.to_le_bytes()],
+        bump
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct FreezeHolder<'info> {
+    #[account(
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        seeds = [b"role", &[Role::TransferAgent as u8], authority.key().as_ref()],
+        bump = transfer_agent_role.bump
+    )]
+    pub transfer_agent_role: Option<Account<'info, RoleMembership>>,
+
+    #[account(
+        address = asset.mint @ ErrorCode::MintMismatch,
+        constraint = mint.freeze_authority == COption::Some(asset.key())
+            @ TransferAgentError::FreezeAuthorityMismatch
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// Holder account; the redemption vault and sale escrow are never frozen
+    #[account(
+        mut,
+        token::mint = mint,
+        constraint = holder_token_account.owner != asset.key()
+            && holder_token_account.owner != PrimarySale::address(asset_id)
+            @ TransferAgentError::ProgramOwnedAccount
+    )]
+    pub holder_token_account: Account<'info, TokenAccount>,
+
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct ThawHolder<'info> {
+    #[account(
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        seeds = [b"role", &[Role::TransferAgent as u8], authority.key().as_ref()],
+        bump = transfer_agent_role.bump
+    )]
+    pub transfer_agent_role: Option<Account<'info, RoleMembership>>,
+
+    #[account(
+        address = asset.mint @ ErrorCode::MintMismatch,
+        constraint = mint.freeze_authority == COption::Some(asset.key())
+            @ TransferAgentError::FreezeAuthorityMismatch
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// Holder account; the redemption vault and sale escrow are never frozen
+    #[account(
+        mut,
+        token::mint = mint,
+        constraint = holder_token_account.owner != asset.key()
+            && holder_token_account.owner != PrimarySale::address(asset_id)
+            @ TransferAgentError::ProgramOwnedAccount
+    )]
+    pub holder_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Must be empty; an account here means the balance was reissued
+    #[account(
+        seeds = [b"forced_transfer", holder_token_account.key().as_ref()],
+        bump,
+        constraint = forced_transfer_record.data_is_empty() @ TransferAgentError::BalanceReissued
+    )]
+    pub forced_transfer_record: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct ForcedTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        seeds = [b"role", &[Role::TransferAgent as u8], authority.key().as_ref()],
+        bump = transfer_agent_role.bump
+    )]
+    pub transfer_agent_role: Option<Account<'info, RoleMembership>>,
+
+    #[account(
+        mut,
+        address = asset.mint @ ErrorCode::MintMismatch,
+        constraint = mint.freeze_authority == COption::Some(asset.key())
+            @ TransferAgentError::FreezeAuthorityMismatch
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// Holder account; balances escrowed by the asset or its sale cannot be seized
+    #[account(
+        mut,
+        token::mint = mint,
+        constraint = source_token_account.owner != asset.key()
+            && source_token_account.owner != PrimarySale::address(asset_id)
+            @ TransferAgentError::ProgramOwnedAccount
+    )]
+    pub source_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        constraint = destination_token_account.key() != source_token_account.key()
+            @ TransferAgentError::InvalidDestination
+    )]
+    pub destination_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ForcedTransferRecord::INIT_SPACE,
+        seeds = [b"forced_transfer", source_token_account.key().as_ref()],
+        bump
+    )]
+    pub forced_transfer_record: Account<'info, ForcedTransferRecord>,
+
+    /// Required when the asset has a custodian
+    #[account(seeds = [b"reserve", &asset_id.to_le_bytes()], bump = reserve.bump)]
+    pub reserve: Option<Account<'info, ReserveAttestation>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+// Instructions
+pub fn create_asset_mint(ctx: Context<CreateAssetMint>, asset_id: u64, decimals: u8) -> Result<()> {
+    ctx.accounts.asset.mint = ctx.accounts.mint.key();
+
+    emit!(AssetMintCreated {
+        asset_id,
+        mint: ctx.accounts.mint.key(),
+        decimals,
+    });
+
+    Ok(())
+}
+
+pub fn freeze_holder(ctx: Context<FreezeHolder>, asset_id: u64) -> Result<()> {
+    let frozen_by = ctx.accounts.authority.key();
+    require_role(
+        &ctx.accounts.registry,
+        &frozen_by,
+        &ctx.accounts.transfer_agent_role,
+        Role::TransferAgent,
+    )?;
+    require!(
+        !ctx.accounts.holder_token_account.is_frozen(),
+        TransferAgentError::AlreadyFrozen
+    );
+
+    let asset = &ctx.accounts.asset;
+    let asset_seeds = &[b"asset".as_ref(), &asset.asset_id.to_le_bytes(), &[asset.bump]];
+    let signer_seeds = &[&asset_seeds[..]];
+
+    let cpi_accounts = token::FreezeAccount {
+        account: ctx.accounts.holder_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        authority: ctx.accounts.asset.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    token::freeze_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds))?;
+
+    emit!(HolderFrozen {
+        asset_id,
+        holder: ctx.accounts.holder_token_account.owner,
+        token_account: ctx.accounts.holder_token_account.key(),
+        frozen_by,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn thaw_holder(ctx: Context<ThawHolder>, asset_id: u64) -> Result<()> {
+    let thawed_by = ctx.accounts.authority.key();
+    require_role(
+        &ctx.accounts.registry,
+        &thawed_by,
+        &ctx.accounts.transfer_agent_role,
+        Role::TransferAgent,
+    )?;
+    require!(
+        ctx.accounts.holder_token_account.is_frozen(),
+        TransferAgentError::NotFrozen
+    );
+
+    let asset = &ctx.accounts.asset;
+    let asset_seeds = &[b"asset".as_ref(), &asset.asset_id.to_le_bytes(), &[asset.bump]];
+    let signer_seeds = &[&asset_seeds[..]];
+
+    let cpi_accounts = token::ThawAccount {
+        account: ctx.accounts.holder_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        authority: ctx.accounts.asset.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    token::thaw_account(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds))?;
+
+    emit!(HolderThawed {
+        asset_id,
+        holder: ctx.accounts.holder_token_account.owner,
+        token_account: ctx.accounts.holder_token_account.key(),
+        thawed_by,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Move the whole balance of `source_token_account` to `destination_token_account`.
+/// The asset PDA cannot burn from holder accounts, so the source is frozen for good
+/// and the same amount is reissued to the destination. The reissue bypasses
+/// `record_mint` because it adds no holder supply: the mint supply less `voided_supply`
+/// must stay within `circulating_supply`, which is checked after minting.
+pub fn forced_transfer(
+    ctx: Context<ForcedTransfer>,
+    asset_id: u64,
+    legal_reference_hash: [u8; 32],
+) -> Result<()> {
+    let executed_by = ctx.accounts.authority.key();
+    require_role(
+        &ctx.accounts.registry,
+        &executed_by,
+        &ctx.accounts.transfer_agent_role,
+        Role::TransferAgent,
+    )?;
+    ctx.accounts.registry.require_not_paused(PAUSE_FORCED_TRANSFERS)?;
+    require!(
+        legal_reference_hash != [0; 32],
+        TransferAgentError::MissingLegalReference
+    );
+    let amount = ctx.accounts.source_token_account.amount;
+    require!(amount > 0, TransferAgentError::NothingToTransfer);
+
+    let asset = &ctx.accounts.asset;
+    let asset_seeds = &[b"asset".as_ref(), &asset.asset_id.to_le_bytes(), &[asset.bump]];
+    let signer_seeds = &[&asset_seeds[..]];
+
+    if !ctx.accounts.source_token_account.is_frozen() {
+        let cpi_accounts = token::FreezeAccount {
+            account: ctx.accounts.source_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.asset.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::freeze_account(CpiContext::new_with_signer(
+            cpi_program,
+            cpi_accounts,
+            signer_seeds,
+        ))?;
+    }
+
+    let cpi_accounts = token::MintTo {
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.destination_token_account.to_account_info(),
+        authority: ctx.accounts.asset.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    token::mint_to(
+        CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds),
+        amount,
+    )?;
+
+    // Holder balances are unchanged in total; the voided tokens stay on the mint
+    let asset = &mut ctx.accounts.asset;
+    asset.voided_supply = asset.voided_supply.checked_add(amount).unwrap();
+
+    ctx.accounts.mint.reload()?;
+    let live_supply = ctx.accounts.mint.supply.checked_sub(asset.voided_supply);
+    require!(
+        live_supply.is_some_and(|supply| supply <= asset.circulating_supply),
+        TransferAgentError::SupplyInvariantViolated
+    );
+    require_reserves(asset, &ctx.accounts.reserve)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let from_owner = ctx.accounts.source_token_account.owner;
+    let record = &mut ctx.accounts.forced_transfer_record;
+    record.asset_id = asset_id;
+    record.source_token_account = ctx.accounts.source_token_account.key();
+    record.from_owner = from_owner;
+    record.destination_token_account = ctx.accounts.destination_token_account.key();
+    record.amount = amount;
+    record.legal_reference_hash = legal_reference_hash;
+    record.executed_by = executed_by;
+    record.executed_at = now;
+    record.bump = ctx.bumps.forced_transfer_record;
+
+    emit!(ForcedTransferExecuted {
+        asset_id,
+        from_owner,
+        source_token_account: record.source_token_account,
+        destination_owner: ctx.accounts.destination_token_account.owner,
+        destination_token_account: record.destination_token_account,
+        amount,
+        legal_reference_hash,
+        executed_by,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// Events
+#[event]
+pub struct AssetMintCreated {
+    pub asset_id: u64,
+    pub mint: Pubkey,
+    pub decimals: u8,
+}
+
+#[event]
+pub struct HolderFrozen {
+    pub asset_id: u64,
+    pub holder: Pubkey,
+    pub token_account: Pubkey,
+    pub frozen_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct HolderThawed {
+    pub asset_id: u64,
+    pub holder: Pubkey,
+    pub token_account: Pubkey,
+    pub thawed_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ForcedTransferExecuted {
+    pub asset_id: u64,
+    pub from_owner: Pubkey,
+    pub source_token_account: Pubkey,
+    pub destination_owner: Pubkey,
+    pub destination_token_account: Pubkey,
+    pub amount: u64,
+    pub legal_reference_hash: [u8; 32],
+    pub executed_by: Pubkey,
+    pub timestamp: i64,
+}
+
+// Errors
+#[error_code(offset = 7700)]
+pub enum TransferAgentError {
+    #[msg("Mint freeze authority is not the asset")]
+    FreezeAuthorityMismatch,
+    #[msg("Token account is already frozen")]
+    AlreadyFrozen,
+    #[msg("Token account is not frozen")]
+    NotFrozen,
+    #[msg("Token account balance was reissued by a forced transfer and stays frozen")]
+    BalanceReissued,
+    #[msg("Forced transfer destination must differ from the source")]
+    InvalidDestination,
+    #[msg("Forced transfer needs a legal reference hash")]
+    MissingLegalReference,
+    #[msg("Source token account holds no tokens")]
+    NothingToTransfer,
+    #[msg("Reissued supply is not backed by voided and circulating supply")]
+    SupplyInvariantViolated,
+    #[msg("Token account is held by the asset or its primary sale")]
+    ProgramOwnedAccount,
+}
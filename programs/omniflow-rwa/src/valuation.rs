@@ -0,0 +1,371 @@
+use anchor_lang::prelude::*;
+
+use crate::roles::{require_role, Role, RoleMembership};
+use crate::{RWAAsset, Registry};
+
+/// Number of valuations kept per asset before the oldest is overwritten
+pub const VALUATION_HISTORY_LEN: usize = 16;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct Valuation {
+    /// Asset value in minor units of `currency`
+    pub value: u64,
+    /// ISO 4217 currency code, e.g. `*b"USD"`
+    pub currency: [u8; 3],
+    pub effective_date: i64,
+    /// Hash of the appraisal methodology or report
+    pub methodology_hash: [u8; 32],
+    pub oracle: Pubkey,
+    pub submitted_at: i64,
+}
+
+/// Valuation policy and ring buffer of recent valuations, stored at `[b"valuation", asset_id]`
+#[account]
+#[derive(InitSpace)]
+pub struct ValuationHistory {
+    pub asset_id: u64,
+    pub currency: [u8; 3],
+    /// Minting stops once the latest valuation is older than this many seconds
+    pub max_staleness: i64,
+    /// Valuations moving `total_value` by more than this need Admin confirmation
+    pub max_deviation_bps: u16,
+    #[max_len(16)]
+    pub entries: Vec<Valuation>,
+    /// Slot the next valuation is written to
+    pub next_slot: u8,
+    /// A valuation exceeded the deviation threshold and nothing is applied until an Admin
+    /// confirms the latest one
+    pub pending_review: bool,
+    pub bump: u8,
+}
+
+impl ValuationHistory {
+    pub fn latest(&self) -> Option<&Valuation> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let len = self.entries.len();
+        self.entries.get((self.next_slot as usize + len - 1) % len)
+    }
+
+    fn push(&mut self, valuation: Valuation) {
+        let slot = self.next_slot as usize;
+        if slot < self.entries.len() {
+            self.entries[slot] = valuation;
+        } else {
+            self.entries.push(valuation);
+        }
+        self.next_slot = ((slot + 1) % VALUATION_HISTORY_LEN) as u8;
+    }
+
+    /// Whether the latest valuation is recent enough and was applied
+    pub fn is_current(&self, now: i64) -> bool {
+        !self.pending_review
+            && self
+                .latest()
+                .is_some_and(|latest| now - latest.effective_date <= self.max_staleness)
+    }
+
+    /// Value per whole token in minor currency units, for `supply` base units of a mint
+    /// with `decimals`
+    pub fn nav_per_token(value: u64, supply: u64, decimals: u8) -> Option<u64> {
+        if supply == 0 {
+            return None;
+        }
+        let nav = (value as u128).checked_mul(10u128.pow(decimals as u32))? / supply as u128;
+        u64::try_from(nav).ok()
+    }
+}
+
+/// Change from `previous` to `value` in basis points
+pub fn deviation_bps(previous: u64, value: u64) -> u64 {
+    if previous == 0 {
+        return 0;
+    }
+    let change = (value as i128 - previous as i128).unsigned_abs();
+    (change * 10_000 / previous as u128).min(u64::MAX as u128) as u64
+}
+
+/// Require a current valuation before issuing tokens of an asset with a valuation policy
+pub fn require_current_valuation(
+    asset: &RWAAsset,
+    valuation: &Option<Account<ValuationHistory>>,
+) -> Result<()> {
+    if asset.valuation_required {
+        let valuation = valuation.as_ref().ok_or(ValuationError::ValuationMissing)?;
+        require!(valuation.asset_id == asset.asset_id, ValuationError::ValuationMissing);
+        require!(!valuation.pending_review, ValuationError::ValuationUnderReview);
+        require!(
+            valuation.is_current(Clock::get()?.unix_timestamp),
+            ValuationError::ValuationStale
+        );
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(asset_id: u64)]
+pub struct ConfigureValuation<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &asset_id.to_le_bytes()],
+        bump = asset.bump,
+        has_one = owner
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + ValuationHistory::INIT_SPACE,
+        seeds = [b"valuation", &asset_id.to_le_bytes()],
+        bump
+    )]
+    pub valuation: Account<'info, ValuationHistory>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitValuation<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &valuation.asset_id.to_le_bytes()],
+        bump = asset.bump
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        mut,
+        seeds = [b"valuation", &valuation.asset_id.to_le_bytes()],
+        bump = valuation.bump
+    )]
+    pub valuation: Account<'info, ValuationHistory>,
+
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        seeds = [b"role", &[Role::ValuationOracle as u8], oracle.key().as_ref()],
+        bump = oracle_role.bump
+    )]
+    pub oracle_role: Option<Account<'info, RoleMembership>>,
+
+    pub oracle: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ConfirmValuation<'info> {
+    #[account(
+        mut,
+        seeds = [b"asset", &valuation.asset_id.to_le_bytes()],
+        bump = asset.bump
+    )]
+    pub asset: Account<'info, RWAAsset>,
+
+    #[account(
+        mut,
+        seeds = [b"valuation", &valuation.asset_id.to_le_bytes()],
+        bump = valuation.bump
+    )]
+    pub valuation: Account<'info, ValuationHistory>,
+
+    #[account(
+        seeds = [b"registry"],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        seeds = [b"role", &[Role::Admin as u8], authority.key().as_ref()],
+        bump = admin_role.bump
+    )]
+    pub admin_role: Option<Account<'info, RoleMembership>>,
+
+    pub authority: Signer<'info>,
+}
+
+// Instructions
+pub fn configure_valuation(
+    ctx: Context<ConfigureValuation>,
+    asset_id: u64,
+    currency: [u8; 3],
+    max_staleness: i64,
+    max_deviation_bps: u16,
+) -> Result<()> {
+    require!(
+        currency.iter().all(u8::is_ascii_uppercase),
+        ValuationError::InvalidCurrency
+    );
+    require!(max_staleness > 0, ValuationError::InvalidValuationPolicy);
+
+    let valuation = &mut ctx.accounts.valuation;
+    // Existing history is only comparable in the same currency
+    require!(
+        valuation.entries.is_empty() || valuation.currency == currency,
+        ValuationError::InvalidCurrency
+    );
+    valuation.asset_id = asset_id;
+    valuation.currency = currency;
+    valuation.max_staleness = max_staleness;
+    valuation.max_deviation_bps = max_deviation_bps;
+    valuation.bump = ctx.bumps.valuation;
+
+    ctx.accounts.asset.valuation_required = true;
+
+    emit!(ValuationPolicyUpdated {
+        asset_id,
+        currency,
+        max_staleness,
+        max_deviation_bps,
+    });
+
+    Ok(())
+}
+
+pub fn submit_valuation(
+    ctx: Context<SubmitValuation>,
+    value: u64,
+    currency: [u8; 3],
+    effective_date: i64,
+    methodology_hash: [u8; 32],
+) -> Result<()> {
+    let oracle = ctx.accounts.oracle.key();
+    require_role(
+        &ctx.accounts.registry,
+        &oracle,
+        &ctx.accounts.oracle_role,
+        Role::ValuationOracle,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let valuation = &mut ctx.accounts.valuation;
+    require!(currency == valuation.currency, ValuationError::InvalidCurrency);
+    require!(effective_date <= now, ValuationError::InvalidEffectiveDate);
+    if let Some(latest) = valuation.latest() {
+        require!(
+            effective_date > latest.effective_date,
+            ValuationError::InvalidEffectiveDate
+        );
+    }
+
+    let asset = &mut ctx.accounts.asset;
+    let previous_value = asset.total_value;
+    let deviation = deviation_bps(previous_value, value);
+    // An open review stays open until confirmed, so a later in-range valuation cannot
+    // slip an unreviewed one past the Admin
+    let applied = !valuation.pending_review && deviation <= valuation.max_deviation_bps as u64;
+
+    valuation.push(Valuation {
+        value,
+        currency,
+        effective_date,
+        methodology_hash,
+        oracle,
+        submitted_at: now,
+    });
+    valuation.pending_review = !applied;
+    if applied {
+        asset.total_value = value;
+    }
+
+    emit!(ValuationSubmitted {
+        asset_id: asset.asset_id,
+        value,
+        currency,
+        effective_date,
+        methodology_hash,
+        oracle,
+        previous_value,
+        deviation_bps: deviation,
+        applied,
+    });
+
+    Ok(())
+}
+
+pub fn confirm_valuation(ctx: Context<ConfirmValuation>) -> Result<()> {
+    let confirmed_by = ctx.accounts.authority.key();
+    require_role(
+        &ctx.accounts.registry,
+        &confirmed_by,
+        &ctx.accounts.admin_role,
+        Role::Admin,
+    )?;
+
+    let valuation = &mut ctx.accounts.valuation;
+    require!(valuation.pending_review, ValuationError::NoPendingReview);
+    let value = valuation.latest().unwrap().value;
+    valuation.pending_review = false;
+
+    let asset = &mut ctx.accounts.asset;
+    let previous_value = asset.total_value;
+    asset.total_value = value;
+
+    emit!(ValuationConfirmed {
+        asset_id: asset.asset_id,
+        previous_value,
+        value,
+        confirmed_by,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Events
+#[event]
+pub struct ValuationPolicyUpdated {
+    pub asset_id: u64,
+    pub currency: [u8; 3],
+    pub max_staleness: i64,
+    pub max_deviation_bps: u16,
+}
+
+#[event]
+pub struct ValuationSubmitted {
+    pub asset_id: u64,
+    pub value: u64,
+    pub currency: [u8; 3],
+    pub effective_date: i64,
+    pub methodology_hash: [u8; 32],
+    pub oracle: Pubkey,
+    pub previous_value: u64,
+    pub deviation_bps: u64,
+    /// False when the valuation awaits Admin confirmation
+    pub applied: bool,
+}
+
+#[event]
+pub struct ValuationConfirmed {
+    pub asset_id: u64,
+    pub previous_value: u64,
+    pub value: u64,
+    pub confirmed_by: Pubkey,
+    pub timestamp: i64,
+}
+
+// Errors
+#[error_code(offset = 7300)]
+pub enum ValuationError {
+    #[msg("Currency must be a three-letter ISO 4217 code matching the policy")]
+    InvalidCurrency,
+    #[msg("Valuation policy staleness must be positive")]
+    InvalidValuationPolicy,
+    #[msg("Effective date must be in the past and after the latest valuation")]
+    InvalidEffectiveDate,
+    #[msg("Asset requires a valuation account")]
+    ValuationMissing,
+    #[msg("Latest valuation is stale")]
+    ValuationStale,
+    #[msg("Latest valuation is awaiting confirmation")]
+    ValuationUnderReview,
+    #[msg("No valuation is awaiting confirmation")]
+    NoPendingReview,
+}
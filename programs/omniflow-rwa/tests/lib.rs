@@ -1,13 +1,302 @@
 use anchor_lang::prelude::*;
+use anchor_lang::InstructionData;
 use anchor_spl::token::{self, Token, TokenAccount, Mint};
 use omniflow_rwa::*;
+use solana_program::program_option::COption;
+use solana_program::program_pack::Pack;
 use solana_program_test::*;
 use solana_sdk::{
     account::Account,
+    instruction::{Instruction, InstructionError},
     signature::{Keypair, Signer},
-    transaction::Transaction,
+    transaction::{Transaction, TransactionError},
 };
 
+// Instruction test harness. Accounts are written straight into the test bank so each
+// test starts from the state it exercises instead of replaying every setup instruction.
+
+fn program_test() -> ProgramTest {
+    ProgramTest::new("omniflow_rwa", omniflow_rwa::ID, processor!(omniflow_rwa::entry))
+}
+
+fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &omniflow_rwa::ID).0
+}
+
+fn add_program_account<T: AccountSerialize>(
+    program_test: &mut ProgramTest,
+    address: Pubkey,
+    state: &T,
+    space: usize,
+) {
+    let mut data = Vec::new();
+    state.try_serialize(&mut data).unwrap();
+    data.resize(space.max(data.len()), 0);
+    program_test.add_account(
+        address,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: omniflow_rwa::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+fn add_funded_signer(program_test: &mut ProgramTest) -> Keypair {
+    let signer = Keypair::new();
+    program_test.add_account(
+        signer.pubkey(),
+        Account::new(10_000_000_000, 0, &solana_program::system_program::ID),
+    );
+    signer
+}
+
+fn add_mint(program_test: &mut ProgramTest, address: Pubkey, authority: Pubkey, supply: u64) {
+    let mut data = vec![0u8; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: COption::Some(authority),
+        supply,
+        decimals: 0,
+        is_initialized: true,
+        freeze_authority: COption::Some(authority),
+    }
+    .pack_into_slice(&mut data);
+    program_test.add_account(
+        address,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: token::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+/// Adds `owner`'s associated token account for `mint` and returns its address
+fn add_token_account(
+    program_test: &mut ProgramTest,
+    mint: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+) -> Pubkey {
+    let address = spl_associated_token_account::get_associated_token_address(&owner, &mint);
+    add_token_account_at(program_test, address, mint, owner, amount);
+    address
+}
+
+fn add_token_account_at(
+    program_test: &mut ProgramTest,
+    address: Pubkey,
+    mint: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+) {
+    let mut data = vec![0u8; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint,
+        owner,
+        amount,
+        delegate: COption::None,
+        state: spl_token::state::AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    }
+    .pack_into_slice(&mut data);
+    program_test.add_account(
+        address,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: token::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+fn registry_state(authority: Pubkey) -> Registry {
+    Registry {
+        authority,
+        pending_authority: None,
+        total_assets: 0,
+        next_asset_id: 1,
+        allocate_asset_ids: false,
+        indexed_assets: 0,
+        wormhole_bridge: None,
+        layerzero_endpoint: None,
+        outbound_sequence: 0,
+        pending_outbound_transfers: 0,
+        pause_flags: 0,
+        guardian: None,
+        bump: Pubkey::find_program_address(&[b"registry"], &omniflow_rwa::ID).1,
+    }
+}
+
+fn asset_state(asset_id: u64, owner: Pubkey) -> RWAAsset {
+    RWAAsset {
+        asset_id,
+        asset_type: AssetType::RealEstate,
+        owner,
+        pending_owner: None,
+        owner_transfer_approved: false,
+        metadata_uri: "https://example.com/metadata/1".to_string(),
+        kyc_level: KYCLevel::None,
+        total_value: 1_000_000,
+        total_supply: 100_000,
+        circulating_supply: 0,
+        is_active: true,
+        paused: false,
+        created_at: 0,
+        chain_id: 1,
+        require_verified_recipient: false,
+        index_position: 0,
+        retirement_count: 0,
+        redemption_count: 0,
+        distribution_count: 0,
+        snapshot_id: 0,
+        snapshot_authority: None,
+        allocation_count: 0,
+        valuation_required: false,
+        price_feed_required: false,
+        reserve_required: false,
+        metadata_version: 0,
+        metadata_content_hash: None,
+        voided_supply: 0,
+        pending_redemptions: 0,
+        open_distributions: 0,
+        open_allocation_rounds: 0,
+        sale_open: false,
+        retired: false,
+        mint: pda(&[b"asset_mint", &asset_id.to_le_bytes()]),
+        paused_by_registry: false,
+        bump: Pubkey::find_program_address(&[b"asset", &asset_id.to_le_bytes()], &omniflow_rwa::ID).1,
+    }
+}
+
+/// Adds an active, unexpired passport held by `owner` and returns its address
+fn add_passport(program_test: &mut ProgramTest, owner: Pubkey, kyc_level: identity::KYCLevel) -> Pubkey {
+    let did = format!("did:{}", &owner.to_string()[..16]);
+    let (address, bump) =
+        Pubkey::find_program_address(&[b"identity_passport", did.as_bytes()], &omniflow_rwa::ID);
+    let passport = IdentityPassport {
+        mint: Pubkey::new_unique(),
+        owner,
+        did,
+        kyc_level,
+        investor_tier: InvestorTier::Accredited,
+        reputation_score: 100,
+        issuance_date: 0,
+        expiration_date: i64::MAX,
+        is_active: true,
+        metadata_uri: String::new(),
+        issuer: Pubkey::new_unique(),
+        cross_chain_addresses: Vec::new(),
+        credentials: Vec::new(),
+        bump,
+    };
+    add_program_account(program_test, address, &passport, IdentityPassport::MAX_SIZE);
+    address
+}
+
+fn add_registry(program_test: &mut ProgramTest, registry: &Registry) -> Pubkey {
+    let address = pda(&[b"registry"]);
+    add_program_account(program_test, address, registry, 8 + Registry::INIT_SPACE);
+    address
+}
+
+fn add_asset(program_test: &mut ProgramTest, asset: &RWAAsset) -> Pubkey {
+    let address = RWAAsset::address(asset.asset_id);
+    add_program_account(program_test, address, asset, 8 + RWAAsset::INIT_SPACE);
+    address
+}
+
+/// Adds the mint `create_asset_mint` would have created for `asset_id` and returns its address
+fn add_asset_mint(program_test: &mut ProgramTest, asset_id: u64, supply: u64) -> Pubkey {
+    let address = pda(&[b"asset_mint", &asset_id.to_le_bytes()]);
+    add_mint(program_test, address, RWAAsset::address(asset_id), supply);
+    address
+}
+
+/// Adds asset index page 0 listing `assets` as active
+fn add_asset_index_page(program_test: &mut ProgramTest, assets: &[&RWAAsset]) -> Pubkey {
+    let (address, bump) =
+        Pubkey::find_program_address(&[b"asset_index", &0u64.to_le_bytes()], &omniflow_rwa::ID);
+    let page = AssetIndexPage {
+        page: 0,
+        entries: assets
+            .iter()
+            .map(|asset| AssetIndexEntry {
+                asset_id: asset.asset_id,
+                asset_type: asset.asset_type,
+                owner: asset.owner,
+                status: AssetStatus::Active,
+            })
+            .collect(),
+        bump,
+    };
+    add_program_account(program_test, address, &page, 8 + AssetIndexPage::INIT_SPACE);
+    address
+}
+
+fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: omniflow_rwa::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> std::result::Result<(), BanksClientError> {
+    // A fresh blockhash keeps repeated identical instructions from being deduplicated
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+fn assert_program_error(
+    result: std::result::Result<(), BanksClientError>,
+    expected: impl Into<u32>,
+) {
+    let expected = expected.into();
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+            assert_eq!(code, expected)
+        }
+        other => panic!("expected custom error {expected}, got {other:?}"),
+    }
+}
+
+async fn fetch<T: AccountDeserialize>(context: &mut ProgramTestContext, address: Pubkey) -> T {
+    let account = context.banks_client.get_account(address).await.unwrap().unwrap();
+    T::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+async fn token_balance(context: &mut ProgramTestContext, address: Pubkey) -> u64 {
+    let account = context.banks_client.get_account(address).await.unwrap().unwrap();
+    spl_token::state::Account::unpack(&account.data).unwrap().amount
+}
+
+async fn warp_to_timestamp(context: &mut ProgramTestContext, unix_timestamp: i64) {
+    let mut clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp = unix_timestamp;
+    context.set_sysvar(&clock);
+}
+
 #[tokio::test]
 async fn test_initialize_registry() {
     let program_id = Pubkey::new_unique();
@@ -24,8 +313,13 @@ async fn test_initialize_registry() {
         &program_id,
     );
     
+    let program_data = solana_program::bpf_loader_upgradeable::get_program_data_address(&program_id);
+    
     let accounts = omniflow_rwa::accounts::InitializeRegistry {
         registry: registry_pda,
+        program: program_id,
+        program_data,
+        upgrade_authority: payer.pubkey(),
         payer: payer.pubkey(),
         system_program: solana_program::system_program::id(),
     };
@@ -54,10 +348,14 @@ async fn test_initialize_registry() {
     let registry_data: Registry = Registry::try_deserialize(&mut &registry_account.data[8..]).unwrap();
     
     assert_eq!(registry_data.authority, authority.pubkey());
+    assert_eq!(registry_data.pending_authority, None);
     assert_eq!(registry_data.total_assets, 0);
+    assert_eq!(registry_data.next_asset_id, 1);
+    assert!(!registry_data.allocate_asset_ids);
     assert_eq!(registry_data.wormhole_bridge, wormhole_bridge);
     assert_eq!(registry_data.layerzero_endpoint, layerzero_endpoint);
-    assert!(!registry_data.paused);
+    assert_eq!(registry_data.pause_flags, 0);
+    assert_eq!(registry_data.guardian, None);
 }
 
 #[tokio::test]
@@ -78,9 +376,17 @@ async fn test_register_rwa_asset() {
         &program_id,
     );
     
+    let (asset_index_pda, _) = Pubkey::find_program_address(
+        &[b"asset_index", &0u64.to_le_bytes()],
+        &program_id,
+    );
+    
     let accounts = omniflow_rwa::accounts::RegisterRWAAsset {
         asset: asset_pda,
         registry: registry_pda,
+        issuer_role: None,
+        asset_index_page: asset_index_pda,
+        asset_attributes: None,
         owner: payer.pubkey(),
         system_program: solana_program::system_program::id(),
     };
@@ -96,6 +402,7 @@ async fn test_register_rwa_asset() {
             total_value: 1_000_000,
             total_supply: 100_000,
             chain_id: 1,
+            attributes: None,
         }.data(),
     };
     
@@ -121,6 +428,21 @@ async fn test_register_rwa_asset() {
     assert_eq!(asset_data.circulating_supply, 0);
     assert!(asset_data.is_active);
     assert_eq!(asset_data.chain_id, 1);
+    assert_eq!(asset_data.retirement_count, 0);
+    assert_eq!(asset_data.redemption_count, 0);
+    assert_eq!(asset_data.distribution_count, 0);
+    assert_eq!(asset_data.snapshot_id, 0);
+    assert_eq!(asset_data.snapshot_authority(), payer.pubkey());
+    assert_eq!(asset_data.allocation_count, 0);
+    assert!(!asset_data.valuation_required);
+    assert!(!asset_data.price_feed_required);
+    assert!(!asset_data.reserve_required);
+    assert_eq!(asset_data.metadata_version, 0);
+    assert_eq!(asset_data.metadata_content_hash, None);
+    assert_eq!(asset_data.voided_supply, 0);
+    assert_eq!(asset_data.pending_redemptions, 0);
+    assert!(!asset_data.sale_open);
+    assert!(!asset_data.retired);
 }
 
 #[tokio::test]
@@ -137,6 +459,15 @@ async fn test_cross_chain_transfer_initiation() {
         &program_id,
     );
     let (registry_pda, _) = Pubkey::find_program_address(&[b"registry"], &program_id);
+    let target_chain = 5u16; // Polygon (Wormhole chain id)
+    let (chain_config_pda, _) = Pubkey::find_program_address(
+        &[b"chain", &target_chain.to_le_bytes()],
+        &program_id,
+    );
+    let (outbound_transfer_pda, _) = Pubkey::find_program_address(
+        &[b"outbound_transfer", &0u64.to_le_bytes()],
+        &program_id,
+    );
     
     // Create mint and token accounts
     let mint = Keypair::new();
@@ -145,10 +476,14 @@ async fn test_cross_chain_transfer_initiation() {
     let accounts = omniflow_rwa::accounts::InitiateCrossChainTransfer {
         asset: asset_pda,
         registry: registry_pda,
+        target_chain_config: chain_config_pda,
         mint: mint.pubkey(),
         from_token_account: token_account.pubkey(),
+        sender_passport: None,
+        outbound_transfer: outbound_transfer_pda,
         owner: payer.pubkey(),
         token_program: token::ID,
+        system_program: solana_program::system_program::id(),
     };
     
     let instruction = Instruction {
@@ -157,7 +492,7 @@ async fn test_cross_chain_transfer_initiation() {
         data: omniflow_rwa::instruction::InitiateCrossChainTransfer {
             asset_id,
             amount: 1000,
-            target_chain: 137, // Polygon
+            target_chain,
             target_recipient: [1u8; 32],
         }.data(),
     };
@@ -173,52 +508,445 @@ async fn test_cross_chain_transfer_initiation() {
     // banks_client.process_transaction(transaction).await.unwrap();
 }
 
-#[tokio::test]
-async fn test_asset_metadata_update() {
-    let program_id = Pubkey::new_unique();
-    let mut program_test = ProgramTest::new("omniflow_rwa", program_id, processor!(omniflow_rwa::entry));
+#[test]
+fn test_recipient_address_formats() {
+    let mut evm_recipient = [0u8; 32];
+    evm_recipient[31] = 0xab;
+    assert!(AddressFormat::Evm.is_valid_recipient(&evm_recipient));
+    assert!(!AddressFormat::Evm.is_valid_recipient(&[1u8; 32]));
+    assert!(!AddressFormat::Solana.is_valid_recipient(&[0u8; 32]));
+    // Cosmos payloads are 20 or 32 bytes, so a 24-byte one left-padded is malformed
+    let mut cosmos_recipient = [1u8; 32];
+    assert!(AddressFormat::Cosmos.is_valid_recipient(&cosmos_recipient));
+    cosmos_recipient[..8].fill(0);
+    assert!(!AddressFormat::Cosmos.is_valid_recipient(&cosmos_recipient));
+    cosmos_recipient[..12].fill(0);
+    assert!(AddressFormat::Cosmos.is_valid_recipient(&cosmos_recipient));
     
-    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+    assert_eq!(
+        AddressFormat::Evm.decode_address("0x00000000000000000000000000000000000000ab"),
+        Some(evm_recipient)
+    );
+    assert_eq!(AddressFormat::Evm.decode_address("0x1234"), None);
     
-    let asset_id = 1u64;
-    let (asset_pda, _) = Pubkey::find_program_address(
-        &[b"asset", &asset_id.to_le_bytes()],
-        &program_id,
+    let solana_recipient = Pubkey::new_unique();
+    assert_eq!(
+        AddressFormat::Solana.decode_address(&solana_recipient.to_string()),
+        Some(solana_recipient.to_bytes())
+    );
+    // Valid base58, but only the 12 bytes of "Hello World!"
+    assert_eq!(AddressFormat::Solana.decode_address("2NEpo7TZRRrLZSi2U"), None);
+    
+    let cosmos = AddressFormat::Cosmos
+        .decode_address("cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xu")
+        .unwrap();
+    assert_eq!(&cosmos[..12], &[0u8; 12]);
+    assert_eq!(&cosmos[12..], &(1..=20).collect::<Vec<u8>>()[..]);
+    // Corrupted checksum
+    assert_eq!(
+        AddressFormat::Cosmos.decode_address("cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xv"),
+        None
+    );
+}
+
+#[test]
+fn test_asset_attribute_validation() {
+    let real_estate = AssetAttributeData::RealEstate {
+        parcel_id: "APN-123-456".to_string(),
+        square_footage: 12_500,
+        rental_yield_bps: 650,
+    };
+    assert!(real_estate.validate(AssetType::RealEstate).is_ok());
+    assert!(real_estate.validate(AssetType::CarbonCredits).is_err());
+    
+    let carbon = |vintage: u16, tonnes: u64| AssetAttributeData::CarbonCredits {
+        registry: CarbonRegistry::Verra,
+        vintage,
+        project_id: "VCS-1234".to_string(),
+        tonnes,
+    };
+    assert!(carbon(2021, 10_000).validate(AssetType::CarbonCredits).is_ok());
+    assert!(carbon(1985, 10_000).validate(AssetType::CarbonCredits).is_err());
+    assert!(carbon(2021, 0).validate(AssetType::CarbonCredits).is_err());
+    
+    let metals = |bar_serials: Vec<&str>| AssetAttributeData::PreciousMetals {
+        metal: Metal::Gold,
+        fineness: 9999,
+        vault: "Zurich-01".to_string(),
+        bar_serials: bar_serials.into_iter().map(String::from).collect(),
+    };
+    assert!(metals(vec!["AB1001", "AB1002"]).validate(AssetType::PreciousMetals).is_ok());
+    assert!(metals(vec!["AB1001", "AB1001"]).validate(AssetType::PreciousMetals).is_err());
+    assert!(metals(vec![""]).validate(AssetType::PreciousMetals).is_err());
+}
+
+#[test]
+fn test_distribution_balance_proofs() {
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+    let alice_leaf = merkle::balance_leaf(&alice, 750);
+    let bob_leaf = merkle::balance_leaf(&bob, 250);
+    let root = merkle::hash_pair(&alice_leaf, &bob_leaf);
+    
+    assert!(merkle::verify_proof(&[bob_leaf], &root, alice_leaf));
+    assert!(merkle::verify_proof(&[alice_leaf], &root, bob_leaf));
+    assert!(!merkle::verify_proof(&[bob_leaf], &root, merkle::balance_leaf(&alice, 751)));
+    
+    let distribution = Distribution {
+        asset_id: 1,
+        distribution_id: 0,
+        payout_mint: Pubkey::new_unique(),
+        snapshot_id: 1,
+        balances_root: root,
+        snapshot_supply: 1_000,
+        total_amount: 50_000,
+        claimed_amount: 0,
+        claimed_balance: 0,
+        created_at: 0,
+        claim_deadline: 1,
+        swept: false,
+        bump: 255,
+    };
+    assert_eq!(distribution.payout_for(750), Some(37_500));
+    assert_eq!(distribution.payout_for(250), Some(12_500));
+    assert_eq!(distribution.payout_for(u64::MAX), None);
+}
+
+#[test]
+fn test_client_snapshot_balance_queries() {
+    use omniflow_rwa::client::{fetch_snapshot, verify_snapshot_balance};
+    use std::collections::HashMap;
+    
+    let holder = Pubkey::new_unique();
+    let other = Pubkey::new_unique();
+    let holder_leaf = merkle::balance_leaf(&holder, 400);
+    let other_leaf = merkle::balance_leaf(&other, 600);
+    
+    let snapshot = BalanceSnapshot {
+        asset_id: 7,
+        snapshot_id: 1,
+        supply: 1_000,
+        taken_at: 1_700_000_000,
+        balances_root: Some(merkle::hash_pair(&holder_leaf, &other_leaf)),
+        holder_count: 2,
+        committed_by: Some(Pubkey::new_unique()),
+        bump: 255,
+    };
+    let mut data = Vec::new();
+    snapshot.try_serialize(&mut data).unwrap();
+    let accounts = HashMap::from([(BalanceSnapshot::address(7, 1), data)]);
+    let fetcher = |address: &Pubkey| accounts.get(address).cloned();
+    
+    assert_eq!(fetch_snapshot(&fetcher, 7, 1).unwrap().supply, 1_000);
+    assert!(fetch_snapshot(&fetcher, 7, 2).is_none());
+    assert!(verify_snapshot_balance(&fetcher, 7, 1, &holder, 400, &[other_leaf]));
+    assert!(!verify_snapshot_balance(&fetcher, 7, 1, &holder, 600, &[other_leaf]));
+    assert!(!verify_snapshot_balance(&fetcher, 7, 2, &holder, 400, &[other_leaf]));
+}
+
+#[test]
+fn test_allocation_tree_proofs() {
+    let allocations: Vec<(Pubkey, u64)> = (0..5u64)
+        .map(|i| (Pubkey::new_unique(), 1_000 * (i + 1)))
+        .collect();
+    let leaves = allocations
+        .iter()
+        .enumerate()
+        .map(|(index, (recipient, amount))| merkle::allocation_leaf(index as u32, recipient, *amount))
+        .collect();
+    let tree = merkle::MerkleTree::new(leaves);
+    let root = tree.root();
+    
+    for (index, (recipient, amount)) in allocations.iter().enumerate() {
+        let leaf = merkle::allocation_leaf(index as u32, recipient, *amount);
+        assert!(merkle::verify_proof(&tree.proof(index), &root, leaf));
+        // The same allocation under another index must not verify
+        let moved = merkle::allocation_leaf(index as u32 + 1, recipient, *amount);
+        assert!(!merkle::verify_proof(&tree.proof(index), &root, moved));
+    }
+    
+    let mut round = AllocationRound {
+        asset_id: 1,
+        round_id: 0,
+        allocations_root: root,
+        leaf_count: 5,
+        total_allocated: 15_000,
+        claimed_amount: 0,
+        created_at: 0,
+        claimed: vec![0; 1],
+        bump: 255,
+    };
+    assert!(!round.is_claimed(3));
+    round.claimed[0] |= 1 << 3;
+    assert!(round.is_claimed(3));
+    assert!(!round.is_claimed(4));
+}
+
+#[test]
+fn test_primary_sale_pricing_and_refund_window() {
+    let mut sale = PrimarySale {
+        asset_id: 1,
+        quote_mint: Pubkey::new_unique(),
+        // 25 USDC per whole token
+        price_per_token: 25_000_000,
+        start_time: 100,
+        end_time: 200,
+        min_ticket: 1_000_000,
+        max_ticket: 100_000_000,
+        soft_cap: 50_000_000,
+        hard_cap: 200_000_000,
+        required_kyc: KYCLevel::Basic,
+        required_tier: InvestorTier::Retail,
+        sold: 0,
+        raised: 0,
+        refunded: 0,
+        bump: 255,
+    };
+    
+    assert_eq!(sale.cost_of(2_000_000, 6), Some(50_000_000));
+    // Fractions of a quote unit are charged to the buyer
+    assert_eq!(sale.cost_of(1, 6), Some(25));
+    assert_eq!(sale.cost_of(1, 9), Some(1));
+    
+    assert!(!sale.has_ended(150));
+    assert!(sale.has_failed(200));
+    sale.sold = sale.soft_cap;
+    assert!(!sale.has_failed(200));
+    sale.sold = sale.hard_cap;
+    assert!(sale.has_ended(150));
+}
+
+#[test]
+fn test_valuation_history_policy() {
+    let valuation = |effective_date: i64| Valuation {
+        value: 1_000_000 + effective_date as u64,
+        currency: *b"USD",
+        effective_date,
+        methodology_hash: [7; 32],
+        oracle: Pubkey::new_unique(),
+        submitted_at: effective_date,
+    };
+    
+    // A full ring buffer whose newest entry sits just before `next_slot`
+    let mut history = ValuationHistory {
+        asset_id: 1,
+        currency: *b"USD",
+        max_staleness: 86_400,
+        max_deviation_bps: 500,
+        entries: (0..VALUATION_HISTORY_LEN as i64).map(|i| valuation(1_000 + i)).collect(),
+        next_slot: 0,
+        pending_review: false,
+        bump: 255,
+    };
+    history.entries[2] = valuation(5_000);
+    history.next_slot = 3;
+    assert_eq!(history.latest().unwrap().effective_date, 5_000);
+    
+    assert!(history.is_current(5_000 + 86_400));
+    assert!(!history.is_current(5_000 + 86_401));
+    history.pending_review = true;
+    assert!(!history.is_current(5_000));
+    
+    assert_eq!(deviation_bps(1_000_000, 1_050_000), 500);
+    assert_eq!(deviation_bps(1_000_000, 900_000), 1_000);
+    assert_eq!(deviation_bps(0, 900_000), 0);
+    
+    // $1,000,000.00 over 100,000 tokens with 6 decimals is $10.00 per token
+    assert_eq!(ValuationHistory::nav_per_token(100_000_000, 100_000_000_000, 6), Some(1_000));
+    assert_eq!(ValuationHistory::nav_per_token(100_000_000, 0, 6), None);
+}
+
+#[test]
+fn test_price_feed_market_valuation() {
+    // Gold at $2,345.67 per ounce with a $15.00 confidence interval
+    let data = price_feed::mock_price_account_data(234_567_000, 1_500_000, -5, 1_000);
+    let price = PythPrice::parse(&data).unwrap();
+    assert_eq!(price.price, 234_567_000);
+    assert_eq!(price.expo, -5);
+    assert_eq!(price.publish_time, 1_000);
+    assert!(PythPrice::parse(&data[..200]).is_err());
+
+    // 400.5 ounces in custody, valued in cents
+    let feed = PriceFeed {
+        asset_id: 1,
+        price_account: Pubkey::new_unique(),
+        feed_program: Pubkey::new_unique(),
+        max_staleness: 60,
+        max_confidence_bps: 100,
+        custody_quantity: 400_500,
+        quantity_decimals: 3,
+        value_decimals: 2,
+        last_value: 0,
+        last_publish_time: 0,
+        bump: 255,
+    };
+    assert_eq!(feed.value_at(&price, 1_060).unwrap(), 93_944_083);
+    assert!(feed.value_at(&price, 1_061).is_err());
+
+    let wide = PythPrice { conf: 2_400_000, ..price };
+    assert!(feed.value_at(&wide, 1_000).is_err());
+
+    assert_eq!(custody_value(5, 3, 2, 0, 2), Some(1_000_000));
+    assert_eq!(custody_value(u64::MAX, 10, u64::MAX, 0, 0), None);
+}
+
+#[test]
+fn test_reserve_attestation_coverage() {
+    // 250 one-ounce bars, each backing 1,000 tokens with 6 decimals
+    let mut reserve = ReserveAttestation {
+        asset_id: 1,
+        custodian: Pubkey::new_unique(),
+        max_staleness: 30 * 86_400,
+        tokens_per_unit: 1_000_000_000,
+        quantity: 250,
+        audit_report_hash: [9; 32],
+        auditor: Pubkey::new_unique(),
+        attested_at: 0,
+        attestation_count: 0,
+        bump: 255,
+    };
+    assert!(!reserve.is_current(0));
+
+    reserve.attested_at = 1_000;
+    reserve.attestation_count = 1;
+    assert!(reserve.is_current(1_000 + 30 * 86_400));
+    assert!(!reserve.is_current(1_001 + 30 * 86_400));
+
+    assert_eq!(reserve.backed_supply(), 250_000_000_000);
+    assert_eq!(reserve.collateralization_bps(200_000_000_000), Some(12_500));
+    assert_eq!(reserve.collateralization_bps(250_000_000_000), Some(10_000));
+    assert_eq!(reserve.collateralization_bps(0), None);
+}
+
+#[test]
+fn test_client_document_history() {
+    use omniflow_rwa::client::{document_history, latest_document};
+    use sha2::{Digest, Sha256};
+    use std::collections::HashMap;
+
+    let memorandum_v1 = b"Offering memorandum, January edition".as_slice();
+    let memorandum_v2 = b"Offering memorandum, amended in March".as_slice();
+    let document = |version: u32, content: &[u8], previous_hash| AssetDocument {
+        asset_id: 3,
+        document_type: DocumentType::OfferingMemorandum,
+        version,
+        content_hash: Sha256::digest(content).into(),
+        uri: format!("ipfs://memorandum/v{version}"),
+        effective_date: 1_700_000_000 + version as i64,
+        previous_hash,
+        published_by: Pubkey::new_unique(),
+        published_at: 1_700_000_000 + version as i64,
+        bump: 255,
+    };
+    let first = document(1, memorandum_v1, None);
+    let second = document(2, memorandum_v2, Some(first.content_hash));
+    assert!(second.verify_content(memorandum_v2));
+    assert!(!second.verify_content(memorandum_v1));
+
+    let mut accounts = HashMap::new();
+    for doc in [&first, &second] {
+        let mut data = Vec::new();
+        doc.try_serialize(&mut data).unwrap();
+        accounts.insert(AssetDocument::address(3, doc.document_type, doc.version), data);
+    }
+    let fetcher = |address: &Pubkey| accounts.get(address).cloned();
+
+    let history = document_history(&fetcher, 3, DocumentType::OfferingMemorandum);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[1].previous_hash, Some(history[0].content_hash));
+    let latest = latest_document(&fetcher, 3, DocumentType::OfferingMemorandum).unwrap();
+    assert_eq!(latest.uri, "ipfs://memorandum/v2");
+    assert!(latest_document(&fetcher, 3, DocumentType::TitleDeed).is_none());
+}
+
+#[test]
+fn test_client_asset_index_iteration() {
+    use omniflow_rwa::client::{assets_by_owner, assets_by_type, iter_assets};
+    use std::collections::HashMap;
+    
+    let issuer = Pubkey::new_unique();
+    let entry = |asset_id: u64| AssetIndexEntry {
+        asset_id,
+        asset_type: if asset_id % 2 == 0 { AssetType::RealEstate } else { AssetType::CarbonCredits },
+        owner: if asset_id < 5 { issuer } else { Pubkey::new_unique() },
+        status: AssetStatus::Active,
+    };
+    
+    // One full page followed by a partially filled one
+    let mut accounts = HashMap::new();
+    let page_size = ASSET_INDEX_PAGE_SIZE;
+    for (page, ids) in [(0u64, 0..page_size), (1, page_size..page_size + 3)] {
+        let index_page = AssetIndexPage {
+            page,
+            entries: ids.map(entry).collect(),
+            bump: 255,
+        };
+        let mut data = Vec::new();
+        index_page.try_serialize(&mut data).unwrap();
+        accounts.insert(AssetIndexPage::address(page), data);
+    }
+    let fetcher = |address: &Pubkey| accounts.get(address).cloned();
+    
+    let all: Vec<u64> = iter_assets(&fetcher).map(|entry| entry.asset_id).collect();
+    assert_eq!(all, (0..page_size + 3).collect::<Vec<u64>>());
+    assert_eq!(assets_by_owner(&fetcher, issuer).count(), 5);
+    assert_eq!(
+        assets_by_type(&fetcher, AssetType::RealEstate).count() as u64,
+        (page_size + 3 + 1) / 2
     );
+}
+
+#[tokio::test]
+async fn test_asset_metadata_update() {
+    let mut program_test = program_test();
+    let authority = add_funded_signer(&mut program_test);
+    let owner = add_funded_signer(&mut program_test);
+    let registry = add_registry(&mut program_test, &registry_state(authority.pubkey()));
+    let asset_id = 1u64;
+    let asset = add_asset(&mut program_test, &asset_state(asset_id, owner.pubkey()));
+    let mut context = program_test.start_with_context().await;
     
+    let metadata_revision = MetadataRevision::address(asset_id, 1);
     let accounts = omniflow_rwa::accounts::UpdateAssetMetadata {
-        asset: asset_pda,
-        owner: payer.pubkey(),
+        asset,
+        registry,
+        metadata_revision,
+        registry_authority: None,
+        owner: owner.pubkey(),
+        system_program: solana_program::system_program::ID,
     };
     
     let new_metadata_uri = "https://example.com/metadata/1-updated".to_string();
     let new_kyc_level = Some(KYCLevel::Institutional);
+    let content_hash = [7u8; 32];
     
-    let instruction = Instruction {
-        program_id,
-        accounts: accounts.to_account_metas(Some(true)),
-        data: omniflow_rwa::instruction::UpdateAssetMetadata {
+    let instruction = instruction(
+        accounts,
+        omniflow_rwa::instruction::UpdateAssetMetadata {
             asset_id,
             new_metadata_uri: new_metadata_uri.clone(),
+            content_hash,
             new_kyc_level,
-        }.data(),
-    };
-    
-    let transaction = Transaction::new_signed_with_payer(
-        &[instruction],
-        Some(&payer.pubkey()),
-        &[&payer],
-        recent_blockhash,
+            reason: "Annual appraisal".to_string(),
+        },
     );
-    
-    banks_client.process_transaction(transaction).await.unwrap();
+    process(&mut context, instruction, &[&owner]).await.unwrap();
     
     // Verify metadata was updated
-    let asset_account = banks_client.get_account(asset_pda).await.unwrap().unwrap();
-    let asset_data: RWAAsset = RWAAsset::try_deserialize(&mut &asset_account.data[8..]).unwrap();
-    
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
     assert_eq!(asset_data.metadata_uri, new_metadata_uri);
-    assert_eq!(asset_data.kyc_level, KYCLevel::Institutional);
+    assert!(asset_data.kyc_level == KYCLevel::Institutional);
+    assert_eq!(asset_data.metadata_version, 1);
+    assert_eq!(asset_data.metadata_content_hash, Some(content_hash));
+    
+    let revision: MetadataRevision = fetch(&mut context, metadata_revision).await;
+    assert_eq!(revision.version, 1);
+    assert_eq!(revision.content_hash, content_hash);
+    assert_eq!(revision.previous_content_hash, None);
+    assert!(revision.kyc_level == KYCLevel::Institutional);
+    assert!(revision.previous_kyc_level == KYCLevel::None);
+    assert_eq!(revision.reason, "Annual appraisal");
+    assert_eq!(revision.updated_by, owner.pubkey());
 }
 
 #[tokio::test]
@@ -232,6 +960,9 @@ async fn test_registry_pause_functionality() {
     
     let accounts = omniflow_rwa::accounts::SetRegistryPause {
         registry: registry_pda,
+        pauser_role: None,
+        multisig: Pubkey::find_program_address(&[b"multisig"], &program_id).0,
+        multisig_proposal: None,
         authority: payer.pubkey(),
     };
     
@@ -256,7 +987,7 @@ async fn test_registry_pause_functionality() {
     let registry_account = banks_client.get_account(registry_pda).await.unwrap().unwrap();
     let registry_data: Registry = Registry::try_deserialize(&mut &registry_account.data[8..]).unwrap();
     
-    assert!(registry_data.paused);
+    assert_eq!(registry_data.pause_flags, PAUSE_ALL);
 }
 
 // Integration tests for cross-chain functionality
@@ -304,3 +1035,3309 @@ async fn test_multiple_asset_registration() {
     
     // Verify all assets are registered correctly
 }
+
+fn mint_rwa_tokens_instruction(
+    asset_id: u64,
+    owner: Pubkey,
+    mint: Pubkey,
+    recipient: Pubkey,
+    recipient_passport: Option<Pubkey>,
+    amount: u64,
+) -> Instruction {
+    instruction(
+        omniflow_rwa::accounts::MintRWATokens {
+            asset: RWAAsset::address(asset_id),
+            registry: pda(&[b"registry"]),
+            mint,
+            token_account: spl_associated_token_account::get_associated_token_address(&recipient, &mint),
+            recipient,
+            recipient_passport,
+            valuation: None,
+            reserve: None,
+            owner,
+            token_program: token::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+            system_program: solana_program::system_program::id(),
+        },
+        omniflow_rwa::instruction::MintRwaTokens { asset_id, amount },
+    )
+}
+
+#[tokio::test]
+async fn test_mint_rwa_tokens_to_passport_holder() {
+    let mut program_test = program_test();
+    let owner = add_funded_signer(&mut program_test);
+    let asset_id = 1u64;
+    add_registry(&mut program_test, &registry_state(owner.pubkey()));
+    let asset = add_asset(
+        &mut program_test,
+        &RWAAsset {
+            kyc_level: KYCLevel::Basic,
+            ..asset_state(asset_id, owner.pubkey())
+        },
+    );
+    let mint = add_asset_mint(&mut program_test, asset_id, 0);
+    let investor = Pubkey::new_unique();
+    let passport = add_passport(&mut program_test, investor, identity::KYCLevel::Enhanced);
+    let mut context = program_test.start_with_context().await;
+    
+    // A KYC asset cannot be minted to a recipient without a passport
+    let result = process(
+        &mut context,
+        mint_rwa_tokens_instruction(asset_id, owner.pubkey(), mint, investor, None, 500),
+        &[&owner],
+    )
+    .await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::KycRequirementNotMet);
+    
+    process(
+        &mut context,
+        mint_rwa_tokens_instruction(asset_id, owner.pubkey(), mint, investor, Some(passport), 500),
+        &[&owner],
+    )
+    .await
+    .unwrap();
+    
+    let token_account = spl_associated_token_account::get_associated_token_address(&investor, &mint);
+    assert_eq!(token_balance(&mut context, token_account).await, 500);
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert_eq!(asset_data.circulating_supply, 500);
+}
+
+#[tokio::test]
+async fn test_claim_allocation_bounded_by_round_total() {
+    let mut program_test = program_test();
+    let owner = Pubkey::new_unique();
+    let recipient = add_funded_signer(&mut program_test);
+    let asset_id = 1u64;
+    add_registry(&mut program_test, &registry_state(owner));
+    let asset = add_asset(&mut program_test, &asset_state(asset_id, owner));
+    let mint = add_asset_mint(&mut program_test, asset_id, 0);
+    
+    // The committed leaves add up to more than the round total
+    let leaves = vec![
+        merkle::allocation_leaf(0, &recipient.pubkey(), 600),
+        merkle::allocation_leaf(1, &recipient.pubkey(), 600),
+    ];
+    let tree = merkle::MerkleTree::new(leaves);
+    let allocation_round = pda(&[b"allocation", &asset_id.to_le_bytes(), &0u64.to_le_bytes()]);
+    add_program_account(
+        &mut program_test,
+        allocation_round,
+        &AllocationRound {
+            asset_id,
+            round_id: 0,
+            allocations_root: tree.root(),
+            leaf_count: 2,
+            total_allocated: 1_000,
+            claimed_amount: 0,
+            created_at: 0,
+            claimed: vec![0],
+            bump: Pubkey::find_program_address(
+                &[b"allocation", &asset_id.to_le_bytes(), &0u64.to_le_bytes()],
+                &omniflow_rwa::ID,
+            )
+            .1,
+        },
+        8 + AllocationRound::INIT_SPACE,
+    );
+    let mut context = program_test.start_with_context().await;
+    
+    let claim = |index: u32| {
+        instruction(
+            omniflow_rwa::accounts::ClaimAllocation {
+                asset,
+                registry: pda(&[b"registry"]),
+                allocation_round,
+                mint,
+                token_account: spl_associated_token_account::get_associated_token_address(
+                    &recipient.pubkey(),
+                    &mint,
+                ),
+                recipient_passport: None,
+                valuation: None,
+                reserve: None,
+                recipient: recipient.pubkey(),
+                token_program: token::ID,
+                associated_token_program: anchor_spl::associated_token::ID,
+                system_program: solana_program::system_program::id(),
+            },
+            omniflow_rwa::instruction::ClaimAllocation {
+                index,
+                amount: 600,
+                proof: tree.proof(index as usize),
+            },
+        )
+    };
+    
+    process(&mut context, claim(0), &[&recipient]).await.unwrap();
+    let round: AllocationRound = fetch(&mut context, allocation_round).await;
+    assert_eq!(round.claimed_amount, 600);
+    assert!(round.is_claimed(0));
+    
+    let result = process(&mut context, claim(1), &[&recipient]).await;
+    assert_program_error(result, AllocationError::ExceedsRoundTotal);
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert_eq!(asset_data.circulating_supply, 600);
+}
+
+#[tokio::test]
+async fn test_registry_authority_handover() {
+    let mut program_test = program_test();
+    let authority = add_funded_signer(&mut program_test);
+    let new_authority = add_funded_signer(&mut program_test);
+    let outsider = add_funded_signer(&mut program_test);
+    let registry = add_registry(&mut program_test, &registry_state(authority.pubkey()));
+    let mut context = program_test.start_with_context().await;
+    
+    let manage = |signer: &Keypair| omniflow_rwa::accounts::ManageRegistryAuthority {
+        registry,
+        multisig: pda(&[b"multisig"]),
+        multisig_proposal: None,
+        authority: signer.pubkey(),
+    };
+    let accept = |signer: &Keypair| {
+        instruction(
+            omniflow_rwa::accounts::AcceptRegistryAuthority {
+                registry,
+                new_authority: signer.pubkey(),
+            },
+            omniflow_rwa::instruction::AcceptAuthority {},
+        )
+    };
+    
+    let propose = omniflow_rwa::instruction::ProposeAuthority {
+        new_authority: new_authority.pubkey(),
+    };
+    let result = process(&mut context, instruction(manage(&outsider), propose), &[&outsider]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::Unauthorized);
+    
+    let propose = omniflow_rwa::instruction::ProposeAuthority {
+        new_authority: new_authority.pubkey(),
+    };
+    process(&mut context, instruction(manage(&authority), propose), &[&authority])
+        .await
+        .unwrap();
+    let registry_data: Registry = fetch(&mut context, registry).await;
+    assert_eq!(registry_data.pending_authority, Some(new_authority.pubkey()));
+    
+    // Only the proposed key can accept
+    let result = process(&mut context, accept(&outsider), &[&outsider]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::Unauthorized);
+    process(&mut context, accept(&new_authority), &[&new_authority])
+        .await
+        .unwrap();
+    let registry_data: Registry = fetch(&mut context, registry).await;
+    assert_eq!(registry_data.authority, new_authority.pubkey());
+    assert_eq!(registry_data.pending_authority, None);
+    
+    // The new authority proposes and then withdraws a handover
+    let propose = omniflow_rwa::instruction::ProposeAuthority {
+        new_authority: outsider.pubkey(),
+    };
+    process(&mut context, instruction(manage(&new_authority), propose), &[&new_authority])
+        .await
+        .unwrap();
+    let cancel = omniflow_rwa::instruction::CancelAuthorityTransfer {};
+    let result = process(&mut context, instruction(manage(&authority), cancel), &[&authority]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::Unauthorized);
+    let cancel = omniflow_rwa::instruction::CancelAuthorityTransfer {};
+    process(&mut context, instruction(manage(&new_authority), cancel), &[&new_authority])
+        .await
+        .unwrap();
+    let registry_data: Registry = fetch(&mut context, registry).await;
+    assert_eq!(registry_data.pending_authority, None);
+    let result = process(&mut context, accept(&outsider), &[&outsider]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::Unauthorized);
+}
+
+#[tokio::test]
+async fn test_identity_registry_authority_handover() {
+    let mut program_test = program_test();
+    let authority = add_funded_signer(&mut program_test);
+    let new_authority = add_funded_signer(&mut program_test);
+    let outsider = add_funded_signer(&mut program_test);
+    let (registry, bump) = Pubkey::find_program_address(&[b"identity_registry"], &omniflow_rwa::ID);
+    add_program_account(
+        &mut program_test,
+        registry,
+        &IdentityRegistry {
+            authority: authority.pubkey(),
+            pending_authority: None,
+            total_passports: 0,
+            authorized_issuers: vec![authority.pubkey()],
+            cross_chain_bridge: None,
+            paused: false,
+            bump,
+        },
+        IdentityRegistry::MAX_SIZE,
+    );
+    let mut context = program_test.start_with_context().await;
+    
+    let manage = |signer: &Keypair| omniflow_rwa::accounts::ManageIdentityAuthority {
+        registry,
+        authority: signer.pubkey(),
+    };
+    let accept = |signer: &Keypair| {
+        instruction(
+            omniflow_rwa::accounts::AcceptIdentityAuthority {
+                registry,
+                new_authority: signer.pubkey(),
+            },
+            omniflow_rwa::instruction::AcceptIdentityAuthority {},
+        )
+    };
+    
+    let propose = omniflow_rwa::instruction::ProposeIdentityAuthority {
+        new_authority: new_authority.pubkey(),
+    };
+    let result = process(&mut context, instruction(manage(&outsider), propose), &[&outsider]).await;
+    assert_program_error(result, IdentityError::Unauthorized);
+    
+    let propose = omniflow_rwa::instruction::ProposeIdentityAuthority {
+        new_authority: new_authority.pubkey(),
+    };
+    process(&mut context, instruction(manage(&authority), propose), &[&authority])
+        .await
+        .unwrap();
+    let registry_data: IdentityRegistry = fetch(&mut context, registry).await;
+    assert_eq!(registry_data.pending_authority, Some(new_authority.pubkey()));
+    
+    let result = process(&mut context, accept(&outsider), &[&outsider]).await;
+    assert_program_error(result, IdentityError::Unauthorized);
+    process(&mut context, accept(&new_authority), &[&new_authority])
+        .await
+        .unwrap();
+    let registry_data: IdentityRegistry = fetch(&mut context, registry).await;
+    assert_eq!(registry_data.authority, new_authority.pubkey());
+    assert_eq!(registry_data.pending_authority, None);
+    
+    let propose = omniflow_rwa::instruction::ProposeIdentityAuthority {
+        new_authority: outsider.pubkey(),
+    };
+    process(&mut context, instruction(manage(&new_authority), propose), &[&new_authority])
+        .await
+        .unwrap();
+    let cancel = omniflow_rwa::instruction::CancelIdentityAuthorityTransfer {};
+    let result = process(&mut context, instruction(manage(&authority), cancel), &[&authority]).await;
+    assert_program_error(result, IdentityError::Unauthorized);
+    let cancel = omniflow_rwa::instruction::CancelIdentityAuthorityTransfer {};
+    process(&mut context, instruction(manage(&new_authority), cancel), &[&new_authority])
+        .await
+        .unwrap();
+    let registry_data: IdentityRegistry = fetch(&mut context, registry).await;
+    assert_eq!(registry_data.pending_authority, None);
+    let cancel = omniflow_rwa::instruction::CancelIdentityAuthorityTransfer {};
+    let result = process(&mut context, instruction(manage(&new_authority), cancel), &[&new_authority]).await;
+    assert_program_error(result, IdentityError::NoPendingAuthority);
+}
+
+#[tokio::test]
+async fn test_retire_asset_keeps_tombstone() {
+    let mut program_test = program_test();
+    let owner = add_funded_signer(&mut program_test);
+    let registry = add_registry(
+        &mut program_test,
+        &Registry {
+            total_assets: 2,
+            next_asset_id: 3,
+            indexed_assets: 2,
+            ..registry_state(owner.pubkey())
+        },
+    );
+    let redeeming = RWAAsset {
+        pending_redemptions: 1,
+        ..asset_state(1, owner.pubkey())
+    };
+    let idle = RWAAsset {
+        index_position: 1,
+        ..asset_state(2, owner.pubkey())
+    };
+    add_asset(&mut program_test, &redeeming);
+    let asset = add_asset(&mut program_test, &idle);
+    let asset_index_page = add_asset_index_page(&mut program_test, &[&redeeming, &idle]);
+    let issuer_role = add_role(&mut program_test, Role::Issuer, owner.pubkey());
+    let mut context = program_test.start_with_context().await;
+    
+    let retire = |asset_id: u64| {
+        instruction(
+            omniflow_rwa::accounts::RetireAsset {
+                asset: RWAAsset::address(asset_id),
+                registry,
+                asset_index_page,
+                sale: None,
+                authority: owner.pubkey(),
+            },
+            omniflow_rwa::instruction::RetireAsset { asset_id },
+        )
+    };
+    
+    let result = process(&mut context, retire(1), &[&owner]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::AssetHasOpenObligations);
+    
+    process(&mut context, retire(2), &[&owner]).await.unwrap();
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert!(asset_data.retired);
+    assert!(!asset_data.is_active);
+    let registry_data: Registry = fetch(&mut context, registry).await;
+    assert_eq!(registry_data.total_assets, 1);
+    let page: AssetIndexPage = fetch(&mut context, asset_index_page).await;
+    assert_eq!(page.entries[1].status, AssetStatus::Retired);
+    
+    let result = process(&mut context, retire(2), &[&owner]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::AssetAlreadyRetired);
+    let reactivate = instruction(
+        omniflow_rwa::accounts::ManageAssetLifecycle {
+            asset,
+            registry,
+            asset_index_page,
+            authority: owner.pubkey(),
+        },
+        omniflow_rwa::instruction::ReactivateAsset { asset_id: 2 },
+    );
+    let result = process(&mut context, reactivate, &[&owner]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::AssetAlreadyRetired);
+    
+    // The retired id cannot be registered again, so its PDA never changes hands
+    let register = instruction(
+        omniflow_rwa::accounts::RegisterRWAAsset {
+            asset,
+            registry,
+            issuer_role: Some(issuer_role),
+            asset_index_page,
+            asset_attributes: None,
+            owner: owner.pubkey(),
+            system_program: solana_program::system_program::id(),
+        },
+        omniflow_rwa::instruction::RegisterRwaAsset {
+            asset_id: 2,
+            asset_type: AssetType::RealEstate,
+            metadata_uri: "https://example.com/metadata/2".to_string(),
+            kyc_level: KYCLevel::None,
+            total_value: 1_000_000,
+            total_supply: 100_000,
+            chain_id: 1,
+            attributes: None,
+        },
+    );
+    assert!(process(&mut context, register, &[&owner]).await.is_err());
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert!(asset_data.retired);
+}
+
+fn sale_state(asset_id: u64, quote_mint: Pubkey) -> PrimarySale {
+    PrimarySale {
+        asset_id,
+        quote_mint,
+        price_per_token: 10,
+        start_time: 0,
+        end_time: 2_000_000_000,
+        min_ticket: 10,
+        max_ticket: 1_000,
+        soft_cap: 500,
+        hard_cap: 1_000,
+        required_kyc: KYCLevel::None,
+        required_tier: InvestorTier::None,
+        sold: 0,
+        raised: 0,
+        refunded: 0,
+        bump: Pubkey::find_program_address(&[b"primary_sale", &asset_id.to_le_bytes()], &omniflow_rwa::ID).1,
+    }
+}
+
+/// Adds a sale with empty treasury and escrow accounts and returns the sale address
+fn add_sale(program_test: &mut ProgramTest, sale: &PrimarySale, mint: Pubkey, escrowed: u64) -> Pubkey {
+    let address = pda(&[b"primary_sale", &sale.asset_id.to_le_bytes()]);
+    add_program_account(program_test, address, sale, 8 + PrimarySale::INIT_SPACE);
+    let treasury = pda(&[b"sale_treasury", address.as_ref()]);
+    add_token_account_at(program_test, treasury, sale.quote_mint, address, sale.raised);
+    let escrow = pda(&[b"sale_escrow", address.as_ref()]);
+    add_token_account_at(program_test, escrow, mint, address, escrowed);
+    address
+}
+
+#[tokio::test]
+async fn test_create_primary_sale_checks() {
+    let mut program_test = program_test();
+    let owner = add_funded_signer(&mut program_test);
+    let near_cap = RWAAsset {
+        kyc_level: KYCLevel::Basic,
+        total_supply: u64::MAX,
+        circulating_supply: u64::MAX - 10,
+        ..asset_state(1, owner.pubkey())
+    };
+    let issuable = RWAAsset {
+        kyc_level: KYCLevel::Basic,
+        ..asset_state(2, owner.pubkey())
+    };
+    add_asset(&mut program_test, &near_cap);
+    add_asset(&mut program_test, &issuable);
+    let mints = [
+        add_asset_mint(&mut program_test, 1, 0),
+        add_asset_mint(&mut program_test, 2, 0),
+    ];
+    let quote_mint = Pubkey::new_unique();
+    add_mint(&mut program_test, quote_mint, Pubkey::new_unique(), 0);
+    let mut context = program_test.start_with_context().await;
+    let now = context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp;
+    
+    let create = |asset_id: u64, mint: Pubkey, required_kyc: KYCLevel| {
+        let sale = pda(&[b"primary_sale", &asset_id.to_le_bytes()]);
+        instruction(
+            omniflow_rwa::accounts::CreatePrimarySale {
+                asset: RWAAsset::address(asset_id),
+                sale,
+                quote_mint,
+                treasury: pda(&[b"sale_treasury", sale.as_ref()]),
+                mint,
+                escrow: pda(&[b"sale_escrow", sale.as_ref()]),
+                owner: owner.pubkey(),
+                token_program: token::ID,
+                system_program: solana_program::system_program::id(),
+            },
+            omniflow_rwa::instruction::CreatePrimarySale {
+                asset_id,
+                price_per_token: 10,
+                start_time: now,
+                end_time: now + 1_000,
+                min_ticket: 10,
+                max_ticket: 1_000,
+                soft_cap: 500,
+                hard_cap: 1_000,
+                required_kyc,
+                required_tier: InvestorTier::None,
+            },
+        )
+    };
+    
+    // Overflowing the supply cap is an error rather than a panic
+    let result = process(&mut context, create(1, mints[0], KYCLevel::Basic), &[&owner]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::ExceedsMaxSupply);
+    
+    let result = process(&mut context, create(2, mints[1], KYCLevel::None), &[&owner]).await;
+    assert_program_error(result, SaleError::KycBelowAssetLevel);
+    
+    process(&mut context, create(2, mints[1], KYCLevel::Enhanced), &[&owner])
+        .await
+        .unwrap();
+    let sale: PrimarySale = fetch(&mut context, pda(&[b"primary_sale", &2u64.to_le_bytes()])).await;
+    assert!(sale.required_kyc == KYCLevel::Enhanced);
+    let asset_data: RWAAsset = fetch(&mut context, RWAAsset::address(2)).await;
+    assert!(asset_data.sale_open);
+}
+
+#[tokio::test]
+async fn test_primary_sale_escrow_refund_and_delivery() {
+    let mut program_test = program_test();
+    let owner = Pubkey::new_unique();
+    add_registry(&mut program_test, &registry_state(owner));
+    let quote_mint = Pubkey::new_unique();
+    add_mint(&mut program_test, quote_mint, Pubkey::new_unique(), 0);
+    
+    // Asset 1 raised its KYC level after its sale opened; asset 2's sale already met the soft cap
+    let failing = add_asset(
+        &mut program_test,
+        &RWAAsset {
+            kyc_level: KYCLevel::Enhanced,
+            sale_open: true,
+            ..asset_state(1, owner)
+        },
+    );
+    add_asset(
+        &mut program_test,
+        &RWAAsset {
+            circulating_supply: 500,
+            sale_open: true,
+            ..asset_state(2, owner)
+        },
+    );
+    let failing_mint = add_asset_mint(&mut program_test, 1, 0);
+    let funded_mint = add_asset_mint(&mut program_test, 2, 500);
+    let failing_sale = add_sale(&mut program_test, &sale_state(1, quote_mint), failing_mint, 0);
+    let funded_sale = add_sale(
+        &mut program_test,
+        &PrimarySale {
+            sold: 500,
+            raised: 5_000,
+            ..sale_state(2, quote_mint)
+        },
+        funded_mint,
+        500,
+    );
+    
+    let buyer = add_funded_signer(&mut program_test);
+    let basic_buyer = add_funded_signer(&mut program_test);
+    let buyer_quote = add_token_account(&mut program_test, quote_mint, buyer.pubkey(), 100_000);
+    let basic_buyer_quote = add_token_account(&mut program_test, quote_mint, basic_buyer.pubkey(), 100_000);
+    let passport = add_passport(&mut program_test, buyer.pubkey(), identity::KYCLevel::Enhanced);
+    let basic_passport = add_passport(&mut program_test, basic_buyer.pubkey(), identity::KYCLevel::Basic);
+    let funded_purchase = pda(&[b"sale_purchase", funded_sale.as_ref(), buyer.pubkey().as_ref()]);
+    add_program_account(
+        &mut program_test,
+        funded_purchase,
+        &SalePurchase {
+            sale: funded_sale,
+            buyer: buyer.pubkey(),
+            amount: 500,
+            paid: 5_000,
+            refunded: false,
+            delivered: 0,
+            bump: Pubkey::find_program_address(
+                &[b"sale_purchase", funded_sale.as_ref(), buyer.pubkey().as_ref()],
+                &omniflow_rwa::ID,
+            )
+            .1,
+        },
+        8 + SalePurchase::INIT_SPACE,
+    );
+    let mut context = program_test.start_with_context().await;
+    
+    let escrow = pda(&[b"sale_escrow", failing_sale.as_ref()]);
+    let treasury = pda(&[b"sale_treasury", failing_sale.as_ref()]);
+    let purchase = |buyer: &Keypair| pda(&[b"sale_purchase", failing_sale.as_ref(), buyer.pubkey().as_ref()]);
+    let buy = |buyer: &Keypair, buyer_quote_account: Pubkey, buyer_passport: Pubkey, amount: u64| {
+        instruction(
+            omniflow_rwa::accounts::BuyPrimarySale {
+                asset: failing,
+                registry: pda(&[b"registry"]),
+                sale: failing_sale,
+                purchase: purchase(buyer),
+                treasury,
+                mint: failing_mint,
+                escrow,
+                buyer_quote_account,
+                buyer_passport: Some(buyer_passport),
+                valuation: None,
+                reserve: None,
+                price_feed: None,
+                price_account: None,
+                buyer: buyer.pubkey(),
+                token_program: token::ID,
+                system_program: solana_program::system_program::id(),
+            },
+            omniflow_rwa::instruction::BuyPrimarySale { amount },
+        )
+    };
+    
+    // The sale has no KYC requirement of its own, but the asset's still applies
+    let result = process(&mut context, buy(&basic_buyer, basic_buyer_quote, basic_passport, 100), &[&basic_buyer]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::KycRequirementNotMet);
+    
+    process(&mut context, buy(&buyer, buyer_quote, passport, 200), &[&buyer])
+        .await
+        .unwrap();
+    assert_eq!(token_balance(&mut context, escrow).await, 200);
+    assert_eq!(token_balance(&mut context, treasury).await, 2_000);
+    assert_eq!(token_balance(&mut context, buyer_quote).await, 98_000);
+    
+    let buyer_tokens = spl_associated_token_account::get_associated_token_address(&buyer.pubkey(), &failing_mint);
+    let claim = |sale: Pubkey, purchase: Pubkey, mint: Pubkey| {
+        instruction(
+            omniflow_rwa::accounts::ClaimSaleTokens {
+                sale,
+                purchase,
+                escrow: pda(&[b"sale_escrow", sale.as_ref()]),
+                mint,
+                buyer_token_account: spl_associated_token_account::get_associated_token_address(
+                    &buyer.pubkey(),
+                    &mint,
+                ),
+                buyer: buyer.pubkey(),
+                token_program: token::ID,
+                associated_token_program: anchor_spl::associated_token::ID,
+                system_program: solana_program::system_program::id(),
+            },
+            omniflow_rwa::instruction::ClaimSaleTokens {},
+        )
+    };
+    let result = process(&mut context, claim(failing_sale, purchase(&buyer), failing_mint), &[&buyer]).await;
+    assert_program_error(result, SaleError::SoftCapNotMet);
+    assert!(context.banks_client.get_account(buyer_tokens).await.unwrap().is_none());
+    
+    // The sale ends short of its soft cap and the escrowed tokens are burned for the refund
+    warp_to_timestamp(&mut context, 2_000_000_000).await;
+    let refund = instruction(
+        omniflow_rwa::accounts::RefundPrimarySale {
+            asset: failing,
+            sale: failing_sale,
+            purchase: purchase(&buyer),
+            treasury,
+            mint: failing_mint,
+            escrow,
+            buyer_quote_account: buyer_quote,
+            buyer: buyer.pubkey(),
+            token_program: token::ID,
+        },
+        omniflow_rwa::instruction::RefundPrimarySale {},
+    );
+    process(&mut context, refund, &[&buyer]).await.unwrap();
+    assert_eq!(token_balance(&mut context, escrow).await, 0);
+    assert_eq!(token_balance(&mut context, buyer_quote).await, 100_000);
+    let asset_data: RWAAsset = fetch(&mut context, failing).await;
+    assert_eq!(asset_data.circulating_supply, 0);
+    
+    // A sale that met its soft cap releases the escrow to the buyer once
+    process(&mut context, claim(funded_sale, funded_purchase, funded_mint), &[&buyer])
+        .await
+        .unwrap();
+    let delivered_to = spl_associated_token_account::get_associated_token_address(&buyer.pubkey(), &funded_mint);
+    assert_eq!(token_balance(&mut context, delivered_to).await, 500);
+    let result = process(&mut context, claim(funded_sale, funded_purchase, funded_mint), &[&buyer]).await;
+    assert_program_error(result, SaleError::NothingToDeliver);
+}
+
+fn add_timelock(program_test: &mut ProgramTest, delays: [i64; 3]) -> Pubkey {
+    let (address, bump) = Pubkey::find_program_address(&[b"timelock"], &omniflow_rwa::ID);
+    let timelock_config = TimelockConfig {
+        delays,
+        action_count: 0,
+        bump,
+    };
+    add_program_account(program_test, address, &timelock_config, 8 + TimelockConfig::INIT_SPACE);
+    address
+}
+
+#[tokio::test]
+async fn test_recipient_policy_timelock() {
+    let mut program_test = program_test();
+    let authority = add_funded_signer(&mut program_test);
+    let owner = add_funded_signer(&mut program_test);
+    let registry = add_registry(&mut program_test, &registry_state(authority.pubkey()));
+    let asset = add_asset(&mut program_test, &asset_state(1, owner.pubkey()));
+    let timelock_config = add_timelock(&mut program_test, [0, 3_600, 0]);
+    let mut context = program_test.start_with_context().await;
+    warp_to_timestamp(&mut context, 1_000).await;
+    
+    // The owner can no longer flip the policy directly
+    let set_policy = instruction(
+        omniflow_rwa::accounts::SetRecipientPolicy {
+            asset,
+            timelock_config,
+            owner: owner.pubkey(),
+        },
+        omniflow_rwa::instruction::SetRecipientPolicy {
+            asset_id: 1,
+            require_verified_recipient: true,
+        },
+    );
+    let result = process(&mut context, set_policy, &[&owner]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::TimelockRequired);
+    
+    let queued_action = pda(&[b"timelock_action", &0u64.to_le_bytes()]);
+    let queue = instruction(
+        omniflow_rwa::accounts::QueueTimelockAction {
+            timelock_config,
+            queued_action,
+            registry,
+            admin_role: None,
+            authority: authority.pubkey(),
+            system_program: solana_program::system_program::ID,
+        },
+        omniflow_rwa::instruction::QueueTimelockAction {
+            action: TimelockAction::SetRecipientPolicy {
+                asset_id: 1,
+                require_verified_recipient: true,
+            },
+        },
+    );
+    process(&mut context, queue, &[&authority]).await.unwrap();
+    let queued: QueuedAction = fetch(&mut context, queued_action).await;
+    assert_eq!(queued.eta, 4_600);
+    
+    let executor = context.payer.pubkey();
+    let execute = || {
+        instruction(
+            omniflow_rwa::accounts::ExecuteTimelockAction {
+                queued_action,
+                timelock_config,
+                registry,
+                asset: Some(asset),
+                metadata_revision: None,
+                rent_receiver: authority.pubkey(),
+                executor,
+                system_program: solana_program::system_program::ID,
+            },
+            omniflow_rwa::instruction::ExecuteTimelockAction {},
+        )
+    };
+    let result = process(&mut context, execute(), &[]).await;
+    assert_program_error(result, TimelockError::DelayNotElapsed);
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert!(!asset_data.require_verified_recipient);
+    
+    warp_to_timestamp(&mut context, 4_600).await;
+    process(&mut context, execute(), &[]).await.unwrap();
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert!(asset_data.require_verified_recipient);
+    assert!(context.banks_client.get_account(queued_action).await.unwrap().is_none());
+}
+
+fn update_asset_metadata_instruction(
+    registry: Pubkey,
+    asset: &RWAAsset,
+    registry_authority: Option<Pubkey>,
+    new_kyc_level: KYCLevel,
+) -> Instruction {
+    instruction(
+        omniflow_rwa::accounts::UpdateAssetMetadata {
+            asset: RWAAsset::address(asset.asset_id),
+            registry,
+            metadata_revision: MetadataRevision::address(asset.asset_id, asset.metadata_version + 1),
+            registry_authority,
+            owner: asset.owner,
+            system_program: solana_program::system_program::ID,
+        },
+        omniflow_rwa::instruction::UpdateAssetMetadata {
+            asset_id: asset.asset_id,
+            new_metadata_uri: asset.metadata_uri.clone(),
+            content_hash: [1u8; 32],
+            new_kyc_level: Some(new_kyc_level),
+            reason: "KYC review".to_string(),
+        },
+    )
+}
+
+#[tokio::test]
+async fn test_asset_kyc_downgrade_gate() {
+    let mut program_test = program_test();
+    let authority = add_funded_signer(&mut program_test);
+    let owner = add_funded_signer(&mut program_test);
+    let registry = add_registry(&mut program_test, &registry_state(authority.pubkey()));
+    let mut unissued = asset_state(1, owner.pubkey());
+    unissued.kyc_level = KYCLevel::Institutional;
+    add_asset(&mut program_test, &unissued);
+    let mut issued = asset_state(2, owner.pubkey());
+    issued.kyc_level = KYCLevel::Institutional;
+    issued.circulating_supply = 100;
+    let issued_address = add_asset(&mut program_test, &issued);
+    let timelock_config = add_timelock(&mut program_test, [0, 3_600, 0]);
+    let mut context = program_test.start_with_context().await;
+    warp_to_timestamp(&mut context, 1_000).await;
+    
+    // With nothing issued the owner still needs the registry authority to lower the level
+    let downgrade = update_asset_metadata_instruction(registry, &unissued, None, KYCLevel::Basic);
+    let result = process(&mut context, downgrade, &[&owner]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::Unauthorized);
+    let downgrade =
+        update_asset_metadata_instruction(registry, &unissued, Some(authority.pubkey()), KYCLevel::Basic);
+    process(&mut context, downgrade, &[&owner, &authority]).await.unwrap();
+    let asset_data: RWAAsset = fetch(&mut context, RWAAsset::address(1)).await;
+    assert!(asset_data.kyc_level == KYCLevel::Basic);
+    assert_eq!(asset_data.metadata_version, 1);
+    
+    // Once tokens circulate only the timelock can lower it
+    let downgrade =
+        update_asset_metadata_instruction(registry, &issued, Some(authority.pubkey()), KYCLevel::Basic);
+    let result = process(&mut context, downgrade, &[&owner, &authority]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::KycDowngradeRequiresTimelock);
+    
+    let queued_action = pda(&[b"timelock_action", &0u64.to_le_bytes()]);
+    let queue = instruction(
+        omniflow_rwa::accounts::QueueTimelockAction {
+            timelock_config,
+            queued_action,
+            registry,
+            admin_role: None,
+            authority: authority.pubkey(),
+            system_program: solana_program::system_program::ID,
+        },
+        omniflow_rwa::instruction::QueueTimelockAction {
+            action: TimelockAction::SetAssetKycLevel {
+                asset_id: 2,
+                kyc_level: KYCLevel::Basic,
+            },
+        },
+    );
+    process(&mut context, queue, &[&authority]).await.unwrap();
+    warp_to_timestamp(&mut context, 4_600).await;
+    
+    let metadata_revision = MetadataRevision::address(2, 1);
+    let executor = context.payer.pubkey();
+    let execute = |metadata_revision: Option<Pubkey>| {
+        instruction(
+            omniflow_rwa::accounts::ExecuteTimelockAction {
+                queued_action,
+                timelock_config,
+                registry,
+                asset: Some(issued_address),
+                metadata_revision,
+                rent_receiver: authority.pubkey(),
+                executor,
+                system_program: solana_program::system_program::ID,
+            },
+            omniflow_rwa::instruction::ExecuteTimelockAction {},
+        )
+    };
+    let result = process(&mut context, execute(None), &[]).await;
+    assert_program_error(result, TimelockError::RevisionMissing);
+    process(&mut context, execute(Some(metadata_revision)), &[]).await.unwrap();
+    
+    let asset_data: RWAAsset = fetch(&mut context, issued_address).await;
+    assert!(asset_data.kyc_level == KYCLevel::Basic);
+    assert_eq!(asset_data.metadata_version, 1);
+    let revision: MetadataRevision = fetch(&mut context, metadata_revision).await;
+    assert_eq!(revision.version, 1);
+    assert!(revision.kyc_level == KYCLevel::Basic);
+    assert!(revision.previous_kyc_level == KYCLevel::Institutional);
+    assert_eq!(revision.updated_by, authority.pubkey());
+}
+
+#[tokio::test]
+async fn test_create_asset_mint_records_mint() {
+    let mut program_test = program_test();
+    let owner = add_funded_signer(&mut program_test);
+    add_registry(&mut program_test, &registry_state(owner.pubkey()));
+    let asset = add_asset(
+        &mut program_test,
+        &RWAAsset {
+            mint: Pubkey::default(),
+            ..asset_state(1, owner.pubkey())
+        },
+    );
+    // Same authorities as the real mint, but not the one the asset recorded
+    let foreign_mint = Pubkey::new_unique();
+    add_mint(&mut program_test, foreign_mint, asset, 0);
+    let mut context = program_test.start_with_context().await;
+    
+    let mint = pda(&[b"asset_mint", &1u64.to_le_bytes()]);
+    let create = instruction(
+        omniflow_rwa::accounts::CreateAssetMint {
+            asset,
+            mint,
+            owner: owner.pubkey(),
+            token_program: token::ID,
+            system_program: solana_program::system_program::ID,
+        },
+        omniflow_rwa::instruction::CreateAssetMint {
+            asset_id: 1,
+            decimals: 0,
+        },
+    );
+    process(&mut context, create, &[&owner]).await.unwrap();
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert_eq!(asset_data.mint, mint);
+    
+    let investor = Pubkey::new_unique();
+    let result = process(
+        &mut context,
+        mint_rwa_tokens_instruction(1, owner.pubkey(), foreign_mint, investor, None, 500),
+        &[&owner],
+    )
+    .await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::MintMismatch);
+    
+    process(
+        &mut context,
+        mint_rwa_tokens_instruction(1, owner.pubkey(), mint, investor, None, 500),
+        &[&owner],
+    )
+    .await
+    .unwrap();
+    let token_account = spl_associated_token_account::get_associated_token_address(&investor, &mint);
+    assert_eq!(token_balance(&mut context, token_account).await, 500);
+}
+
+async fn is_frozen(context: &mut ProgramTestContext, address: Pubkey) -> bool {
+    let account = context.banks_client.get_account(address).await.unwrap().unwrap();
+    spl_token::state::Account::unpack(&account.data).unwrap().is_frozen()
+}
+
+#[tokio::test]
+async fn test_transfer_agent_freeze_thaw_and_forced_transfer() {
+    let mut program_test = program_test();
+    let authority = add_funded_signer(&mut program_test);
+    let outsider = add_funded_signer(&mut program_test);
+    let owner = Pubkey::new_unique();
+    let registry = add_registry(&mut program_test, &registry_state(authority.pubkey()));
+    let asset = add_asset(
+        &mut program_test,
+        &RWAAsset {
+            circulating_supply: 300,
+            ..asset_state(1, owner)
+        },
+    );
+    let mint = add_asset_mint(&mut program_test, 1, 300);
+    let (holder, receiver) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let holder_account = add_token_account(&mut program_test, mint, holder, 300);
+    let receiver_account = add_token_account(&mut program_test, mint, receiver, 0);
+    
+    // Asset 2's books claim less circulating supply than its mint holds
+    add_asset(
+        &mut program_test,
+        &RWAAsset {
+            circulating_supply: 100,
+            ..asset_state(2, owner)
+        },
+    );
+    let understated_mint = add_asset_mint(&mut program_test, 2, 300);
+    let understated_holder = add_token_account(&mut program_test, understated_mint, holder, 300);
+    let understated_receiver = add_token_account(&mut program_test, understated_mint, receiver, 0);
+    
+    // Program-owned escrows for asset 1
+    let redemption_vault = pda(&[b"redemption_vault", &1u64.to_le_bytes()]);
+    add_token_account_at(&mut program_test, redemption_vault, mint, asset, 0);
+    let sale_escrow = pda(&[b"sale_escrow", PrimarySale::address(1).as_ref()]);
+    add_token_account_at(&mut program_test, sale_escrow, mint, PrimarySale::address(1), 0);
+    let transfer_agent_role = add_role(&mut program_test, Role::TransferAgent, authority.pubkey());
+    let mut context = program_test.start_with_context().await;
+    
+    let freeze = |signer: &Keypair, transfer_agent_role: Option<Pubkey>, holder_token_account: Pubkey| {
+        instruction(
+            omniflow_rwa::accounts::FreezeHolder {
+                asset,
+                registry,
+                transfer_agent_role,
+                mint,
+                holder_token_account,
+                authority: signer.pubkey(),
+                token_program: token::ID,
+            },
+            omniflow_rwa::instruction::FreezeHolder { asset_id: 1 },
+        )
+    };
+    let thaw = || {
+        instruction(
+            omniflow_rwa::accounts::ThawHolder {
+                asset,
+                registry,
+                transfer_agent_role: Some(transfer_agent_role),
+                mint,
+                holder_token_account: holder_account,
+                forced_transfer_record: pda(&[b"forced_transfer", holder_account.as_ref()]),
+                authority: authority.pubkey(),
+                token_program: token::ID,
+            },
+            omniflow_rwa::instruction::ThawHolder { asset_id: 1 },
+        )
+    };
+    let forced_transfer = |asset_id: u64, source: Pubkey, destination: Pubkey, legal_reference_hash: [u8; 32]| {
+        instruction(
+            omniflow_rwa::accounts::ForcedTransfer {
+                asset: RWAAsset::address(asset_id),
+                registry,
+                transfer_agent_role: Some(transfer_agent_role),
+                mint: pda(&[b"asset_mint", &asset_id.to_le_bytes()]),
+                source_token_account: source,
+                destination_token_account: destination,
+                forced_transfer_record: pda(&[b"forced_transfer", source.as_ref()]),
+                reserve: None,
+                authority: authority.pubkey(),
+                token_program: token::ID,
+                system_program: solana_program::system_program::ID,
+            },
+            omniflow_rwa::instruction::ForcedTransfer {
+                asset_id,
+                legal_reference_hash,
+            },
+        )
+    };
+    
+    let result = process(&mut context, freeze(&outsider, None, holder_account), &[&outsider]).await;
+    assert_program_error(result, RoleError::MissingRole);
+    // The registry authority only acts as transfer agent through an explicit grant
+    let result = process(&mut context, freeze(&authority, None, holder_account), &[&authority]).await;
+    assert_program_error(result, RoleError::MissingRole);
+    process(
+        &mut context,
+        freeze(&authority, Some(transfer_agent_role), holder_account),
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    assert!(is_frozen(&mut context, holder_account).await);
+    let result = process(
+        &mut context,
+        freeze(&authority, Some(transfer_agent_role), holder_account),
+        &[&authority],
+    )
+    .await;
+    assert_program_error(result, TransferAgentError::AlreadyFrozen);
+    
+    process(&mut context, thaw(), &[&authority]).await.unwrap();
+    assert!(!is_frozen(&mut context, holder_account).await);
+    let result = process(&mut context, thaw(), &[&authority]).await;
+    assert_program_error(result, TransferAgentError::NotFrozen);
+    
+    // Escrows held by the asset or its sale are neither frozen nor seized
+    for escrow in [redemption_vault, sale_escrow] {
+        let result = process(
+            &mut context,
+            freeze(&authority, Some(transfer_agent_role), escrow),
+            &[&authority],
+        )
+        .await;
+        assert_program_error(result, TransferAgentError::ProgramOwnedAccount);
+        let result = process(
+            &mut context,
+            forced_transfer(1, escrow, receiver_account, [9; 32]),
+            &[&authority],
+        )
+        .await;
+        assert_program_error(result, TransferAgentError::ProgramOwnedAccount);
+    }
+    
+    let result = process(
+        &mut context,
+        forced_transfer(1, holder_account, receiver_account, [0; 32]),
+        &[&authority],
+    )
+    .await;
+    assert_program_error(result, TransferAgentError::MissingLegalReference);
+    process(
+        &mut context,
+        forced_transfer(1, holder_account, receiver_account, [9; 32]),
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    assert_eq!(token_balance(&mut context, receiver_account).await, 300);
+    assert!(is_frozen(&mut context, holder_account).await);
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert_eq!(asset_data.circulating_supply, 300);
+    assert_eq!(asset_data.voided_supply, 300);
+    let record: ForcedTransferRecord =
+        fetch(&mut context, pda(&[b"forced_transfer", holder_account.as_ref()])).await;
+    assert_eq!(record.amount, 300);
+    assert_eq!(record.destination_token_account, receiver_account);
+    
+    // The reissued balance keeps the source frozen for good
+    let result = process(&mut context, thaw(), &[&authority]).await;
+    assert_program_error(result, TransferAgentError::BalanceReissued);
+    
+    // Reissuing on top of unaccounted supply would inflate holder balances
+    let result = process(
+        &mut context,
+        forced_transfer(2, understated_holder, understated_receiver, [9; 32]),
+        &[&authority],
+    )
+    .await;
+    assert_program_error(result, TransferAgentError::SupplyInvariantViolated);
+}
+
+fn add_chain(
+    program_test: &mut ProgramTest,
+    wormhole_chain_id: u16,
+    address_format: AddressFormat,
+    enabled: bool,
+) -> Pubkey {
+    let (address, bump) = Pubkey::find_program_address(
+        &[b"chain", &wormhole_chain_id.to_le_bytes()],
+        &omniflow_rwa::ID,
+    );
+    let chain_config = ChainConfig {
+        wormhole_chain_id,
+        name: format!("chain-{wormhole_chain_id}"),
+        evm_chain_id: None,
+        address_format,
+        enabled,
+        added_at: 0,
+        bump,
+    };
+    add_program_account(program_test, address, &chain_config, 8 + ChainConfig::INIT_SPACE);
+    address
+}
+
+#[tokio::test]
+async fn test_outbound_transfer_amount_and_cancellation() {
+    let mut program_test = program_test();
+    let authority = add_funded_signer(&mut program_test);
+    let sender = add_funded_signer(&mut program_test);
+    let registry = add_registry(
+        &mut program_test,
+        &Registry {
+            outbound_sequence: 1,
+            pending_outbound_transfers: 1,
+            ..registry_state(authority.pubkey())
+        },
+    );
+    let asset = add_asset(
+        &mut program_test,
+        &RWAAsset {
+            circulating_supply: 300,
+            ..asset_state(1, Pubkey::new_unique())
+        },
+    );
+    // 100 of the 300 issued tokens were burned by an outbound transfer that never arrived
+    let mint = add_asset_mint(&mut program_test, 1, 200);
+    let sender_token_account = add_token_account(&mut program_test, mint, sender.pubkey(), 200);
+    let target_chain_config = add_chain(&mut program_test, 2, AddressFormat::Evm, true);
+    let (stuck_transfer, bump) = Pubkey::find_program_address(
+        &[b"outbound_transfer", &0u64.to_le_bytes()],
+        &omniflow_rwa::ID,
+    );
+    let outbound_transfer = OutboundTransfer {
+        sequence: 0,
+        asset_id: 1,
+        amount: 100,
+        target_chain: 2,
+        recipient: [1u8; 32],
+        sender: sender.pubkey(),
+        initiated_at: 1_000,
+        bump,
+    };
+    add_program_account(
+        &mut program_test,
+        stuck_transfer,
+        &outbound_transfer,
+        8 + OutboundTransfer::INIT_SPACE,
+    );
+    let mut context = program_test.start_with_context().await;
+    warp_to_timestamp(&mut context, 1_000 + OUTBOUND_TRANSFER_TIMEOUT - 1).await;
+    
+    let mut recipient = [0u8; 32];
+    recipient[12..].copy_from_slice(&[7u8; 20]);
+    let initiate = instruction(
+        omniflow_rwa::accounts::InitiateCrossChainTransfer {
+            asset,
+            registry,
+            target_chain_config,
+            mint,
+            from_token_account: sender_token_account,
+            sender_passport: None,
+            outbound_transfer: pda(&[b"outbound_transfer", &1u64.to_le_bytes()]),
+            owner: sender.pubkey(),
+            token_program: token::ID,
+            system_program: solana_program::system_program::ID,
+        },
+        omniflow_rwa::instruction::InitiateCrossChainTransfer {
+            asset_id: 1,
+            amount: 0,
+            target_chain: 2,
+            target_recipient: recipient,
+        },
+    );
+    let result = process(&mut context, initiate, &[&sender]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::InvalidTransferAmount);
+    
+    let cancel = |signer: &Keypair| {
+        instruction(
+            omniflow_rwa::accounts::CancelOutboundTransfer {
+                outbound_transfer: stuck_transfer,
+                registry,
+                asset,
+                mint,
+                sender_token_account,
+                sender: sender.pubkey(),
+                admin_role: None,
+                authority: signer.pubkey(),
+                token_program: token::ID,
+            },
+            omniflow_rwa::instruction::CancelOutboundTransfer {},
+        )
+    };
+    let result = process(&mut context, cancel(&sender), &[&sender]).await;
+    assert_program_error(result, RoleError::MissingRole);
+    // Relayers still have time to deliver
+    let result = process(&mut context, cancel(&authority), &[&authority]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::OutboundTransferNotExpired);
+    
+    warp_to_timestamp(&mut context, 1_000 + OUTBOUND_TRANSFER_TIMEOUT).await;
+    process(&mut context, cancel(&authority), &[&authority]).await.unwrap();
+    assert_eq!(token_balance(&mut context, sender_token_account).await, 300);
+    assert!(context.banks_client.get_account(stuck_transfer).await.unwrap().is_none());
+    let registry_data: Registry = fetch(&mut context, registry).await;
+    assert_eq!(registry_data.pending_outbound_transfers, 0);
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert_eq!(asset_data.circulating_supply, 300);
+}
+
+fn register_rwa_asset_instruction(owner: Pubkey, issuer_role: Option<Pubkey>, asset_id: u64) -> Instruction {
+    instruction(
+        omniflow_rwa::accounts::RegisterRWAAsset {
+            asset: RWAAsset::address(asset_id),
+            registry: pda(&[b"registry"]),
+            issuer_role,
+            asset_index_page: pda(&[b"asset_index", &0u64.to_le_bytes()]),
+            asset_attributes: None,
+            owner,
+            system_program: solana_program::system_program::ID,
+        },
+        omniflow_rwa::instruction::RegisterRwaAsset {
+            asset_id,
+            asset_type: AssetType::RealEstate,
+            metadata_uri: format!("https://example.com/metadata/{asset_id}"),
+            kyc_level: KYCLevel::None,
+            total_value: 1_000_000,
+            total_supply: 100_000,
+            chain_id: 1,
+            attributes: None,
+        },
+    )
+}
+
+#[tokio::test]
+async fn test_asset_id_ranges() {
+    let mut program_test = program_test();
+    let authority = add_funded_signer(&mut program_test);
+    let registry = add_registry(&mut program_test, &registry_state(authority.pubkey()));
+    let issuer_role = add_role(&mut program_test, Role::Issuer, authority.pubkey());
+    let mut context = program_test.start_with_context().await;
+    let register = |asset_id: u64| register_rwa_asset_instruction(authority.pubkey(), Some(issuer_role), asset_id);
+    
+    // Ids below the caller range are only handed out in order
+    let result = process(&mut context, register(5), &[&authority]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::AssetIdNotAllocated);
+    let result = process(&mut context, register(CALLER_ASSET_ID_BASE - 1), &[&authority]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::AssetIdNotAllocated);
+    process(&mut context, register(1), &[&authority]).await.unwrap();
+    
+    // A caller id at the very top neither panics nor moves the counter
+    process(&mut context, register(u64::MAX), &[&authority]).await.unwrap();
+    let registry_data: Registry = fetch(&mut context, registry).await;
+    assert_eq!(registry_data.next_asset_id, 2);
+    assert_eq!(registry_data.total_assets, 2);
+    
+    let enable = instruction(
+        omniflow_rwa::accounts::SetAssetIdAllocation {
+            registry,
+            multisig: pda(&[b"multisig"]),
+            multisig_proposal: None,
+            authority: authority.pubkey(),
+        },
+        omniflow_rwa::instruction::SetAssetIdAllocation {
+            allocate_asset_ids: true,
+        },
+    );
+    process(&mut context, enable, &[&authority]).await.unwrap();
+    let result = process(&mut context, register(CALLER_ASSET_ID_BASE), &[&authority]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::AssetIdNotAllocated);
+    process(&mut context, register(2), &[&authority]).await.unwrap();
+    let registry_data: Registry = fetch(&mut context, registry).await;
+    assert_eq!(registry_data.next_asset_id, 3);
+}
+
+fn add_role(program_test: &mut ProgramTest, role: Role, member: Pubkey) -> Pubkey {
+    let (address, bump) =
+        Pubkey::find_program_address(&[b"role", &[role as u8], member.as_ref()], &omniflow_rwa::ID);
+    let membership = RoleMembership {
+        role,
+        member,
+        granted_by: Pubkey::new_unique(),
+        granted_at: 0,
+        bump,
+    };
+    add_program_account(program_test, address, &membership, 8 + RoleMembership::INIT_SPACE);
+    address
+}
+
+#[tokio::test]
+async fn test_asset_pause_levels() {
+    let mut program_test = program_test();
+    let authority = Pubkey::new_unique();
+    let owner = add_funded_signer(&mut program_test);
+    let guardian = add_funded_signer(&mut program_test);
+    let pauser = add_funded_signer(&mut program_test);
+    let outsider = add_funded_signer(&mut program_test);
+    let registry = add_registry(
+        &mut program_test,
+        &Registry {
+            guardian: Some(guardian.pubkey()),
+            ..registry_state(authority)
+        },
+    );
+    let asset = add_asset(&mut program_test, &asset_state(1, owner.pubkey()));
+    let pauser_role = add_role(&mut program_test, Role::Pauser, pauser.pubkey());
+    let mut context = program_test.start_with_context().await;
+    
+    let set_pause = |signer: &Keypair, pauser_role: Option<Pubkey>, paused: bool| {
+        instruction(
+            omniflow_rwa::accounts::SetAssetPause {
+                asset,
+                registry,
+                pauser_role,
+                authority: signer.pubkey(),
+            },
+            omniflow_rwa::instruction::SetAssetPause { asset_id: 1, paused },
+        )
+    };
+    
+    let result = process(&mut context, set_pause(&outsider, None, true), &[&outsider]).await;
+    assert_program_error(result, RoleError::MissingRole);
+    
+    // The owner lifts its own pause
+    process(&mut context, set_pause(&owner, None, true), &[&owner]).await.unwrap();
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert!(asset_data.paused);
+    assert!(!asset_data.paused_by_registry);
+    process(&mut context, set_pause(&owner, None, false), &[&owner]).await.unwrap();
+    
+    // but not one imposed by the guardian, which cannot lift it either
+    process(&mut context, set_pause(&guardian, None, true), &[&guardian]).await.unwrap();
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert!(asset_data.paused_by_registry);
+    let result = process(&mut context, set_pause(&owner, None, false), &[&owner]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::AssetPausedByRegistry);
+    let result = process(&mut context, set_pause(&guardian, None, false), &[&guardian]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::AssetPausedByRegistry);
+    
+    // An owner pause on top keeps the registry pause in force
+    process(&mut context, set_pause(&owner, None, true), &[&owner]).await.unwrap();
+    let result = process(&mut context, set_pause(&owner, None, false), &[&owner]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::AssetPausedByRegistry);
+    
+    process(&mut context, set_pause(&pauser, Some(pauser_role), false), &[&pauser])
+        .await
+        .unwrap();
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert!(!asset_data.paused);
+    assert!(!asset_data.paused_by_registry);
+}
+
+#[tokio::test]
+async fn test_registry_pause_blocks_holder_operations() {
+    let mut program_test = program_test();
+    let authority = add_funded_signer(&mut program_test);
+    let owner = add_funded_signer(&mut program_test);
+    let holder = add_funded_signer(&mut program_test);
+    let registry = add_registry(
+        &mut program_test,
+        &Registry {
+            pause_flags: PAUSE_REDEMPTIONS | PAUSE_METADATA_UPDATES | PAUSE_FORCED_TRANSFERS,
+            ..registry_state(authority.pubkey())
+        },
+    );
+    let asset = add_asset(
+        &mut program_test,
+        &RWAAsset {
+            circulating_supply: 100,
+            ..asset_state(1, owner.pubkey())
+        },
+    );
+    let mint = add_asset_mint(&mut program_test, 1, 100);
+    let holder_token_account = add_token_account(&mut program_test, mint, holder.pubkey(), 100);
+    let receiver_token_account = add_token_account(&mut program_test, mint, Pubkey::new_unique(), 0);
+    let transfer_agent_role = add_role(&mut program_test, Role::TransferAgent, authority.pubkey());
+    let mut context = program_test.start_with_context().await;
+    
+    let request_redemption = instruction(
+        omniflow_rwa::accounts::RequestRedemption {
+            asset,
+            registry,
+            redemption_request: pda(&[b"redemption", &1u64.to_le_bytes(), &0u64.to_le_bytes()]),
+            mint,
+            redemption_vault: pda(&[b"redemption_vault", &1u64.to_le_bytes()]),
+            holder_token_account,
+            holder: holder.pubkey(),
+            token_program: token::ID,
+            system_program: solana_program::system_program::ID,
+        },
+        omniflow_rwa::instruction::RequestRedemption { asset_id: 1, amount: 10 },
+    );
+    let result = process(&mut context, request_redemption, &[&holder]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::RegistryPaused);
+    
+    let update_attributes = instruction(
+        omniflow_rwa::accounts::UpdateAssetAttributes {
+            asset,
+            registry,
+            asset_attributes: pda(&[b"asset_attributes", &1u64.to_le_bytes()]),
+            owner: owner.pubkey(),
+            system_program: solana_program::system_program::ID,
+        },
+        omniflow_rwa::instruction::UpdateAssetAttributes {
+            asset_id: 1,
+            attributes: AssetAttributeData::RealEstate {
+                parcel_id: "APN-001".to_string(),
+                square_footage: 1_200,
+                rental_yield_bps: 450,
+            },
+        },
+    );
+    let result = process(&mut context, update_attributes, &[&owner]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::RegistryPaused);
+    
+    let forced_transfer = instruction(
+        omniflow_rwa::accounts::ForcedTransfer {
+            asset,
+            registry,
+            transfer_agent_role: Some(transfer_agent_role),
+            mint,
+            source_token_account: holder_token_account,
+            destination_token_account: receiver_token_account,
+            forced_transfer_record: pda(&[b"forced_transfer", holder_token_account.as_ref()]),
+            reserve: None,
+            authority: authority.pubkey(),
+            token_program: token::ID,
+            system_program: solana_program::system_program::ID,
+        },
+        omniflow_rwa::instruction::ForcedTransfer {
+            asset_id: 1,
+            legal_reference_hash: [9; 32],
+        },
+    );
+    let result = process(&mut context, forced_transfer, &[&authority]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::RegistryPaused);
+}
+
+/// Adds a Pyth v2 price account owned by `feed_program` and returns its address
+fn add_price_account(program_test: &mut ProgramTest, feed_program: Pubkey, price: i64, publish_time: i64) -> Pubkey {
+    let address = Pubkey::new_unique();
+    let data = price_feed::mock_price_account_data(price, 0, -5, publish_time);
+    program_test.add_account(
+        address,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: feed_program,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    address
+}
+
+#[tokio::test]
+async fn test_price_feed_pinned_to_oracle_and_used_by_sales_and_reserves() {
+    let mut program_test = program_test();
+    let authority = add_funded_signer(&mut program_test);
+    let custodian = add_funded_signer(&mut program_test);
+    let buyer = add_funded_signer(&mut program_test);
+    let registry = add_registry(&mut program_test, &registry_state(authority.pubkey()));
+    let asset = add_asset(
+        &mut program_test,
+        &RWAAsset {
+            asset_type: AssetType::PreciousMetals,
+            sale_open: true,
+            ..asset_state(1, Pubkey::new_unique())
+        },
+    );
+    let mint = add_asset_mint(&mut program_test, 1, 0);
+    let reserve = ReserveAttestation::address(1);
+    add_program_account(
+        &mut program_test,
+        reserve,
+        &ReserveAttestation {
+            asset_id: 1,
+            custodian: custodian.pubkey(),
+            max_staleness: 3_600,
+            tokens_per_unit: 1_000,
+            quantity: 0,
+            audit_report_hash: [0; 32],
+            auditor: Pubkey::default(),
+            attested_at: 0,
+            attestation_count: 0,
+            bump: Pubkey::find_program_address(&[b"reserve", &1u64.to_le_bytes()], &omniflow_rwa::ID).1,
+        },
+        8 + ReserveAttestation::INIT_SPACE,
+    );
+    let quote_mint = Pubkey::new_unique();
+    add_mint(&mut program_test, quote_mint, Pubkey::new_unique(), 0);
+    let sale = add_sale(&mut program_test, &sale_state(1, quote_mint), mint, 0);
+    let buyer_quote = add_token_account(&mut program_test, quote_mint, buyer.pubkey(), 100_000);
+    
+    // Gold at $2,345.67, once from Pyth and once from an account anyone could have written
+    let spoofed = add_price_account(&mut program_test, Pubkey::new_unique(), 234_567_000, 10_000);
+    let price_account = add_price_account(&mut program_test, PYTH_ORACLE_PROGRAM_ID, 234_567_000, 10_000);
+    let mut context = program_test.start_with_context().await;
+    warp_to_timestamp(&mut context, 10_000).await;
+    
+    let price_feed = pda(&[b"price_feed", &1u64.to_le_bytes()]);
+    let configure = |price_account: Pubkey| {
+        instruction(
+            omniflow_rwa::accounts::ConfigurePriceFeed {
+                asset,
+                price_feed,
+                price_account,
+                registry,
+                admin_role: None,
+                authority: authority.pubkey(),
+                system_program: solana_program::system_program::ID,
+            },
+            omniflow_rwa::instruction::ConfigurePriceFeed {
+                asset_id: 1,
+                max_staleness: 60,
+                max_confidence_bps: 100,
+                custody_quantity: 0,
+                quantity_decimals: 3,
+                value_decimals: 2,
+            },
+        )
+    };
+    let result = process(&mut context, configure(spoofed), &[&authority]).await;
+    assert_program_error(result, PriceFeedError::UntrustedFeedProgram);
+    
+    process(&mut context, configure(price_account), &[&authority]).await.unwrap();
+    let feed: PriceFeed = fetch(&mut context, price_feed).await;
+    assert_eq!(feed.feed_program, PYTH_ORACLE_PROGRAM_ID);
+    
+    let attest = |price_feed: Option<Pubkey>, price_account: Option<Pubkey>| {
+        instruction(
+            omniflow_rwa::accounts::PostReserveAttestation {
+                asset,
+                reserve,
+                price_feed,
+                price_account,
+                custodian: custodian.pubkey(),
+            },
+            omniflow_rwa::instruction::PostReserveAttestation {
+                quantity: 400,
+                audit_report_hash: [7; 32],
+                auditor: Pubkey::new_unique(),
+            },
+        )
+    };
+    let result = process(&mut context, attest(None, None), &[&custodian]).await;
+    assert_program_error(result, PriceFeedError::PriceFeedMissing);
+    
+    // 400 attested ounces become the custody quantity and the asset is revalued at them
+    process(&mut context, attest(Some(price_feed), Some(price_account)), &[&custodian])
+        .await
+        .unwrap();
+    let feed: PriceFeed = fetch(&mut context, price_feed).await;
+    assert_eq!(feed.custody_quantity, 400_000);
+    assert_eq!(feed.last_value, 93_826_800);
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert_eq!(asset_data.total_value, 93_826_800);
+    
+    let buy = instruction(
+        omniflow_rwa::accounts::BuyPrimarySale {
+            asset,
+            registry,
+            sale,
+            purchase: pda(&[b"sale_purchase", sale.as_ref(), buyer.pubkey().as_ref()]),
+            treasury: pda(&[b"sale_treasury", sale.as_ref()]),
+            mint,
+            escrow: pda(&[b"sale_escrow", sale.as_ref()]),
+            buyer_quote_account: buyer_quote,
+            buyer_passport: None,
+            valuation: None,
+            reserve: None,
+            price_feed: Some(price_feed),
+            price_account: Some(price_account),
+            buyer: buyer.pubkey(),
+            token_program: token::ID,
+            system_program: solana_program::system_program::ID,
+        },
+        omniflow_rwa::instruction::BuyPrimarySale { amount: 100 },
+    );
+    process(&mut context, buy.clone(), &[&buyer]).await.unwrap();
+    
+    // Sales stop once the price is older than the feed allows
+    warp_to_timestamp(&mut context, 10_061).await;
+    let result = process(&mut context, buy, &[&buyer]).await;
+    assert_program_error(result, PriceFeedError::StalePrice);
+}
+
+#[tokio::test]
+async fn test_valuation_review_stays_open_until_confirmed() {
+    let mut program_test = program_test();
+    let admin = add_funded_signer(&mut program_test);
+    let oracle = add_funded_signer(&mut program_test);
+    let registry = add_registry(&mut program_test, &registry_state(admin.pubkey()));
+    let oracle_role = add_role(&mut program_test, Role::ValuationOracle, oracle.pubkey());
+    let asset = add_asset(
+        &mut program_test,
+        &RWAAsset {
+            valuation_required: true,
+            circulating_supply: 50_000,
+            ..asset_state(1, Pubkey::new_unique())
+        },
+    );
+    let valuation = pda(&[b"valuation", &1u64.to_le_bytes()]);
+    add_program_account(
+        &mut program_test,
+        valuation,
+        &ValuationHistory {
+            asset_id: 1,
+            currency: *b"USD",
+            max_staleness: 86_400,
+            max_deviation_bps: 500,
+            entries: Vec::new(),
+            next_slot: 0,
+            pending_review: false,
+            bump: Pubkey::find_program_address(&[b"valuation", &1u64.to_le_bytes()], &omniflow_rwa::ID).1,
+        },
+        8 + ValuationHistory::INIT_SPACE,
+    );
+    let mut context = program_test.start_with_context().await;
+    warp_to_timestamp(&mut context, 10_000).await;
+    
+    let submit = |value: u64, effective_date: i64| {
+        instruction(
+            omniflow_rwa::accounts::SubmitValuation {
+                asset,
+                valuation,
+                registry,
+                oracle_role: Some(oracle_role),
+                oracle: oracle.pubkey(),
+            },
+            omniflow_rwa::instruction::SubmitValuation {
+                value,
+                currency: *b"USD",
+                effective_date,
+                methodology_hash: [3; 32],
+            },
+        )
+    };
+    
+    // Doubling the value needs review
+    process(&mut context, submit(2_000_000, 1_000), &[&oracle]).await.unwrap();
+    let history: ValuationHistory = fetch(&mut context, valuation).await;
+    assert!(history.pending_review);
+    
+    // A later valuation within the threshold does not close the review
+    process(&mut context, submit(1_010_000, 2_000), &[&oracle]).await.unwrap();
+    let history: ValuationHistory = fetch(&mut context, valuation).await;
+    assert!(history.pending_review);
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert_eq!(asset_data.total_value, 1_000_000);
+    
+    let confirm = instruction(
+        omniflow_rwa::accounts::ConfirmValuation {
+            asset,
+            valuation,
+            registry,
+            admin_role: None,
+            authority: admin.pubkey(),
+        },
+        omniflow_rwa::instruction::ConfirmValuation {},
+    );
+    process(&mut context, confirm.clone(), &[&admin]).await.unwrap();
+    let history: ValuationHistory = fetch(&mut context, valuation).await;
+    assert!(!history.pending_review);
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert_eq!(asset_data.total_value, 1_010_000);
+    let result = process(&mut context, confirm, &[&admin]).await;
+    assert_program_error(result, ValuationError::NoPendingReview);
+    
+    process(&mut context, submit(1_020_000, 3_000), &[&oracle]).await.unwrap();
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert_eq!(asset_data.total_value, 1_020_000);
+    
+    // NAV is spread over the 50,000 issued tokens, not the 100,000 authorized
+    let data = context.banks_client.get_account(asset).await.unwrap().unwrap().data;
+    let fetcher = |address: &Pubkey| (*address == asset).then(|| data.clone());
+    assert_eq!(omniflow_rwa::client::nav_per_token(&fetcher, 1, 0), Some(20));
+}
+
+#[tokio::test]
+async fn test_snapshot_supply_and_leaf_totals() {
+    let mut program_test = program_test();
+    let owner = add_funded_signer(&mut program_test);
+    let alice = add_funded_signer(&mut program_test);
+    let bob = add_funded_signer(&mut program_test);
+    
+    // 1,000 issued, of which 200 were bridged out and burned; 100 more were voided by a
+    // forced transfer and reissued, and 50 sit in the redemption vault
+    let asset = add_asset(
+        &mut program_test,
+        &RWAAsset {
+            circulating_supply: 1_000,
+            voided_supply: 100,
+            ..asset_state(1, owner.pubkey())
+        },
+    );
+    let mint = add_asset_mint(&mut program_test, 1, 900);
+    add_token_account_at(
+        &mut program_test,
+        pda(&[b"redemption_vault", &1u64.to_le_bytes()]),
+        mint,
+        asset,
+        50,
+    );
+    let mut context = program_test.start_with_context().await;
+    
+    let snapshot = BalanceSnapshot::address(1, 1);
+    let take_snapshot = instruction(
+        omniflow_rwa::accounts::TakeSnapshot {
+            asset,
+            snapshot,
+            mint,
+            redemption_vault: pda(&[b"redemption_vault", &1u64.to_le_bytes()]),
+            primary_sale: pda(&[b"primary_sale", &1u64.to_le_bytes()]),
+            sale_escrow: pda(&[b"sale_escrow", pda(&[b"primary_sale", &1u64.to_le_bytes()]).as_ref()]),
+            owner: owner.pubkey(),
+            system_program: solana_program::system_program::ID,
+        },
+        omniflow_rwa::instruction::TakeSnapshot { asset_id: 1 },
+    );
+    process(&mut context, take_snapshot, &[&owner]).await.unwrap();
+    let snapshot_data: BalanceSnapshot = fetch(&mut context, snapshot).await;
+    assert_eq!(snapshot_data.supply, 750);
+    
+    let root = merkle::hash_pair(
+        &merkle::balance_leaf(&alice.pubkey(), 500),
+        &merkle::balance_leaf(&bob.pubkey(), 250),
+    );
+    let commit = |leaf_total: u64| {
+        instruction(
+            omniflow_rwa::accounts::CommitSnapshotRoot {
+                asset,
+                snapshot,
+                snapshot_authority: owner.pubkey(),
+            },
+            omniflow_rwa::instruction::CommitSnapshotRoot {
+                balances_root: root,
+                holder_count: 2,
+                leaf_total,
+            },
+        )
+    };
+    let result = process(&mut context, commit(1_000), &[&owner]).await;
+    assert_program_error(result, SnapshotError::LeafTotalMismatch);
+    process(&mut context, commit(750), &[&owner]).await.unwrap();
+    let snapshot_data: BalanceSnapshot = fetch(&mut context, snapshot).await;
+    assert_eq!(snapshot_data.balances_root, Some(root));
+}
+
+#[tokio::test]
+async fn test_distribution_claims_capped_at_snapshot_supply() {
+    let mut program_test = program_test();
+    let alice = add_funded_signer(&mut program_test);
+    let bob = add_funded_signer(&mut program_test);
+    let alice_leaf = merkle::balance_leaf(&alice.pubkey(), 500);
+    let bob_leaf = merkle::balance_leaf(&bob.pubkey(), 400);
+    
+    let payout_mint = Pubkey::new_unique();
+    add_mint(&mut program_test, payout_mint, Pubkey::new_unique(), 7_500);
+    
+    // A root whose leaves sum to 900 against a supply of 750
+    let (distribution, bump) = Pubkey::find_program_address(
+        &[b"distribution", &1u64.to_le_bytes(), &0u64.to_le_bytes()],
+        &omniflow_rwa::ID,
+    );
+    add_program_account(
+        &mut program_test,
+        distribution,
+        &Distribution {
+            asset_id: 1,
+            distribution_id: 0,
+            payout_mint,
+            snapshot_id: 1,
+            balances_root: merkle::hash_pair(&alice_leaf, &bob_leaf),
+            snapshot_supply: 750,
+            total_amount: 7_500,
+            claimed_amount: 0,
+            claimed_balance: 0,
+            created_at: 0,
+            claim_deadline: i64::MAX,
+            swept: false,
+            bump,
+        },
+        8 + Distribution::INIT_SPACE,
+    );
+    let distribution_vault = pda(&[b"distribution_vault", distribution.as_ref()]);
+    add_token_account_at(&mut program_test, distribution_vault, payout_mint, distribution, 7_500);
+    let alice_payout = add_token_account(&mut program_test, payout_mint, alice.pubkey(), 0);
+    let bob_payout = add_token_account(&mut program_test, payout_mint, bob.pubkey(), 0);
+    let mut context = program_test.start_with_context().await;
+    
+    let claim = |holder: &Keypair, holder_payout_account: Pubkey, balance: u64, proof: [u8; 32]| {
+        instruction(
+            omniflow_rwa::accounts::ClaimDistribution {
+                distribution,
+                claim: pda(&[b"distribution_claim", distribution.as_ref(), holder.pubkey().as_ref()]),
+                distribution_vault,
+                holder_payout_account,
+                holder: holder.pubkey(),
+                token_program: token::ID,
+                system_program: solana_program::system_program::ID,
+            },
+            omniflow_rwa::instruction::ClaimDistribution {
+                balance,
+                proof: vec![proof],
+            },
+        )
+    };
+    process(&mut context, claim(&alice, alice_payout, 500, bob_leaf), &[&alice])
+        .await
+        .unwrap();
+    assert_eq!(token_balance(&mut context, alice_payout).await, 5_000);
+    
+    let result = process(&mut context, claim(&bob, bob_payout, 400, alice_leaf), &[&bob]).await;
+    assert_program_error(result, DistributionError::BalanceExceedsSupply);
+}
+
+#[tokio::test]
+async fn test_retire_carbon_credits() {
+    let mut program_test = program_test();
+    let holder = add_funded_signer(&mut program_test);
+    add_registry(&mut program_test, &registry_state(Pubkey::new_unique()));
+    let asset = add_asset(
+        &mut program_test,
+        &RWAAsset {
+            asset_type: AssetType::CarbonCredits,
+            total_supply: 10_000,
+            circulating_supply: 1_000,
+            ..asset_state(1, Pubkey::new_unique())
+        },
+    );
+    let (asset_attributes, bump) =
+        Pubkey::find_program_address(&[b"asset_attributes", &1u64.to_le_bytes()], &omniflow_rwa::ID);
+    add_program_account(
+        &mut program_test,
+        asset_attributes,
+        &AssetAttributes {
+            asset_id: 1,
+            attributes: AssetAttributeData::CarbonCredits {
+                registry: CarbonRegistry::Verra,
+                vintage: 2021,
+                project_id: "VCS-1234".to_string(),
+                tonnes: 500,
+            },
+            updated_at: 0,
+            bump,
+        },
+        8 + AssetAttributes::INIT_SPACE,
+    );
+    let mint = add_asset_mint(&mut program_test, 1, 1_000);
+    let holder_token_account = add_token_account(&mut program_test, mint, holder.pubkey(), 1_000);
+    let mut context = program_test.start_with_context().await;
+    
+    let retire = |amount: u64| {
+        instruction(
+            omniflow_rwa::accounts::RetireCarbonCredits {
+                asset,
+                registry: pda(&[b"registry"]),
+                asset_attributes,
+                certificate: pda(&[b"retirement", &1u64.to_le_bytes(), &0u64.to_le_bytes()]),
+                mint,
+                holder_token_account,
+                holder: holder.pubkey(),
+                token_program: token::ID,
+                system_program: solana_program::system_program::ID,
+            },
+            omniflow_rwa::instruction::RetireCarbonCredits {
+                asset_id: 1,
+                amount,
+                beneficiary_name: "Acme Corp".to_string(),
+                reason: "2024 scope 1 offset".to_string(),
+            },
+        )
+    };
+    let result = process(&mut context, retire(0), &[&holder]).await;
+    assert_program_error(result, CarbonError::InvalidRetirementAmount);
+    
+    // 10 of 10,000 tokens back 0.5 of 500 tonnes
+    let result = process(&mut context, retire(10), &[&holder]).await;
+    assert_program_error(result, CarbonError::RetirementTooSmall);
+    
+    process(&mut context, retire(400), &[&holder]).await.unwrap();
+    let certificate: RetirementCertificate =
+        fetch(&mut context, pda(&[b"retirement", &1u64.to_le_bytes(), &0u64.to_le_bytes()])).await;
+    assert_eq!(certificate.tonnes, 20);
+    assert_eq!(token_balance(&mut context, holder_token_account).await, 600);
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert_eq!(asset_data.circulating_supply, 600);
+    assert_eq!(asset_data.total_supply, 9_600);
+}
+
+#[tokio::test]
+async fn test_supported_chain_lifecycle() {
+    let mut program_test = program_test();
+    let authority = add_funded_signer(&mut program_test);
+    let admin = add_funded_signer(&mut program_test);
+    let outsider = add_funded_signer(&mut program_test);
+    let registry = add_registry(&mut program_test, &registry_state(authority.pubkey()));
+    let admin_role = add_role(&mut program_test, Role::Admin, admin.pubkey());
+    let mut context = program_test.start_with_context().await;
+    
+    let chain_config = pda(&[b"chain", &2u16.to_le_bytes()]);
+    let add = |signer: &Keypair, evm_chain_id: Option<u64>, address_format: AddressFormat| {
+        instruction(
+            omniflow_rwa::accounts::AddSupportedChain {
+                chain_config,
+                registry,
+                admin_role: None,
+                multisig: pda(&[b"multisig"]),
+                multisig_proposal: None,
+                authority: signer.pubkey(),
+                system_program: solana_program::system_program::ID,
+            },
+            omniflow_rwa::instruction::AddSupportedChain {
+                wormhole_chain_id: 2,
+                name: "Ethereum".to_string(),
+                evm_chain_id,
+                address_format,
+            },
+        )
+    };
+    let result = process(&mut context, add(&outsider, Some(1), AddressFormat::Evm), &[&outsider]).await;
+    assert_program_error(result, RoleError::MissingRole);
+    let result = process(&mut context, add(&authority, Some(1), AddressFormat::Solana), &[&authority]).await;
+    assert_program_error(result, ChainError::InvalidEvmChainId);
+    
+    process(&mut context, add(&authority, Some(1), AddressFormat::Evm), &[&authority])
+        .await
+        .unwrap();
+    let chain: ChainConfig = fetch(&mut context, chain_config).await;
+    assert_eq!(chain.name, "Ethereum");
+    assert_eq!(chain.evm_chain_id, Some(1));
+    assert!(chain.enabled);
+    
+    // A chain is only added once
+    assert!(process(&mut context, add(&authority, Some(1), AddressFormat::Evm), &[&authority])
+        .await
+        .is_err());
+    
+    let disable = instruction(
+        omniflow_rwa::accounts::DisableSupportedChain {
+            chain_config,
+            registry,
+            admin_role: Some(admin_role),
+            multisig: pda(&[b"multisig"]),
+            multisig_proposal: None,
+            authority: admin.pubkey(),
+        },
+        omniflow_rwa::instruction::DisableSupportedChain {},
+    );
+    process(&mut context, disable.clone(), &[&admin]).await.unwrap();
+    let chain: ChainConfig = fetch(&mut context, chain_config).await;
+    assert!(!chain.enabled);
+    let result = process(&mut context, disable, &[&admin]).await;
+    assert_program_error(result, ChainError::ChainDisabled);
+    
+    let enable = |signer: &Keypair, admin_role: Option<Pubkey>| {
+        instruction(
+            omniflow_rwa::accounts::EnableSupportedChain {
+                chain_config,
+                registry,
+                admin_role,
+                multisig: pda(&[b"multisig"]),
+                multisig_proposal: None,
+                authority: signer.pubkey(),
+            },
+            omniflow_rwa::instruction::EnableSupportedChain {},
+        )
+    };
+    let result = process(&mut context, enable(&outsider, None), &[&outsider]).await;
+    assert_program_error(result, RoleError::MissingRole);
+    process(&mut context, enable(&admin, Some(admin_role)), &[&admin]).await.unwrap();
+    let chain: ChainConfig = fetch(&mut context, chain_config).await;
+    assert!(chain.enabled);
+    let result = process(&mut context, enable(&admin, Some(admin_role)), &[&admin]).await;
+    assert_program_error(result, ChainError::ChainAlreadyEnabled);
+}
+
+fn add_multisig(program_test: &mut ProgramTest, signers: Vec<Pubkey>, threshold: u8) -> Pubkey {
+    let (address, bump) = Pubkey::find_program_address(&[b"multisig"], &omniflow_rwa::ID);
+    let multisig = Multisig {
+        signers,
+        threshold,
+        proposal_lifetime: 3_600,
+        proposal_count: 0,
+        generation: 0,
+        bump,
+    };
+    add_program_account(program_test, address, &multisig, 8 + Multisig::INIT_SPACE);
+    address
+}
+
+fn propose_instruction(multisig: Pubkey, proposal_id: u64, proposer: Pubkey, action: PrivilegedAction) -> Instruction {
+    instruction(
+        omniflow_rwa::accounts::ProposePrivilegedAction {
+            multisig,
+            proposal: pda(&[b"multisig_proposal", &proposal_id.to_le_bytes()]),
+            proposer,
+            system_program: solana_program::system_program::ID,
+        },
+        omniflow_rwa::instruction::ProposePrivilegedAction { action },
+    )
+}
+
+fn approve_instruction(multisig: Pubkey, proposal_id: u64, signer: Pubkey) -> Instruction {
+    instruction(
+        omniflow_rwa::accounts::ApprovePrivilegedAction {
+            multisig,
+            proposal: pda(&[b"multisig_proposal", &proposal_id.to_le_bytes()]),
+            signer,
+        },
+        omniflow_rwa::instruction::ApprovePrivilegedAction {},
+    )
+}
+
+fn execute_instruction(multisig: Pubkey, proposal_id: u64) -> Instruction {
+    instruction(
+        omniflow_rwa::accounts::ExecutePrivilegedAction {
+            multisig,
+            proposal: pda(&[b"multisig_proposal", &proposal_id.to_le_bytes()]),
+        },
+        omniflow_rwa::instruction::ExecutePrivilegedAction {},
+    )
+}
+
+#[tokio::test]
+async fn test_multisig_proposal_lifecycle() {
+    let mut program_test = program_test();
+    let authority = add_funded_signer(&mut program_test);
+    let guardian = add_funded_signer(&mut program_test);
+    let pauser = add_funded_signer(&mut program_test);
+    let outsider = add_funded_signer(&mut program_test);
+    let signers = [
+        add_funded_signer(&mut program_test),
+        add_funded_signer(&mut program_test),
+        add_funded_signer(&mut program_test),
+    ];
+    let registry = add_registry(
+        &mut program_test,
+        &Registry {
+            guardian: Some(guardian.pubkey()),
+            ..registry_state(authority.pubkey())
+        },
+    );
+    let pauser_role = add_role(&mut program_test, Role::Pauser, pauser.pubkey());
+    let chain_config = add_chain(&mut program_test, 2, AddressFormat::Evm, true);
+    let multisig = add_multisig(&mut program_test, signers.iter().map(|s| s.pubkey()).collect(), 2);
+    let mut context = program_test.start_with_context().await;
+    warp_to_timestamp(&mut context, 1_000).await;
+    
+    let chain_change = |signer: &Keypair, proposal_id: Option<u64>, enable: bool| {
+        let multisig_proposal = proposal_id.map(|id| pda(&[b"multisig_proposal", &id.to_le_bytes()]));
+        if enable {
+            instruction(
+                omniflow_rwa::accounts::EnableSupportedChain {
+                    chain_config,
+                    registry,
+                    admin_role: None,
+                    multisig,
+                    multisig_proposal,
+                    authority: signer.pubkey(),
+                },
+                omniflow_rwa::instruction::EnableSupportedChain {},
+            )
+        } else {
+            instruction(
+                omniflow_rwa::accounts::DisableSupportedChain {
+                    chain_config,
+                    registry,
+                    admin_role: None,
+                    multisig,
+                    multisig_proposal,
+                    authority: signer.pubkey(),
+                },
+                omniflow_rwa::instruction::DisableSupportedChain {},
+            )
+        }
+    };
+    let pause = |signer: &Keypair, pauser_role: Option<Pubkey>| {
+        instruction(
+            omniflow_rwa::accounts::SetRegistryPause {
+                registry,
+                pauser_role,
+                multisig,
+                multisig_proposal: None,
+                authority: signer.pubkey(),
+            },
+            omniflow_rwa::instruction::SetPauseFlags { pause_flags: PAUSE_MINTING },
+        )
+    };
+    
+    // Once the multisig exists, neither the authority nor a Pauser acts alone
+    let result = process(&mut context, chain_change(&authority, None, false), &[&authority]).await;
+    assert_program_error(result, MultisigError::MultisigRequired);
+    let result = process(&mut context, pause(&pauser, Some(pauser_role)), &[&pauser]).await;
+    assert_program_error(result, MultisigError::MultisigRequired);
+    // but the guardian can still pause in an emergency
+    process(&mut context, pause(&guardian, None), &[&guardian]).await.unwrap();
+    
+    let disable = PrivilegedAction::DisableSupportedChain { wormhole_chain_id: 2 };
+    let result = process(
+        &mut context,
+        propose_instruction(multisig, 0, outsider.pubkey(), disable.clone()),
+        &[&outsider],
+    )
+    .await;
+    assert_program_error(result, MultisigError::NotASigner);
+    process(
+        &mut context,
+        propose_instruction(multisig, 0, signers[0].pubkey(), disable),
+        &[&signers[0]],
+    )
+    .await
+    .unwrap();
+    
+    let result = process(&mut context, execute_instruction(multisig, 0), &[]).await;
+    assert_program_error(result, MultisigError::ThresholdNotMet);
+    let result = process(&mut context, approve_instruction(multisig, 0, signers[0].pubkey()), &[&signers[0]]).await;
+    assert_program_error(result, MultisigError::AlreadyApproved);
+    let result = process(&mut context, approve_instruction(multisig, 0, outsider.pubkey()), &[&outsider]).await;
+    assert_program_error(result, MultisigError::NotASigner);
+    
+    process(&mut context, approve_instruction(multisig, 0, signers[1].pubkey()), &[&signers[1]])
+        .await
+        .unwrap();
+    process(&mut context, execute_instruction(multisig, 0), &[]).await.unwrap();
+    let result = process(&mut context, approve_instruction(multisig, 0, signers[2].pubkey()), &[&signers[2]]).await;
+    assert_program_error(result, MultisigError::ProposalAlreadyExecuted);
+    
+    // The executed proposal authorizes exactly the proposed call, once
+    let result = process(&mut context, chain_change(&outsider, Some(0), true), &[&outsider]).await;
+    assert_program_error(result, MultisigError::ActionMismatch);
+    process(&mut context, chain_change(&outsider, Some(0), false), &[&outsider])
+        .await
+        .unwrap();
+    let chain: ChainConfig = fetch(&mut context, chain_config).await;
+    assert!(!chain.enabled);
+    let result = process(&mut context, chain_change(&outsider, Some(0), false), &[&outsider]).await;
+    assert_program_error(result, MultisigError::ProposalConsumed);
+    
+    // Proposals lapse after the multisig's proposal lifetime
+    let enable = PrivilegedAction::EnableSupportedChain { wormhole_chain_id: 2 };
+    process(
+        &mut context,
+        propose_instruction(multisig, 1, signers[0].pubkey(), enable),
+        &[&signers[0]],
+    )
+    .await
+    .unwrap();
+    warp_to_timestamp(&mut context, 1_000 + 3_601).await;
+    let result = process(&mut context, approve_instruction(multisig, 1, signers[1].pubkey()), &[&signers[1]]).await;
+    assert_program_error(result, MultisigError::ProposalExpired);
+    let result = process(&mut context, execute_instruction(multisig, 1), &[]).await;
+    assert_program_error(result, MultisigError::ProposalExpired);
+}
+
+#[tokio::test]
+async fn test_multisig_signer_rotation() {
+    let mut program_test = program_test();
+    let [a, b, d] = [
+        add_funded_signer(&mut program_test),
+        add_funded_signer(&mut program_test),
+        add_funded_signer(&mut program_test),
+    ];
+    let registry = add_registry(&mut program_test, &registry_state(Pubkey::new_unique()));
+    let chain_config = add_chain(&mut program_test, 2, AddressFormat::Evm, true);
+    let multisig = add_multisig(&mut program_test, vec![a.pubkey(), b.pubkey()], 2);
+    let mut context = program_test.start_with_context().await;
+    
+    let update = |proposal_id: u64, action: PrivilegedAction| {
+        instruction(
+            omniflow_rwa::accounts::UpdateMultisig {
+                multisig,
+                multisig_proposal: pda(&[b"multisig_proposal", &proposal_id.to_le_bytes()]),
+            },
+            omniflow_rwa::instruction::UpdateMultisig { action },
+        )
+    };
+    
+    // Rotate `a` out for `d`: add `d` first, while a proposal to disable a chain is
+    // executed but not yet applied
+    let add_d = PrivilegedAction::AddMultisigSigner { signer: d.pubkey() };
+    let remove_a = PrivilegedAction::RemoveMultisigSigner { signer: a.pubkey() };
+    let disable = PrivilegedAction::DisableSupportedChain { wormhole_chain_id: 2 };
+    process(&mut context, propose_instruction(multisig, 0, a.pubkey(), add_d.clone()), &[&a])
+        .await
+        .unwrap();
+    process(&mut context, propose_instruction(multisig, 1, a.pubkey(), disable), &[&a])
+        .await
+        .unwrap();
+    process(&mut context, approve_instruction(multisig, 0, b.pubkey()), &[&b]).await.unwrap();
+    process(&mut context, approve_instruction(multisig, 1, b.pubkey()), &[&b]).await.unwrap();
+    process(&mut context, execute_instruction(multisig, 1), &[]).await.unwrap();
+    
+    // The executed proposal only applies the change it was approved for
+    process(&mut context, execute_instruction(multisig, 0), &[]).await.unwrap();
+    let result = process(&mut context, update(0, remove_a.clone()), &[]).await;
+    assert_program_error(result, MultisigError::ActionMismatch);
+    process(&mut context, update(0, add_d), &[]).await.unwrap();
+    let multisig_data: Multisig = fetch(&mut context, multisig).await;
+    assert_eq!(multisig_data.signers, vec![a.pubkey(), b.pubkey(), d.pubkey()]);
+    assert_eq!(multisig_data.generation, 1);
+    
+    // Approvals are positional, so proposals from before the change are void, even
+    // once executed
+    let result = process(&mut context, approve_instruction(multisig, 1, b.pubkey()), &[&b]).await;
+    assert_program_error(result, MultisigError::ProposalStale);
+    let disable_chain = instruction(
+        omniflow_rwa::accounts::DisableSupportedChain {
+            chain_config,
+            registry,
+            admin_role: None,
+            multisig,
+            multisig_proposal: Some(pda(&[b"multisig_proposal", &1u64.to_le_bytes()])),
+            authority: a.pubkey(),
+        },
+        omniflow_rwa::instruction::DisableSupportedChain {},
+    );
+    let result = process(&mut context, disable_chain, &[&a]).await;
+    assert_program_error(result, MultisigError::ProposalStale);
+    
+    process(&mut context, propose_instruction(multisig, 2, d.pubkey(), remove_a.clone()), &[&d])
+        .await
+        .unwrap();
+    process(&mut context, approve_instruction(multisig, 2, b.pubkey()), &[&b]).await.unwrap();
+    process(&mut context, execute_instruction(multisig, 2), &[]).await.unwrap();
+    process(&mut context, update(2, remove_a), &[]).await.unwrap();
+    let multisig_data: Multisig = fetch(&mut context, multisig).await;
+    assert_eq!(multisig_data.signers, vec![b.pubkey(), d.pubkey()]);
+    
+    let result = process(
+        &mut context,
+        propose_instruction(multisig, 3, a.pubkey(), PrivilegedAction::ChangeMultisigThreshold { threshold: 1 }),
+        &[&a],
+    )
+    .await;
+    assert_program_error(result, MultisigError::NotASigner);
+    
+    // A threshold above the signer count is rejected when applied
+    let raise = PrivilegedAction::ChangeMultisigThreshold { threshold: 3 };
+    process(&mut context, propose_instruction(multisig, 3, b.pubkey(), raise.clone()), &[&b])
+        .await
+        .unwrap();
+    process(&mut context, approve_instruction(multisig, 3, d.pubkey()), &[&d]).await.unwrap();
+    process(&mut context, execute_instruction(multisig, 3), &[]).await.unwrap();
+    let result = process(&mut context, update(3, raise), &[]).await;
+    assert_program_error(result, MultisigError::InvalidThreshold);
+}
+
+#[tokio::test]
+async fn test_role_grant_revoke_and_issuer_check() {
+    let mut program_test = program_test();
+    let authority = add_funded_signer(&mut program_test);
+    let admin = add_funded_signer(&mut program_test);
+    let issuer = add_funded_signer(&mut program_test);
+    let outsider = add_funded_signer(&mut program_test);
+    add_registry(&mut program_test, &registry_state(authority.pubkey()));
+    let mut context = program_test.start_with_context().await;
+    
+    let membership = |role: Role, member: Pubkey| pda(&[b"role", &[role as u8], member.as_ref()]);
+    let admin_role = membership(Role::Admin, admin.pubkey());
+    let issuer_role = membership(Role::Issuer, issuer.pubkey());
+    let grant = |signer: &Keypair, admin_role: Option<Pubkey>, role: Role, member: Pubkey| {
+        instruction(
+            omniflow_rwa::accounts::GrantRole {
+                membership: membership(role, member),
+                registry: pda(&[b"registry"]),
+                admin_role,
+                multisig: pda(&[b"multisig"]),
+                multisig_proposal: None,
+                authority: signer.pubkey(),
+                system_program: solana_program::system_program::ID,
+            },
+            omniflow_rwa::instruction::GrantRole { role, member },
+        )
+    };
+    let revoke = |signer: &Keypair, admin_role: Option<Pubkey>, membership: Pubkey| {
+        instruction(
+            omniflow_rwa::accounts::RevokeRole {
+                membership,
+                registry: pda(&[b"registry"]),
+                admin_role,
+                multisig: pda(&[b"multisig"]),
+                multisig_proposal: None,
+                authority: signer.pubkey(),
+            },
+            omniflow_rwa::instruction::RevokeRole {},
+        )
+    };
+    
+    let result = process(&mut context, grant(&outsider, None, Role::Issuer, issuer.pubkey()), &[&outsider]).await;
+    assert_program_error(result, RoleError::MissingRole);
+    process(&mut context, grant(&authority, None, Role::Admin, admin.pubkey()), &[&authority])
+        .await
+        .unwrap();
+    
+    // Admins manage every role but their own
+    let result = process(
+        &mut context,
+        grant(&admin, Some(admin_role), Role::Admin, outsider.pubkey()),
+        &[&admin],
+    )
+    .await;
+    assert_program_error(result, RoleError::AdminRoleReserved);
+    process(
+        &mut context,
+        grant(&admin, Some(admin_role), Role::Issuer, issuer.pubkey()),
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    let issuer_membership: RoleMembership = fetch(&mut context, issuer_role).await;
+    assert_eq!(issuer_membership.role, Role::Issuer);
+    assert_eq!(issuer_membership.member, issuer.pubkey());
+    assert_eq!(issuer_membership.granted_by, admin.pubkey());
+    
+    // Registering an asset takes the Issuer role
+    let result = process(&mut context, register_rwa_asset_instruction(outsider.pubkey(), None, 1), &[&outsider]).await;
+    assert_program_error(result, RoleError::MissingRole);
+    process(
+        &mut context,
+        register_rwa_asset_instruction(issuer.pubkey(), Some(issuer_role), 1),
+        &[&issuer],
+    )
+    .await
+    .unwrap();
+    let asset_data: RWAAsset = fetch(&mut context, RWAAsset::address(1)).await;
+    assert_eq!(asset_data.owner, issuer.pubkey());
+    
+    let result = process(&mut context, revoke(&admin, Some(admin_role), admin_role), &[&admin]).await;
+    assert_program_error(result, RoleError::AdminRoleReserved);
+    process(&mut context, revoke(&admin, Some(admin_role), issuer_role), &[&admin])
+        .await
+        .unwrap();
+    assert!(context.banks_client.get_account(issuer_role).await.unwrap().is_none());
+    let result = process(&mut context, register_rwa_asset_instruction(issuer.pubkey(), None, 2), &[&issuer]).await;
+    assert_program_error(result, RoleError::MissingRole);
+    
+    process(&mut context, revoke(&authority, None, admin_role), &[&authority])
+        .await
+        .unwrap();
+    let result = process(
+        &mut context,
+        grant(&admin, None, Role::Issuer, issuer.pubkey()),
+        &[&admin],
+    )
+    .await;
+    assert_program_error(result, RoleError::MissingRole);
+    
+    // The authority only holds Admin implicitly and grants itself anything else
+    let authority_issuer_role = membership(Role::Issuer, authority.pubkey());
+    let result = process(&mut context, register_rwa_asset_instruction(authority.pubkey(), None, 2), &[&authority]).await;
+    assert_program_error(result, RoleError::MissingRole);
+    process(&mut context, grant(&authority, None, Role::Issuer, authority.pubkey()), &[&authority])
+        .await
+        .unwrap();
+    process(
+        &mut context,
+        register_rwa_asset_instruction(authority.pubkey(), Some(authority_issuer_role), 2),
+        &[&authority],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_asset_owner_transfer() {
+    let mut program_test = program_test();
+    let authority = add_funded_signer(&mut program_test);
+    let owner = add_funded_signer(&mut program_test);
+    let new_owner = add_funded_signer(&mut program_test);
+    let outsider = add_funded_signer(&mut program_test);
+    let registry = add_registry(&mut program_test, &registry_state(authority.pubkey()));
+    let institutional = RWAAsset {
+        kyc_level: KYCLevel::Institutional,
+        ..asset_state(1, owner.pubkey())
+    };
+    let asset = add_asset(&mut program_test, &institutional);
+    let asset_index_page = add_asset_index_page(&mut program_test, &[&institutional]);
+    let mut context = program_test.start_with_context().await;
+    
+    let manage = |signer: &Keypair| omniflow_rwa::accounts::ManageAssetOwner {
+        asset,
+        owner: signer.pubkey(),
+    };
+    let propose = |signer: &Keypair, new_owner: Pubkey| {
+        instruction(
+            manage(signer),
+            omniflow_rwa::instruction::ProposeAssetOwner { asset_id: 1, new_owner },
+        )
+    };
+    let cancel = |signer: &Keypair| {
+        instruction(
+            manage(signer),
+            omniflow_rwa::instruction::CancelAssetOwnerTransfer { asset_id: 1 },
+        )
+    };
+    let accept = |signer: &Keypair| {
+        instruction(
+            omniflow_rwa::accounts::AcceptAssetOwner {
+                asset,
+                asset_index_page,
+                new_owner: signer.pubkey(),
+            },
+            omniflow_rwa::instruction::AcceptAssetOwner { asset_id: 1 },
+        )
+    };
+    
+    let result = process(&mut context, propose(&outsider, outsider.pubkey()), &[&outsider]).await;
+    assert_program_error(result, anchor_lang::error::ErrorCode::ConstraintHasOne);
+    process(&mut context, propose(&owner, new_owner.pubkey()), &[&owner])
+        .await
+        .unwrap();
+    let result = process(&mut context, accept(&outsider), &[&outsider]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::Unauthorized);
+    
+    // Institutional assets change hands only with the registry authority's approval
+    let result = process(&mut context, accept(&new_owner), &[&new_owner]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::OwnerTransferNotApproved);
+    let approve = instruction(
+        omniflow_rwa::accounts::ApproveAssetOwner {
+            asset,
+            registry,
+            authority: authority.pubkey(),
+        },
+        omniflow_rwa::instruction::ApproveAssetOwner { asset_id: 1 },
+    );
+    process(&mut context, approve, &[&authority]).await.unwrap();
+    process(&mut context, accept(&new_owner), &[&new_owner]).await.unwrap();
+    
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert_eq!(asset_data.owner, new_owner.pubkey());
+    assert_eq!(asset_data.pending_owner, None);
+    assert!(!asset_data.owner_transfer_approved);
+    let page: AssetIndexPage = fetch(&mut context, asset_index_page).await;
+    assert_eq!(page.entries[0].owner, new_owner.pubkey());
+    
+    // The previous owner has no say any more
+    let result = process(&mut context, propose(&owner, owner.pubkey()), &[&owner]).await;
+    assert_program_error(result, anchor_lang::error::ErrorCode::ConstraintHasOne);
+    
+    process(&mut context, propose(&new_owner, outsider.pubkey()), &[&new_owner])
+        .await
+        .unwrap();
+    process(&mut context, cancel(&new_owner), &[&new_owner]).await.unwrap();
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert_eq!(asset_data.pending_owner, None);
+    let result = process(&mut context, cancel(&new_owner), &[&new_owner]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::NoPendingOwner);
+}
+
+#[tokio::test]
+async fn test_asset_index_pages() {
+    use omniflow_rwa::client::{assets_by_owner, iter_assets};
+    use std::collections::HashMap;
+    
+    let mut program_test = program_test();
+    let issuer = add_funded_signer(&mut program_test);
+    
+    // Page 0 is one entry short of full
+    let filler: Vec<RWAAsset> = (1..ASSET_INDEX_PAGE_SIZE)
+        .map(|asset_id| asset_state(asset_id, Pubkey::new_unique()))
+        .collect();
+    let registry = add_registry(
+        &mut program_test,
+        &Registry {
+            next_asset_id: ASSET_INDEX_PAGE_SIZE,
+            indexed_assets: ASSET_INDEX_PAGE_SIZE - 1,
+            ..registry_state(Pubkey::new_unique())
+        },
+    );
+    let first_page = add_asset_index_page(&mut program_test, &filler.iter().collect::<Vec<_>>());
+    let issuer_role = add_role(&mut program_test, Role::Issuer, issuer.pubkey());
+    let mut context = program_test.start_with_context().await;
+    
+    let last_id = ASSET_INDEX_PAGE_SIZE;
+    let register = register_rwa_asset_instruction(issuer.pubkey(), Some(issuer_role), last_id);
+    process(&mut context, register, &[&issuer])
+        .await
+        .unwrap();
+    
+    // The next registration opens page 1
+    let second_page = AssetIndexPage::address(1);
+    let register = instruction(
+        omniflow_rwa::accounts::RegisterRWAAsset {
+            asset: RWAAsset::address(last_id + 1),
+            registry,
+            issuer_role: Some(issuer_role),
+            asset_index_page: second_page,
+            asset_attributes: None,
+            owner: issuer.pubkey(),
+            system_program: solana_program::system_program::ID,
+        },
+        omniflow_rwa::instruction::RegisterRwaAsset {
+            asset_id: last_id + 1,
+            asset_type: AssetType::PreciousMetals,
+            metadata_uri: "https://example.com/metadata/gold".to_string(),
+            kyc_level: KYCLevel::None,
+            total_value: 1_000_000,
+            total_supply: 100_000,
+            chain_id: 1,
+            attributes: None,
+        },
+    );
+    process(&mut context, register, &[&issuer]).await.unwrap();
+    
+    let page: AssetIndexPage = fetch(&mut context, first_page).await;
+    assert_eq!(page.entries.len() as u64, ASSET_INDEX_PAGE_SIZE);
+    assert_eq!(page.entries[31].asset_id, last_id);
+    let page: AssetIndexPage = fetch(&mut context, second_page).await;
+    assert_eq!(page.page, 1);
+    assert_eq!(page.entries.len(), 1);
+    assert!(page.entries[0].asset_type == AssetType::PreciousMetals);
+    let asset_data: RWAAsset = fetch(&mut context, RWAAsset::address(last_id + 1)).await;
+    assert_eq!(asset_data.index_position, ASSET_INDEX_PAGE_SIZE);
+    
+    let lifecycle = |reactivate: bool| {
+        let accounts = omniflow_rwa::accounts::ManageAssetLifecycle {
+            asset: RWAAsset::address(last_id + 1),
+            registry,
+            asset_index_page: second_page,
+            authority: issuer.pubkey(),
+        };
+        if reactivate {
+            instruction(accounts, omniflow_rwa::instruction::ReactivateAsset { asset_id: last_id + 1 })
+        } else {
+            instruction(accounts, omniflow_rwa::instruction::DeactivateAsset { asset_id: last_id + 1 })
+        }
+    };
+    process(&mut context, lifecycle(false), &[&issuer]).await.unwrap();
+    let page: AssetIndexPage = fetch(&mut context, second_page).await;
+    assert_eq!(page.entries[0].status, AssetStatus::Inactive);
+    process(&mut context, lifecycle(true), &[&issuer]).await.unwrap();
+    let page: AssetIndexPage = fetch(&mut context, second_page).await;
+    assert_eq!(page.entries[0].status, AssetStatus::Active);
+    
+    // Clients walk the same pages
+    let mut accounts = HashMap::new();
+    for address in [first_page, second_page] {
+        let account = context.banks_client.get_account(address).await.unwrap().unwrap();
+        accounts.insert(address, account.data);
+    }
+    let fetcher = |address: &Pubkey| accounts.get(address).cloned();
+    assert_eq!(iter_assets(&fetcher).count() as u64, ASSET_INDEX_PAGE_SIZE + 1);
+    let owned: Vec<u64> = assets_by_owner(&fetcher, issuer.pubkey())
+        .map(|entry| entry.asset_id)
+        .collect();
+    assert_eq!(owned, vec![last_id, last_id + 1]);
+}
+
+#[tokio::test]
+async fn test_asset_attribute_instructions() {
+    let mut program_test = program_test();
+    let issuer = add_funded_signer(&mut program_test);
+    let outsider = add_funded_signer(&mut program_test);
+    let registry = add_registry(&mut program_test, &registry_state(Pubkey::new_unique()));
+    let issuer_role = add_role(&mut program_test, Role::Issuer, issuer.pubkey());
+    let mut context = program_test.start_with_context().await;
+    
+    let real_estate = |rental_yield_bps: u16| AssetAttributeData::RealEstate {
+        parcel_id: "APN-123-456".to_string(),
+        square_footage: 12_500,
+        rental_yield_bps,
+    };
+    let asset_attributes = pda(&[b"asset_attributes", &1u64.to_le_bytes()]);
+    let register = |asset_type: AssetType, attributes: Option<AssetAttributeData>, with_account: bool| {
+        instruction(
+            omniflow_rwa::accounts::RegisterRWAAsset {
+                asset: RWAAsset::address(1),
+                registry,
+                issuer_role: Some(issuer_role),
+                asset_index_page: pda(&[b"asset_index", &0u64.to_le_bytes()]),
+                asset_attributes: with_account.then_some(asset_attributes),
+                owner: issuer.pubkey(),
+                system_program: solana_program::system_program::ID,
+            },
+            omniflow_rwa::instruction::RegisterRwaAsset {
+                asset_id: 1,
+                asset_type,
+                metadata_uri: "https://example.com/metadata/1".to_string(),
+                kyc_level: KYCLevel::None,
+                total_value: 1_000_000,
+                total_supply: 100_000,
+                chain_id: 1,
+                attributes,
+            },
+        )
+    };
+    
+    // Attributes and their account are supplied together and must match the asset type
+    let without_account = register(AssetType::RealEstate, Some(real_estate(650)), false);
+    let result = process(&mut context, without_account, &[&issuer]).await;
+    assert_program_error(result, AttributeError::AttributesAccountMismatch);
+    let wrong_type = register(AssetType::CarbonCredits, Some(real_estate(650)), true);
+    let result = process(&mut context, wrong_type, &[&issuer]).await;
+    assert_program_error(result, AttributeError::AttributeTypeMismatch);
+    process(&mut context, register(AssetType::RealEstate, Some(real_estate(650)), true), &[&issuer])
+        .await
+        .unwrap();
+    let stored: AssetAttributes = fetch(&mut context, asset_attributes).await;
+    assert_eq!(stored.asset_id, 1);
+    assert_eq!(stored.attributes, real_estate(650));
+    
+    // Only the owner updates, and updates are validated like registration
+    let update = |signer: &Keypair, attributes: AssetAttributeData| {
+        instruction(
+            omniflow_rwa::accounts::UpdateAssetAttributes {
+                asset: RWAAsset::address(1),
+                registry,
+                asset_attributes,
+                owner: signer.pubkey(),
+                system_program: solana_program::system_program::ID,
+            },
+            omniflow_rwa::instruction::UpdateAssetAttributes { asset_id: 1, attributes },
+        )
+    };
+    let result = process(&mut context, update(&outsider, real_estate(700)), &[&outsider]).await;
+    assert_program_error(result, anchor_lang::error::ErrorCode::ConstraintHasOne);
+    let result = process(&mut context, update(&issuer, real_estate(10_001)), &[&issuer]).await;
+    assert_program_error(result, AttributeError::InvalidRentalYield);
+    process(&mut context, update(&issuer, real_estate(700)), &[&issuer])
+        .await
+        .unwrap();
+    let stored: AssetAttributes = fetch(&mut context, asset_attributes).await;
+    assert_eq!(stored.attributes, real_estate(700));
+}
+
+#[tokio::test]
+async fn test_redemption_lifecycle() {
+    let mut program_test = program_test();
+    let owner = add_funded_signer(&mut program_test);
+    let holder = add_funded_signer(&mut program_test);
+    let outsider = add_funded_signer(&mut program_test);
+    let registry = add_registry(&mut program_test, &registry_state(Pubkey::new_unique()));
+    let asset = add_asset(
+        &mut program_test,
+        &RWAAsset {
+            total_supply: 1_000,
+            circulating_supply: 1_000,
+            ..asset_state(1, owner.pubkey())
+        },
+    );
+    let mint = add_asset_mint(&mut program_test, 1, 1_000);
+    let holder_token_account = add_token_account(&mut program_test, mint, holder.pubkey(), 100);
+    let outsider_token_account = add_token_account(&mut program_test, mint, outsider.pubkey(), 0);
+    let mut context = program_test.start_with_context().await;
+    
+    let redemption_vault = pda(&[b"redemption_vault", &1u64.to_le_bytes()]);
+    let redemption_request = |request_id: u64| {
+        pda(&[b"redemption", &1u64.to_le_bytes(), &request_id.to_le_bytes()])
+    };
+    let request = |request_id: u64, amount: u64| {
+        instruction(
+            omniflow_rwa::accounts::RequestRedemption {
+                asset,
+                registry,
+                redemption_request: redemption_request(request_id),
+                mint,
+                redemption_vault,
+                holder_token_account,
+                holder: holder.pubkey(),
+                token_program: token::ID,
+                system_program: solana_program::system_program::ID,
+            },
+            omniflow_rwa::instruction::RequestRedemption { asset_id: 1, amount },
+        )
+    };
+    let result = process(&mut context, request(0, 0), &[&holder]).await;
+    assert_program_error(result, RedemptionError::InvalidAmount);
+    
+    // Requests escrow the tokens until the owner settles them
+    process(&mut context, request(0, 40), &[&holder]).await.unwrap();
+    process(&mut context, request(1, 30), &[&holder]).await.unwrap();
+    assert_eq!(token_balance(&mut context, holder_token_account).await, 30);
+    assert_eq!(token_balance(&mut context, redemption_vault).await, 70);
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert_eq!(asset_data.redemption_count, 2);
+    assert_eq!(asset_data.pending_redemptions, 2);
+    
+    let settlement = SettlementReference::FiatWire([7; 32]);
+    let fulfill = |signer: &Keypair, request_id: u64| {
+        instruction(
+            omniflow_rwa::accounts::FulfillRedemption {
+                asset,
+                redemption_request: redemption_request(request_id),
+                mint,
+                redemption_vault,
+                price_feed: None,
+                price_account: None,
+                owner: signer.pubkey(),
+                token_program: token::ID,
+            },
+            omniflow_rwa::instruction::FulfillRedemption { asset_id: 1, settlement },
+        )
+    };
+    let result = process(&mut context, fulfill(&outsider, 0), &[&outsider]).await;
+    assert_program_error(result, anchor_lang::error::ErrorCode::ConstraintHasOne);
+    
+    // Fulfilling burns the escrow and removes the redeemed share of the value
+    process(&mut context, fulfill(&owner, 0), &[&owner]).await.unwrap();
+    assert_eq!(token_balance(&mut context, redemption_vault).await, 30);
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert_eq!(asset_data.total_supply, 960);
+    assert_eq!(asset_data.circulating_supply, 960);
+    assert_eq!(asset_data.total_value, 960_000);
+    assert_eq!(asset_data.pending_redemptions, 1);
+    let fulfilled: RedemptionRequest = fetch(&mut context, redemption_request(0)).await;
+    assert_eq!(fulfilled.status, RedemptionStatus::Fulfilled);
+    assert_eq!(fulfilled.settlement, Some(settlement));
+    assert!(fulfilled.resolved_at.is_some());
+    
+    let reject = |request_id: u64, holder_token_account: Pubkey| {
+        instruction(
+            omniflow_rwa::accounts::RejectRedemption {
+                asset,
+                redemption_request: redemption_request(request_id),
+                redemption_vault,
+                holder_token_account,
+                owner: owner.pubkey(),
+                token_program: token::ID,
+            },
+            omniflow_rwa::instruction::RejectRedemption { asset_id: 1 },
+        )
+    };
+    let result = process(&mut context, reject(0, holder_token_account), &[&owner]).await;
+    assert_program_error(result, RedemptionError::RequestNotPending);
+    
+    // Rejected tokens only go back to the requesting holder
+    let result = process(&mut context, reject(1, outsider_token_account), &[&owner]).await;
+    assert_program_error(result, omniflow_rwa::ErrorCode::Unauthorized);
+    process(&mut context, reject(1, holder_token_account), &[&owner]).await.unwrap();
+    assert_eq!(token_balance(&mut context, holder_token_account).await, 60);
+    assert_eq!(token_balance(&mut context, redemption_vault).await, 0);
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert_eq!(asset_data.total_supply, 960);
+    assert_eq!(asset_data.pending_redemptions, 0);
+    let rejected: RedemptionRequest = fetch(&mut context, redemption_request(1)).await;
+    assert_eq!(rejected.status, RedemptionStatus::Rejected);
+    assert_eq!(rejected.settlement, None);
+}
+
+#[tokio::test]
+async fn test_distribution_lifecycle() {
+    let mut program_test = program_test();
+    let owner = add_funded_signer(&mut program_test);
+    let alice = add_funded_signer(&mut program_test);
+    let bob = add_funded_signer(&mut program_test);
+    let registry = add_registry(&mut program_test, &registry_state(Pubkey::new_unique()));
+    let asset = add_asset(&mut program_test, &asset_state(1, owner.pubkey()));
+    let alice_leaf = merkle::balance_leaf(&alice.pubkey(), 500);
+    let bob_leaf = merkle::balance_leaf(&bob.pubkey(), 250);
+    let (snapshot, bump) = Pubkey::find_program_address(
+        &[b"snapshot", &1u64.to_le_bytes(), &1u64.to_le_bytes()],
+        &omniflow_rwa::ID,
+    );
+    add_program_account(
+        &mut program_test,
+        snapshot,
+        &BalanceSnapshot {
+            asset_id: 1,
+            snapshot_id: 1,
+            supply: 750,
+            taken_at: 0,
+            balances_root: Some(merkle::hash_pair(&alice_leaf, &bob_leaf)),
+            holder_count: 2,
+            committed_by: Some(owner.pubkey()),
+            bump,
+        },
+        8 + BalanceSnapshot::INIT_SPACE,
+    );
+    let payout_mint = Pubkey::new_unique();
+    add_mint(&mut program_test, payout_mint, Pubkey::new_unique(), 7_500);
+    let owner_payout = add_token_account(&mut program_test, payout_mint, owner.pubkey(), 7_500);
+    let alice_payout = add_token_account(&mut program_test, payout_mint, alice.pubkey(), 0);
+    let bob_payout = add_token_account(&mut program_test, payout_mint, bob.pubkey(), 0);
+    let mut context = program_test.start_with_context().await;
+    let now = context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp;
+    
+    let distribution = pda(&[b"distribution", &1u64.to_le_bytes(), &0u64.to_le_bytes()]);
+    let distribution_vault = pda(&[b"distribution_vault", distribution.as_ref()]);
+    let create = |amount: u64, claim_deadline: i64| {
+        instruction(
+            omniflow_rwa::accounts::CreateDistribution {
+                asset,
+                registry,
+                distribution,
+                snapshot,
+                payout_mint,
+                distribution_vault,
+                owner_payout_account: owner_payout,
+                owner: owner.pubkey(),
+                token_program: token::ID,
+                system_program: solana_program::system_program::ID,
+            },
+            omniflow_rwa::instruction::CreateDistribution {
+                asset_id: 1,
+                amount,
+                claim_deadline,
+            },
+        )
+    };
+    let result = process(&mut context, create(0, now + 1_000), &[&owner]).await;
+    assert_program_error(result, DistributionError::InvalidAmount);
+    let result = process(&mut context, create(7_500, now), &[&owner]).await;
+    assert_program_error(result, DistributionError::InvalidDeadline);
+    process(&mut context, create(7_500, now + 1_000), &[&owner]).await.unwrap();
+    assert_eq!(token_balance(&mut context, owner_payout).await, 0);
+    assert_eq!(token_balance(&mut context, distribution_vault).await, 7_500);
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert_eq!(asset_data.distribution_count, 1);
+    assert_eq!(asset_data.open_distributions, 1);
+    
+    let claim = |holder: &Keypair, holder_payout_account: Pubkey, balance: u64, proof: [u8; 32]| {
+        instruction(
+            omniflow_rwa::accounts::ClaimDistribution {
+                distribution,
+                claim: pda(&[b"distribution_claim", distribution.as_ref(), holder.pubkey().as_ref()]),
+                distribution_vault,
+                holder_payout_account,
+                holder: holder.pubkey(),
+                token_program: token::ID,
+                system_program: solana_program::system_program::ID,
+            },
+            omniflow_rwa::instruction::ClaimDistribution {
+                balance,
+                proof: vec![proof],
+            },
+        )
+    };
+    let result = process(&mut context, claim(&alice, alice_payout, 600, bob_leaf), &[&alice]).await;
+    assert_program_error(result, DistributionError::InvalidProof);
+    process(&mut context, claim(&alice, alice_payout, 500, bob_leaf), &[&alice])
+        .await
+        .unwrap();
+    assert_eq!(token_balance(&mut context, alice_payout).await, 5_000);
+    
+    let sweep = instruction(
+        omniflow_rwa::accounts::SweepDistribution {
+            asset,
+            distribution,
+            distribution_vault,
+            owner_payout_account: owner_payout,
+            owner: owner.pubkey(),
+            token_program: token::ID,
+        },
+        omniflow_rwa::instruction::SweepDistribution {},
+    );
+    let result = process(&mut context, sweep.clone(), &[&owner]).await;
+    assert_program_error(result, DistributionError::ClaimPeriodActive);
+    
+    // After the deadline claims close and the owner takes back what was left
+    warp_to_timestamp(&mut context, now + 1_000).await;
+    let result = process(&mut context, claim(&bob, bob_payout, 250, alice_leaf), &[&bob]).await;
+    assert_program_error(result, DistributionError::ClaimPeriodEnded);
+    process(&mut context, sweep.clone(), &[&owner]).await.unwrap();
+    assert_eq!(token_balance(&mut context, owner_payout).await, 2_500);
+    assert_eq!(token_balance(&mut context, distribution_vault).await, 0);
+    let distribution_data: Distribution = fetch(&mut context, distribution).await;
+    assert!(distribution_data.swept);
+    assert_eq!(distribution_data.claimed_amount, 5_000);
+    assert_eq!(distribution_data.claimed_balance, 500);
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert_eq!(asset_data.open_distributions, 0);
+    
+    let result = process(&mut context, sweep, &[&owner]).await;
+    assert_program_error(result, DistributionError::AlreadySwept);
+}
+
+#[tokio::test]
+async fn test_reserve_attestation_gates_minting() {
+    let mut program_test = program_test();
+    let owner = add_funded_signer(&mut program_test);
+    let custodian = add_funded_signer(&mut program_test);
+    let relayer = add_funded_signer(&mut program_test);
+    let admin = add_funded_signer(&mut program_test);
+    let replacement = add_funded_signer(&mut program_test);
+    let registry = add_registry(&mut program_test, &registry_state(admin.pubkey()));
+    let multisig = add_multisig(&mut program_test, vec![admin.pubkey()], 1);
+    let relayer_role = add_role(&mut program_test, Role::BridgeRelayer, relayer.pubkey());
+    let asset = add_asset(
+        &mut program_test,
+        &RWAAsset {
+            asset_type: AssetType::PreciousMetals,
+            ..asset_state(1, owner.pubkey())
+        },
+    );
+    let mint = add_asset_mint(&mut program_test, 1, 0);
+    let investor = Pubkey::new_unique();
+    let mut context = program_test.start_with_context().await;
+    let now = context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp;
+    
+    let reserve = ReserveAttestation::address(1);
+    let configure = |max_staleness: i64, tokens_per_unit: u64| {
+        instruction(
+            omniflow_rwa::accounts::ConfigureReserve {
+                asset,
+                reserve,
+                owner: owner.pubkey(),
+                system_program: solana_program::system_program::ID,
+            },
+            omniflow_rwa::instruction::ConfigureReserve {
+                asset_id: 1,
+                max_staleness,
+                tokens_per_unit,
+            },
+        )
+    };
+    let result = process(&mut context, configure(0, 100), &[&owner]).await;
+    assert_program_error(result, ReserveError::InvalidReservePolicy);
+    let result = process(&mut context, configure(3_600, 0), &[&owner]).await;
+    assert_program_error(result, ReserveError::InvalidReservePolicy);
+    process(&mut context, configure(3_600, 100), &[&owner]).await.unwrap();
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert!(asset_data.reserve_required);
+    
+    // The issuer cannot appoint its own custodian
+    let appoint = |signer: &Keypair, proposal_id: Option<u64>, custodian: Pubkey| {
+        instruction(
+            omniflow_rwa::accounts::AppointReserveCustodian {
+                reserve,
+                registry,
+                admin_role: None,
+                multisig,
+                multisig_proposal: proposal_id
+                    .map(|proposal_id| pda(&[b"multisig_proposal", &proposal_id.to_le_bytes()])),
+                authority: signer.pubkey(),
+            },
+            omniflow_rwa::instruction::AppointReserveCustodian { asset_id: 1, custodian },
+        )
+    };
+    let result = process(&mut context, appoint(&owner, None, owner.pubkey()), &[&owner]).await;
+    assert_program_error(result, RoleError::MissingRole);
+    process(&mut context, appoint(&admin, None, custodian.pubkey()), &[&admin]).await.unwrap();
+    let reserve_data: ReserveAttestation = fetch(&mut context, reserve).await;
+    assert_eq!(reserve_data.custodian, custodian.pubkey());
+    
+    let mint_tokens = |reserve: Option<Pubkey>, amount: u64| {
+        instruction(
+            omniflow_rwa::accounts::MintRWATokens {
+                asset,
+                registry: pda(&[b"registry"]),
+                mint,
+                token_account: spl_associated_token_account::get_associated_token_address(&investor, &mint),
+                recipient: investor,
+                recipient_passport: None,
+                valuation: None,
+                reserve,
+                owner: owner.pubkey(),
+                token_program: token::ID,
+                associated_token_program: anchor_spl::associated_token::ID,
+                system_program: solana_program::system_program::id(),
+            },
+            omniflow_rwa::instruction::MintRwaTokens { asset_id: 1, amount },
+        )
+    };
+    // Nothing mints until the custodian has attested
+    let result = process(&mut context, mint_tokens(None, 100), &[&owner]).await;
+    assert_program_error(result, ReserveError::ReserveMissing);
+    let result = process(&mut context, mint_tokens(Some(reserve), 100), &[&owner]).await;
+    assert_program_error(result, ReserveError::AttestationStale);
+    
+    let attest = |signer: &Keypair, quantity: u64| {
+        instruction(
+            omniflow_rwa::accounts::PostReserveAttestation {
+                asset,
+                reserve,
+                price_feed: None,
+                price_account: None,
+                custodian: signer.pubkey(),
+            },
+            omniflow_rwa::instruction::PostReserveAttestation {
+                quantity,
+                audit_report_hash: [9; 32],
+                auditor: Pubkey::new_unique(),
+            },
+        )
+    };
+    let result = process(&mut context, attest(&owner, 5), &[&owner]).await;
+    assert_program_error(result, anchor_lang::error::ErrorCode::ConstraintHasOne);
+    process(&mut context, attest(&custodian, 5), &[&custodian]).await.unwrap();
+    let reserve_data: ReserveAttestation = fetch(&mut context, reserve).await;
+    assert_eq!(reserve_data.backed_supply(), 500);
+    assert_eq!(reserve_data.attestation_count, 1);
+    
+    // 5 units at 100 tokens each back at most 500 tokens
+    let result = process(&mut context, mint_tokens(Some(reserve), 600), &[&owner]).await;
+    assert_program_error(result, ReserveError::InsufficientReserves);
+    process(&mut context, mint_tokens(Some(reserve), 500), &[&owner]).await.unwrap();
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert_eq!(asset_data.circulating_supply, 500);
+    
+    // Inbound bridge transfers mint against the same reserves
+    let complete_transfer = |reserve: Option<Pubkey>, amount: u64| {
+        instruction(
+            omniflow_rwa::accounts::CompleteCrossChainTransfer {
+                asset,
+                registry: pda(&[b"registry"]),
+                mint,
+                to_token_account: spl_associated_token_account::get_associated_token_address(&investor, &mint),
+                recipient: investor,
+                relayer_role: Some(relayer_role),
+                valuation: None,
+                reserve,
+                payer: relayer.pubkey(),
+                token_program: token::ID,
+                associated_token_program: anchor_spl::associated_token::ID,
+                system_program: solana_program::system_program::id(),
+            },
+            omniflow_rwa::instruction::CompleteCrossChainTransfer {
+                asset_id: 1,
+                amount,
+                source_chain: 2,
+                transfer_hash: [7; 32],
+            },
+        )
+    };
+    let result = process(&mut context, complete_transfer(None, 100), &[&relayer]).await;
+    assert_program_error(result, ReserveError::ReserveMissing);
+    let result = process(&mut context, complete_transfer(Some(reserve), 100), &[&relayer]).await;
+    assert_program_error(result, ReserveError::InsufficientReserves);
+    
+    // The token ratio is fixed once attested
+    let result = process(&mut context, configure(3_600, 200), &[&owner]).await;
+    assert_program_error(result, ReserveError::InvalidReservePolicy);
+    
+    process(&mut context, attest(&custodian, 10), &[&custodian]).await.unwrap();
+    process(&mut context, complete_transfer(Some(reserve), 100), &[&relayer]).await.unwrap();
+    let asset_data: RWAAsset = fetch(&mut context, asset).await;
+    assert_eq!(asset_data.circulating_supply, 600);
+    let investor_tokens = spl_associated_token_account::get_associated_token_address(&investor, &mint);
+    assert_eq!(token_balance(&mut context, investor_tokens).await, 600);
+    warp_to_timestamp(&mut context, now + 3_601).await;
+    let result = process(&mut context, mint_tokens(Some(reserve), 100), &[&owner]).await;
+    assert_program_error(result, ReserveError::AttestationStale);
+    
+    // An executed proposal re-points the custodian and is bound to its action
+    let action = PrivilegedAction::AppointReserveCustodian {
+        asset_id: 1,
+        custodian: replacement.pubkey(),
+    };
+    process(&mut context, propose_instruction(multisig, 0, admin.pubkey(), action), &[&admin])
+        .await
+        .unwrap();
+    process(&mut context, approve_instruction(multisig, 0, admin.pubkey()), &[&admin]).await.unwrap();
+    process(&mut context, execute_instruction(multisig, 0), &[]).await.unwrap();
+    let result = process(&mut context, appoint(&owner, Some(0), owner.pubkey()), &[&owner]).await;
+    assert_program_error(result, MultisigError::ActionMismatch);
+    process(&mut context, appoint(&owner, Some(0), replacement.pubkey()), &[&owner]).await.unwrap();
+    let reserve_data: ReserveAttestation = fetch(&mut context, reserve).await;
+    assert_eq!(reserve_data.custodian, replacement.pubkey());
+    let result = process(&mut context, attest(&custodian, 10), &[&custodian]).await;
+    assert_program_error(result, anchor_lang::error::ErrorCode::ConstraintHasOne);
+}
+
+#[tokio::test]
+async fn test_publish_asset_document_versions() {
+    use sha2::{Digest, Sha256};
+    
+    let mut program_test = program_test();
+    let owner = add_funded_signer(&mut program_test);
+    let outsider = add_funded_signer(&mut program_test);
+    let registry = add_registry(&mut program_test, &registry_state(Pubkey::new_unique()));
+    let asset = add_asset(&mut program_test, &asset_state(1, owner.pubkey()));
+    let mut context = program_test.start_with_context().await;
+    
+    let memorandum_v1 = b"Offering memorandum, January edition".as_slice();
+    let memorandum_v2 = b"Offering memorandum, amended in March".as_slice();
+    let publish = |signer: &Keypair,
+                   document_type: DocumentType,
+                   version: u32,
+                   previous_version: Option<u32>,
+                   content_hash: [u8; 32],
+                   effective_date: i64| {
+        instruction(
+            omniflow_rwa::accounts::PublishAssetDocument {
+                asset,
+                registry,
+                document: AssetDocument::address(1, document_type, version),
+                previous_document: previous_version
+                    .map(|previous| AssetDocument::address(1, document_type, previous)),
+                owner: signer.pubkey(),
+                system_program: solana_program::system_program::ID,
+            },
+            omniflow_rwa::instruction::PublishAssetDocument {
+                asset_id: 1,
+                document_type,
+                version,
+                content_hash,
+                uri: format!("ipfs://memorandum/v{version}"),
+                effective_date,
+            },
+        )
+    };
+    let memorandum = DocumentType::OfferingMemorandum;
+    let v1_hash: [u8; 32] = Sha256::digest(memorandum_v1).into();
+    let v2_hash: [u8; 32] = Sha256::digest(memorandum_v2).into();
+    
+    let result = process(&mut context, publish(&outsider, memorandum, 1, None, v1_hash, 100), &[&outsider]).await;
+    assert_program_error(result, anchor_lang::error::ErrorCode::ConstraintHasOne);
+    let result = process(&mut context, publish(&owner, memorandum, 1, None, [0; 32], 100), &[&owner]).await;
+    assert_program_error(result, DocumentError::InvalidContentHash);
+    // Later versions must name the version they supersede
+    let result = process(&mut context, publish(&owner, memorandum, 2, None, v2_hash, 200), &[&owner]).await;
+    assert_program_error(result, DocumentError::InvalidVersion);
+    
+    process(&mut context, publish(&owner, memorandum, 1, None, v1_hash, 100), &[&owner])
+        .await
+        .unwrap();
+    let first: AssetDocument = fetch(&mut context, AssetDocument::address(1, memorandum, 1)).await;
+    assert_eq!(first.previous_hash, None);
+    assert_eq!(first.published_by, owner.pubkey());
+    assert!(first.verify_content(memorandum_v1));
+    
+    let result = process(&mut context, publish(&owner, memorandum, 2, Some(1), v2_hash, 99), &[&owner]).await;
+    assert_program_error(result, DocumentError::InvalidEffectiveDate);
+    process(&mut context, publish(&owner, memorandum, 2, Some(1), v2_hash, 200), &[&owner])
+        .await
+        .unwrap();
+    let second: AssetDocument = fetch(&mut context, AssetDocument::address(1, memorandum, 2)).await;
+    assert_eq!(second.version, 2);
+    assert_eq!(second.previous_hash, Some(v1_hash));
+    assert!(second.verify_content(memorandum_v2));
+    
+    // A version cannot skip past the one it supersedes
+    let result = process(&mut context, publish(&owner, memorandum, 3, Some(1), v2_hash, 300), &[&owner]).await;
+    assert_program_error(result, anchor_lang::error::ErrorCode::ConstraintSeeds);
+    
+    // Each document type has its own version chain
+    process(&mut context, publish(&owner, DocumentType::TitleDeed, 1, None, v1_hash, 100), &[&owner])
+        .await
+        .unwrap();
+    let deed: AssetDocument = fetch(&mut context, AssetDocument::address(1, DocumentType::TitleDeed, 1)).await;
+    assert_eq!(deed.document_type, DocumentType::TitleDeed);
+    assert_eq!(deed.previous_hash, None);
+}
+
+#[tokio::test]
+async fn test_linked_addresses_need_verification() {
+    let mut program_test = program_test();
+    let holder = add_funded_signer(&mut program_test);
+    let officer = add_funded_signer(&mut program_test);
+    let (registry, bump) = Pubkey::find_program_address(&[b"identity_registry"], &omniflow_rwa::ID);
+    add_program_account(
+        &mut program_test,
+        registry,
+        &IdentityRegistry {
+            authority: Pubkey::new_unique(),
+            pending_authority: None,
+            total_passports: 1,
+            authorized_issuers: Vec::new(),
+            cross_chain_bridge: None,
+            paused: false,
+            bump,
+        },
+        IdentityRegistry::MAX_SIZE,
+    );
+    let passport = add_passport(&mut program_test, holder.pubkey(), identity::KYCLevel::Basic);
+    let chain_config = add_chain(&mut program_test, 2, AddressFormat::Evm, true);
+    let compliance_role = add_role(&mut program_test, Role::ComplianceOfficer, officer.pubkey());
+    let mut context = program_test.start_with_context().await;
+    
+    let address = "0x00000000000000000000000000000000000000ab".to_string();
+    let mut recipient = [0u8; 32];
+    recipient[31] = 0xab;
+    let link = instruction(
+        omniflow_rwa::accounts::LinkCrossChainAddress {
+            identity_passport: passport,
+            chain_config,
+            owner: holder.pubkey(),
+        },
+        omniflow_rwa::instruction::LinkCrossChainAddress {
+            chain_id: 2,
+            address: address.clone(),
+            signature: Vec::new(),
+        },
+    );
+    process(&mut context, link, &[&holder]).await.unwrap();
+    
+    // The owner's claim alone does not make the address a verified recipient
+    let passport_data: IdentityPassport = fetch(&mut context, passport).await;
+    assert!(!passport_data.cross_chain_addresses[0].verified);
+    assert!(!passport_data.has_verified_address(2, AddressFormat::Evm, &recipient));
+    
+    let verify = |signer: &Keypair, compliance_role: Option<Pubkey>, address: &str| {
+        instruction(
+            omniflow_rwa::accounts::VerifyCrossChainAddress {
+                identity_passport: passport,
+                registry,
+                compliance_role,
+                verifier: signer.pubkey(),
+            },
+            omniflow_rwa::instruction::VerifyCrossChainAddress {
+                chain_id: 2,
+                address: address.to_string(),
+            },
+        )
+    };
+    let result = process(&mut context, verify(&holder, None, &address), &[&holder]).await;
+    assert_program_error(result, IdentityError::UnauthorizedIssuer);
+    let unlinked = "0x00000000000000000000000000000000000000cd";
+    let result = process(&mut context, verify(&officer, Some(compliance_role), unlinked), &[&officer]).await;
+    assert_program_error(result, IdentityError::AddressNotLinked);
+    process(&mut context, verify(&officer, Some(compliance_role), &address), &[&officer])
+        .await
+        .unwrap();
+    let passport_data: IdentityPassport = fetch(&mut context, passport).await;
+    assert!(passport_data.has_verified_address(2, AddressFormat::Evm, &recipient));
+}
+
+#[tokio::test]
+async fn test_multisig_gates_registry_configuration() {
+    let mut program_test = program_test();
+    let authority = add_funded_signer(&mut program_test);
+    let new_admin = Pubkey::new_unique();
+    let guardian = Pubkey::new_unique();
+    let [a, b] = [add_funded_signer(&mut program_test), add_funded_signer(&mut program_test)];
+    let registry = add_registry(&mut program_test, &registry_state(authority.pubkey()));
+    let multisig = add_multisig(&mut program_test, vec![a.pubkey(), b.pubkey()], 2);
+    let mut context = program_test.start_with_context().await;
+    
+    let proposal = |proposal_id: Option<u64>| proposal_id.map(|id| pda(&[b"multisig_proposal", &id.to_le_bytes()]));
+    let set_guardian = |proposal_id: Option<u64>| {
+        instruction(
+            omniflow_rwa::accounts::SetGuardian {
+                registry,
+                multisig,
+                multisig_proposal: proposal(proposal_id),
+                authority: authority.pubkey(),
+            },
+            omniflow_rwa::instruction::SetGuardian { guardian: Some(guardian) },
+        )
+    };
+    let grant_admin = |proposal_id: Option<u64>| {
+        instruction(
+            omniflow_rwa::accounts::GrantRole {
+                membership: pda(&[b"role", &[Role::Admin as u8], new_admin.as_ref()]),
+                registry,
+                admin_role: None,
+                multisig,
+                multisig_proposal: proposal(proposal_id),
+                authority: authority.pubkey(),
+                system_program: solana_program::system_program::ID,
+            },
+            omniflow_rwa::instruction::GrantRole {
+                role: Role::Admin,
+                member: new_admin,
+            },
+        )
+    };
+    let allocate_ids = instruction(
+        omniflow_rwa::accounts::SetAssetIdAllocation {
+            registry,
+            multisig,
+            multisig_proposal: None,
+            authority: authority.pubkey(),
+        },
+        omniflow_rwa::instruction::SetAssetIdAllocation {
+            allocate_asset_ids: true,
+        },
+    );
+    let bridge_config = instruction(
+        omniflow_rwa::accounts::UpdateBridgeConfig {
+            registry,
+            timelock_config: pda(&[b"timelock"]),
+            multisig,
+            multisig_proposal: None,
+            authority: authority.pubkey(),
+        },
+        omniflow_rwa::instruction::UpdateBridgeConfig {
+            wormhole_bridge: EndpointUpdate::Set(Pubkey::new_unique()),
+            layerzero_endpoint: EndpointUpdate::Keep,
+        },
+    );
+    
+    // Once the multisig exists the authority's key alone configures nothing
+    let result = process(&mut context, set_guardian(None), &[&authority]).await;
+    assert_program_error(result, MultisigError::MultisigRequired);
+    let result = process(&mut context, grant_admin(None), &[&authority]).await;
+    assert_program_error(result, MultisigError::MultisigRequired);
+    let result = process(&mut context, allocate_ids, &[&authority]).await;
+    assert_program_error(result, MultisigError::MultisigRequired);
+    let result = process(&mut context, bridge_config, &[&authority]).await;
+    assert_program_error(result, MultisigError::MultisigRequired);
+    
+    let actions = [
+        PrivilegedAction::SetGuardian { guardian: Some(guardian) },
+        PrivilegedAction::GrantAdmin { member: new_admin },
+    ];
+    for (proposal_id, action) in actions.into_iter().enumerate() {
+        let proposal_id = proposal_id as u64;
+        process(&mut context, propose_instruction(multisig, proposal_id, a.pubkey(), action), &[&a])
+            .await
+            .unwrap();
+        process(&mut context, approve_instruction(multisig, proposal_id, b.pubkey()), &[&b])
+            .await
+            .unwrap();
+        process(&mut context, execute_instruction(multisig, proposal_id), &[]).await.unwrap();
+    }
+    
+    // Each executed proposal authorizes only its own change
+    let result = process(&mut context, grant_admin(Some(0)), &[&authority]).await;
+    assert_program_error(result, MultisigError::ActionMismatch);
+    process(&mut context, set_guardian(Some(0)), &[&authority]).await.unwrap();
+    process(&mut context, grant_admin(Some(1)), &[&authority]).await.unwrap();
+    let registry_data: Registry = fetch(&mut context, registry).await;
+    assert_eq!(registry_data.guardian, Some(guardian));
+    let membership: RoleMembership =
+        fetch(&mut context, pda(&[b"role", &[Role::Admin as u8], new_admin.as_ref()])).await;
+    assert_eq!(membership.member, new_admin);
+}